@@ -2,4 +2,4 @@
 
 cd "$(dirname "$0")"/.. || exit
 
-cargo test --all --features mock_net
+cargo test --all --features mock_net,scram,serde
@@ -0,0 +1,601 @@
+// Copyright 2020 - 2021 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg_attr(not(feature = "async_io"), allow(dead_code))]
+
+use pg_wire::prelude::*;
+use std::collections::HashMap;
+
+fn main() {
+    #[cfg(not(feature = "async_io"))]
+    println!("execute `cargo run --example extended_query_server --features async_io` to run this example");
+    #[cfg(feature = "async_io")]
+    smol::block_on(async {
+        use async_mutex::Mutex as AsyncMutex;
+        use futures_lite::{AsyncReadExt, AsyncWriteExt};
+        use smol::Async;
+        use std::{net::TcpListener, sync::Arc};
+
+        let listener = Async::<TcpListener>::bind(([127, 0, 0, 1], 5432)).expect("OK");
+        println!("server started");
+
+        let config = ProtocolConfiguration::not_secure();
+        let conn_supervisor = ConnSupervisor::new(0, 10);
+        let pg_wire_listener = PgWireListener::new(listener, config, conn_supervisor);
+
+        loop {
+            match pg_wire_listener.accept().await {
+                Err(io_error) => eprintln!("IO error {:?}", io_error),
+                Ok(Err(protocol_error)) => eprintln!("protocol error {}", protocol_error),
+                Ok(Ok(ClientRequest::Connect((mut channel, props, conn_supervisor, address, settings)))) => {
+                    channel
+                        .write_all(BackendMessage::AuthenticationCleartextPassword.as_vec().as_slice())
+                        .await
+                        .expect("to ask for password in clear text format");
+                    channel.flush().await.expect("to flush the buffer");
+
+                    //TODO: use message decoder for Auth messages
+                    let mut tag_buffer = [0u8; 1];
+                    let _tag = channel.read_exact(&mut tag_buffer).await.map(|_| tag_buffer[0]);
+                    let mut len_buffer = [0u8; 4];
+                    let len = channel
+                        .read_exact(&mut len_buffer)
+                        .await
+                        .map(|_| u32::from_be_bytes(len_buffer) as usize)
+                        .expect("to read message length");
+                    let len = len - 4;
+                    let mut message_buffer = Vec::with_capacity(len);
+                    message_buffer.resize(len, b'0');
+                    let _message = channel
+                        .read_exact(&mut message_buffer)
+                        .await
+                        .map(|_| message_buffer)
+                        .expect("to read message body");
+
+                    // we are ok with any password that user sent
+                    pg_wire_listener
+                        .complete_authentication(&mut channel)
+                        .await
+                        .expect("Auth Ok");
+
+                    let (conn_id, secret_key) = match conn_supervisor.alloc() {
+                        Ok((c, s)) => (c, s),
+                        Err(()) => {
+                            eprintln!("Cannot allocate connection and its secret key");
+                            return;
+                        }
+                    };
+
+                    // sending connection id and its secret key if client wanted to cancel query
+                    channel
+                        .write_all(BackendMessage::BackendKeyData(conn_id, secret_key).as_vec().as_slice())
+                        .await
+                        .expect("to send connection id and secret key");
+
+                    channel
+                        .write_all(BackendMessage::ReadyForQuery.as_vec().as_slice())
+                        .await
+                        .expect("to notify that we ready to handle query");
+
+                    let channel = Arc::new(AsyncMutex::new(channel));
+                    let mut connection =
+                        Connection::new(conn_id, secret_key, props, address, channel, conn_supervisor, settings);
+                    println!("client connected from {:?}", connection.address());
+                    let sender = connection.sender();
+                    let mut session = Session::default();
+                    loop {
+                        match connection.receive().await {
+                            Err(e) => {
+                                eprintln!("Err(e) UNEXPECTED ERROR: {:?}", e);
+                                return;
+                            }
+                            Ok(Err(error)) => {
+                                eprintln!("malformed client message: {}", error);
+                                sender.send(BackendMessage::from(&error)).expect("Ok");
+                                return;
+                            }
+                            Ok(Ok(command)) => {
+                                if !session.handle(command, &sender) {
+                                    println!("close connection");
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                }
+                Ok(Ok(ClientRequest::QueryCancellation(_))) => {
+                    println!("Query cancellation is not supported")
+                }
+            }
+        }
+    })
+}
+
+/// SQLSTATE codes of errors that the example reports
+const INVALID_SQL_STATEMENT_NAME: &str = "26000";
+const INVALID_CURSOR_NAME: &str = "34000";
+const INVALID_TEXT_REPRESENTATION: &str = "22P02";
+const INVALID_BINARY_REPRESENTATION: &str = "22P03";
+const FEATURE_NOT_SUPPORTED: &str = "0A000";
+const PROTOCOL_VIOLATION: &str = "08P01";
+const INTERNAL_ERROR: &str = "XX000";
+
+/// Columns of `SELECT $1::int` query
+const COLUMNS: &[(&str, PgType)] = &[("int4", PgType::Integer)];
+
+/// Handles the extended query flow for `SELECT $1::int` query
+///
+/// A client sends `Parse`, `Bind`, `Describe`, `Execute` and `Sync` messages
+/// and the server responds with `ParseComplete`, `BindComplete`,
+/// `RowDescription`, `DataRow`, `CommandComplete` and `ReadyForQuery`.
+/// After an error the server discards messages until `Sync`
+#[derive(Default)]
+struct Session {
+    statements: HashMap<String, Vec<Option<PgType>>>,
+    portals: HashMap<String, (Value, ResultFormats)>,
+    failed: bool,
+}
+
+impl Session {
+    /// returns `false` when client terminates the connection
+    fn handle(&mut self, command: CommandMessage, sender: &dyn Sender) -> bool {
+        match command {
+            CommandMessage::Sync => {
+                self.failed = false;
+                // unnamed portal is destroyed at the end of the transaction
+                self.portals.remove("");
+                sender.send(BackendMessage::ReadyForQuery).expect("Ok");
+            }
+            CommandMessage::Terminate => return false,
+            // messages are discarded until `Sync` once one of them failed
+            _ if self.failed => {}
+            command => {
+                if let Err((code, message)) = self.handle_command(command, sender) {
+                    self.failed = true;
+                    sender.send(Self::error(code, message)).expect("Ok");
+                }
+            }
+        }
+        true
+    }
+
+    /// returns SQLSTATE code and message of the error if the message failed
+    fn handle_command(&mut self, command: CommandMessage, sender: &dyn Sender) -> Result<(), (&'static str, String)> {
+        match command {
+            CommandMessage::Parse {
+                statement_name,
+                sql,
+                param_types,
+            } => {
+                println!("parse '{}' as {:?} statement", sql, statement_name);
+                self.statements.insert(statement_name, param_types);
+                sender.send(BackendMessage::ParseComplete).expect("Ok");
+            }
+            CommandMessage::DescribeStatement { name } => {
+                let param_types = self
+                    .statements
+                    .get(&name)
+                    .ok_or_else(|| Self::statement_does_not_exist(&name))?
+                    .iter()
+                    .map(|pg_type| pg_type.unwrap_or(PgType::Integer))
+                    .collect();
+                sender
+                    .send(BackendMessage::ParameterDescription(param_types))
+                    .expect("Ok");
+                // result formats are not known until `Bind`, statement columns are described as text
+                let description = RowDescription::with_result_formats(COLUMNS, &ResultFormats::default())
+                    .map_err(|error| (PROTOCOL_VIOLATION, error.to_string()))?;
+                sender.send(description).expect("Ok");
+            }
+            CommandMessage::Bind {
+                portal_name,
+                statement_name,
+                param_formats,
+                raw_params,
+                result_formats,
+            } => {
+                if !self.statements.contains_key(&statement_name) {
+                    return Err(Self::statement_does_not_exist(&statement_name));
+                }
+                // a single format code is applied to all parameters, no format codes means text
+                let format = param_formats.first().copied().unwrap_or(PgFormat::Text);
+                let value = match raw_params.first() {
+                    Some(Some(raw)) => PgType::Integer.decode(&format, raw).map_err(|error| {
+                        let code = match format {
+                            PgFormat::Text => INVALID_TEXT_REPRESENTATION,
+                            PgFormat::Binary => INVALID_BINARY_REPRESENTATION,
+                        };
+                        (code, error.to_string())
+                    })?,
+                    _ => Value::Null,
+                };
+                let result_formats = ResultFormats::from(result_formats);
+                // the query has a single column, e.g. two format codes are a client error
+                if let Err(error) = result_formats.resolve(COLUMNS.len()) {
+                    return Err((PROTOCOL_VIOLATION, error.to_string()));
+                }
+                self.portals.insert(portal_name, (value, result_formats));
+                sender.send(BackendMessage::BindComplete).expect("Ok");
+            }
+            CommandMessage::DescribePortal { name } => {
+                let (_, result_formats) = self
+                    .portals
+                    .get(&name)
+                    .ok_or_else(|| Self::portal_does_not_exist(&name))?;
+                // portal is described with the formats its rows are sent in
+                let description = RowDescription::with_result_formats(COLUMNS, result_formats)
+                    .map_err(|error| (PROTOCOL_VIOLATION, error.to_string()))?;
+                sender.send(description).expect("Ok");
+            }
+            CommandMessage::Execute { portal_name, .. } => {
+                let (value, result_formats) = self
+                    .portals
+                    .get(&portal_name)
+                    .ok_or_else(|| Self::portal_does_not_exist(&portal_name))?;
+                // `NULL` is sent as a field without value
+                let row =
+                    BackendMessage::typed_data_row(std::slice::from_ref(value), &[PgType::Integer], result_formats)
+                        .map_err(|error| (INTERNAL_ERROR, error.to_string()))?;
+                sender.send(row).expect("Ok");
+                sender.send(CommandComplete::select(1)).expect("Ok");
+            }
+            CommandMessage::CloseStatement { name } => {
+                self.statements.remove(&name);
+                sender.send(BackendMessage::CloseComplete).expect("Ok");
+            }
+            CommandMessage::ClosePortal { name } => {
+                self.portals.remove(&name);
+                sender.send(BackendMessage::CloseComplete).expect("Ok");
+            }
+            CommandMessage::Flush => sender.flush().expect("Ok"),
+            CommandMessage::SaslInitialResponse { .. }
+            | CommandMessage::SaslResponse { .. }
+            | CommandMessage::GssResponse { .. } => {
+                return Err((PROTOCOL_VIOLATION, "unexpected authentication message".to_owned()));
+            }
+            CommandMessage::CopyDone | CommandMessage::CopyFail { .. } => {
+                return Err((FEATURE_NOT_SUPPORTED, "copy is not supported".to_owned()));
+            }
+            CommandMessage::Query { sql } => {
+                println!("{:?} is not supported. Only extended query is supported", sql);
+                sender
+                    .send(Self::error(FEATURE_NOT_SUPPORTED, "simple query is not supported"))
+                    .expect("Ok");
+                sender.send(BackendMessage::ReadyForQuery).expect("Ok");
+            }
+            CommandMessage::FunctionCall { .. } => {
+                sender
+                    .send(Self::error(FEATURE_NOT_SUPPORTED, "function call is not supported"))
+                    .expect("Ok");
+                sender.send(BackendMessage::ReadyForQuery).expect("Ok");
+            }
+            CommandMessage::Sync | CommandMessage::Terminate => unreachable!("handled by Session::handle"),
+        }
+        Ok(())
+    }
+
+    fn statement_does_not_exist(name: &str) -> (&'static str, String) {
+        (
+            INVALID_SQL_STATEMENT_NAME,
+            format!("prepared statement {:?} does not exist", name),
+        )
+    }
+
+    fn portal_does_not_exist(name: &str) -> (&'static str, String) {
+        (INVALID_CURSOR_NAME, format!("portal {:?} does not exist", name))
+    }
+
+    fn error<M: ToString>(code: &str, message: M) -> BackendMessage {
+        BackendMessage::ErrorResponse(ErrorFields::new("ERROR", code, message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{io, sync::Mutex};
+
+    #[derive(Default)]
+    struct RecordingSender {
+        messages: Mutex<Vec<BackendMessage>>,
+    }
+
+    impl Sender for RecordingSender {
+        fn flush(&self) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn send(&self, message: BackendMessage) -> io::Result<()> {
+            self.messages.lock().unwrap().push(message);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn extended_query_flow() {
+        let sender = RecordingSender::default();
+        let mut session = Session::default();
+
+        let commands = vec![
+            CommandMessage::Parse {
+                statement_name: "".to_owned(),
+                sql: "SELECT $1::int".to_owned(),
+                param_types: vec![Some(PgType::Integer)],
+            },
+            CommandMessage::Bind {
+                portal_name: "".to_owned(),
+                statement_name: "".to_owned(),
+                param_formats: vec![PgFormat::Binary],
+                raw_params: vec![Some(42i32.to_be_bytes().to_vec())],
+                result_formats: vec![],
+            },
+            CommandMessage::DescribePortal { name: "".to_owned() },
+            CommandMessage::Execute {
+                portal_name: "".to_owned(),
+                max_rows: 0,
+            },
+            CommandMessage::Sync,
+        ];
+        for command in commands {
+            assert!(session.handle(command, &sender));
+        }
+
+        assert_eq!(
+            sender.messages.into_inner().unwrap(),
+            vec![
+                BackendMessage::ParseComplete,
+                BackendMessage::BindComplete,
+                BackendMessage::RowDescription(vec![ColumnMetadata::new("int4", PgType::Integer)]),
+                BackendMessage::RawDataRow(vec![Some(b"42".to_vec())]),
+                CommandComplete::select(1),
+                BackendMessage::ReadyForQuery,
+            ]
+        );
+    }
+
+    #[test]
+    fn binary_result_format() {
+        let sender = RecordingSender::default();
+        let mut session = Session::default();
+
+        let commands = vec![
+            CommandMessage::Parse {
+                statement_name: "".to_owned(),
+                sql: "SELECT $1::int".to_owned(),
+                param_types: vec![Some(PgType::Integer)],
+            },
+            CommandMessage::Bind {
+                portal_name: "".to_owned(),
+                statement_name: "".to_owned(),
+                param_formats: vec![],
+                raw_params: vec![Some(b"42".to_vec())],
+                result_formats: vec![PgFormat::Binary],
+            },
+            CommandMessage::DescribePortal { name: "".to_owned() },
+            CommandMessage::Execute {
+                portal_name: "".to_owned(),
+                max_rows: 0,
+            },
+        ];
+        for command in commands {
+            session.handle(command, &sender);
+        }
+
+        assert_eq!(
+            sender.messages.into_inner().unwrap(),
+            vec![
+                BackendMessage::ParseComplete,
+                BackendMessage::BindComplete,
+                BackendMessage::RowDescription(vec![ColumnMetadata::with_format(
+                    "int4",
+                    PgType::Integer,
+                    PgFormat::Binary
+                )]),
+                BackendMessage::RawDataRow(vec![Some(42i32.to_be_bytes().to_vec())]),
+                CommandComplete::select(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn result_formats_do_not_match_columns() {
+        let sender = RecordingSender::default();
+        let mut session = Session::default();
+
+        session.handle(
+            CommandMessage::Parse {
+                statement_name: "".to_owned(),
+                sql: "SELECT $1::int".to_owned(),
+                param_types: vec![],
+            },
+            &sender,
+        );
+        session.handle(
+            CommandMessage::Bind {
+                portal_name: "".to_owned(),
+                statement_name: "".to_owned(),
+                param_formats: vec![],
+                raw_params: vec![Some(b"42".to_vec())],
+                result_formats: vec![PgFormat::Binary, PgFormat::Text],
+            },
+            &sender,
+        );
+
+        assert_eq!(
+            sender.messages.into_inner().unwrap(),
+            vec![
+                BackendMessage::ParseComplete,
+                BackendMessage::ErrorResponse(ErrorFields::new(
+                    "ERROR",
+                    PROTOCOL_VIOLATION,
+                    "bind message has 2 result formats but query has 1 columns"
+                )),
+            ]
+        );
+    }
+
+    #[test]
+    fn describe_statement() {
+        let sender = RecordingSender::default();
+        let mut session = Session::default();
+
+        session.handle(
+            CommandMessage::Parse {
+                statement_name: "stmt".to_owned(),
+                sql: "SELECT $1::int".to_owned(),
+                param_types: vec![None],
+            },
+            &sender,
+        );
+        session.handle(
+            CommandMessage::DescribeStatement {
+                name: "stmt".to_owned(),
+            },
+            &sender,
+        );
+
+        assert_eq!(
+            sender.messages.into_inner().unwrap(),
+            vec![
+                BackendMessage::ParseComplete,
+                BackendMessage::ParameterDescription(vec![PgType::Integer]),
+                BackendMessage::RowDescription(vec![ColumnMetadata::new("int4", PgType::Integer)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn null_parameter() {
+        let sender = RecordingSender::default();
+        let mut session = Session::default();
+
+        let commands = vec![
+            CommandMessage::Parse {
+                statement_name: "".to_owned(),
+                sql: "SELECT $1::int".to_owned(),
+                param_types: vec![Some(PgType::Integer)],
+            },
+            CommandMessage::Bind {
+                portal_name: "".to_owned(),
+                statement_name: "".to_owned(),
+                param_formats: vec![],
+                raw_params: vec![None],
+                result_formats: vec![],
+            },
+            CommandMessage::Execute {
+                portal_name: "".to_owned(),
+                max_rows: 0,
+            },
+        ];
+        for command in commands {
+            session.handle(command, &sender);
+        }
+
+        assert_eq!(
+            sender.messages.into_inner().unwrap(),
+            vec![
+                BackendMessage::ParseComplete,
+                BackendMessage::BindComplete,
+                BackendMessage::RawDataRow(vec![None]),
+                CommandComplete::select(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn discard_messages_until_sync_after_error() {
+        let sender = RecordingSender::default();
+        let mut session = Session::default();
+
+        let commands = vec![
+            CommandMessage::Bind {
+                portal_name: "".to_owned(),
+                statement_name: "missing".to_owned(),
+                param_formats: vec![],
+                raw_params: vec![],
+                result_formats: vec![],
+            },
+            CommandMessage::Parse {
+                statement_name: "".to_owned(),
+                sql: "SELECT $1::int".to_owned(),
+                param_types: vec![],
+            },
+            CommandMessage::Execute {
+                portal_name: "".to_owned(),
+                max_rows: 0,
+            },
+            CommandMessage::Sync,
+            CommandMessage::DescribePortal { name: "".to_owned() },
+        ];
+        for command in commands {
+            session.handle(command, &sender);
+        }
+
+        assert_eq!(
+            sender.messages.into_inner().unwrap(),
+            vec![
+                BackendMessage::ErrorResponse(ErrorFields::new(
+                    "ERROR",
+                    INVALID_SQL_STATEMENT_NAME,
+                    "prepared statement \"missing\" does not exist"
+                )),
+                BackendMessage::ReadyForQuery,
+                BackendMessage::ErrorResponse(ErrorFields::new(
+                    "ERROR",
+                    INVALID_CURSOR_NAME,
+                    "portal \"\" does not exist"
+                )),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_text_parameter() {
+        let sender = RecordingSender::default();
+        let mut session = Session::default();
+
+        session.handle(
+            CommandMessage::Parse {
+                statement_name: "".to_owned(),
+                sql: "SELECT $1::int".to_owned(),
+                param_types: vec![],
+            },
+            &sender,
+        );
+        session.handle(
+            CommandMessage::Bind {
+                portal_name: "".to_owned(),
+                statement_name: "".to_owned(),
+                param_formats: vec![],
+                raw_params: vec![Some(b"abc".to_vec())],
+                result_formats: vec![],
+            },
+            &sender,
+        );
+
+        assert!(matches!(
+            sender.messages.into_inner().unwrap().as_slice(),
+            [BackendMessage::ParseComplete, BackendMessage::ErrorResponse(fields)]
+                if fields.code.as_deref() == Some(INVALID_TEXT_REPRESENTATION)
+        ));
+    }
+
+    #[test]
+    fn terminate() {
+        let sender = RecordingSender::default();
+        let mut session = Session::default();
+
+        assert!(!session.handle(CommandMessage::Terminate, &sender));
+    }
+}
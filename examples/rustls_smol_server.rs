@@ -0,0 +1,276 @@
+// Copyright 2020 - 2021 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+fn main() {
+    #[cfg(not(feature = "async_rustls"))]
+    println!("execute `cargo run --example rustls_smol_server --features async_rustls` to run this example");
+    #[cfg(feature = "async_rustls")]
+    smol::block_on(async {
+        use async_mutex::Mutex as AsyncMutex;
+        use futures_lite::{AsyncReadExt, AsyncWriteExt};
+        use pg_wire::prelude::*;
+        use smol::Async;
+        use std::{net::TcpListener, path::PathBuf, sync::Arc};
+
+        let listener = Async::<TcpListener>::bind(([127, 0, 0, 1], 5432)).expect("OK");
+        println!("server started");
+
+        let config = ProtocolConfiguration::with_ssl(PathBuf::from("./etc/identity.pem"), String::new());
+        let conn_supervisor = ConnSupervisor::new(0, 10);
+        let pg_wire_listener = PgWireListener::new(listener, config, conn_supervisor);
+
+        loop {
+            match pg_wire_listener.accept().await {
+                Err(io_error) => eprintln!("IO error {:?}", io_error),
+                Ok(Err(protocol_error)) => eprintln!("protocol error {}", protocol_error),
+                Ok(Ok(ClientRequest::Connect((mut channel, props, conn_supervisor, address, settings)))) => {
+                    channel
+                        .write_all(BackendMessage::AuthenticationCleartextPassword.as_vec().as_slice())
+                        .await
+                        .expect("to ask for password in clear text format");
+                    channel.flush().await.expect("to flush the buffer");
+
+                    //TODO: use message decoder for Auth messages
+                    let mut tag_buffer = [0u8; 1];
+                    let _tag = channel.read_exact(&mut tag_buffer).await.map(|_| tag_buffer[0]);
+                    let mut len_buffer = [0u8; 4];
+                    let len = channel
+                        .read_exact(&mut len_buffer)
+                        .await
+                        .map(|_| u32::from_be_bytes(len_buffer) as usize)
+                        .expect("to read message length");
+                    let len = len - 4;
+                    let mut message_buffer = Vec::with_capacity(len);
+                    message_buffer.resize(len, b'0');
+                    let _message = channel
+                        .read_exact(&mut message_buffer)
+                        .await
+                        .map(|_| message_buffer)
+                        .expect("to read message body");
+
+                    // we are ok with any password that user sent
+                    pg_wire_listener
+                        .complete_authentication(&mut channel)
+                        .await
+                        .expect("Auth Ok");
+
+                    let (conn_id, secret_key) = match conn_supervisor.alloc() {
+                        Ok((c, s)) => (c, s),
+                        Err(()) => {
+                            eprintln!("Cannot allocate connection and its secret key");
+                            return;
+                        }
+                    };
+
+                    // sending connection id and its secret key if client wanted to cancel query
+                    channel
+                        .write_all(BackendMessage::BackendKeyData(conn_id, secret_key).as_vec().as_slice())
+                        .await
+                        .expect("to send connection id and secret key");
+
+                    channel
+                        .write_all(BackendMessage::ReadyForQuery.as_vec().as_slice())
+                        .await
+                        .expect("to notify that we ready to handle query");
+
+                    let channel = Arc::new(AsyncMutex::new(channel));
+                    let mut connection =
+                        Connection::new(conn_id, secret_key, props, address, channel, conn_supervisor, settings);
+                    println!("client connected from {:?}", connection.address());
+                    let sender = connection.sender();
+                    loop {
+                        match connection.receive().await {
+                            Err(e) => {
+                                eprintln!("Err(e) UNEXPECTED ERROR: {:?}", e);
+                                return;
+                            }
+                            Ok(Err(error)) => {
+                                eprintln!("malformed client message: {}", error);
+                                sender.send(BackendMessage::from(&error)).expect("Ok");
+                                return;
+                            }
+                            Ok(Ok(command)) => match command {
+                                CommandMessage::Query { sql } => {
+                                    println!("received query: '{}'", sql);
+                                    println!("but anyway we will handle 'select 1'");
+                                    sender
+                                        .send(BackendMessage::RowDescription(vec![ColumnMetadata::new(
+                                            &"col1",
+                                            PgType::Integer,
+                                        )]))
+                                        .expect("Ok");
+                                    sender.send(BackendMessage::DataRow(vec!["1".to_owned()])).expect("Ok");
+                                    sender.send(CommandComplete::select(1)).expect("Ok");
+                                    sender.send(BackendMessage::ReadyForQuery).expect("Ok");
+                                }
+                                CommandMessage::Terminate => {
+                                    println!("close connection");
+                                    break;
+                                }
+                                other => {
+                                    println!("{:?} is not supported. Only simple query is supported", other);
+                                    sender
+                                        .send(BackendMessage::NoticeResponse(ErrorFields::new(
+                                            "WARNING",
+                                            "01000",
+                                            "only simple query is supported",
+                                        )))
+                                        .expect("Ok");
+                                    sender.send(BackendMessage::ReadyForQuery).expect("Ok");
+                                }
+                            },
+                        }
+                    }
+                }
+                Ok(Ok(ClientRequest::QueryCancellation(_))) => {
+                    println!("Query cancellation is not supported")
+                }
+            }
+        }
+    })
+}
+
+#[cfg(all(test, feature = "async_rustls"))]
+mod tests {
+    use futures_lite::{future, AsyncReadExt, AsyncWriteExt};
+    use futures_rustls::{
+        rustls::{internal::pemfile, ClientConfig, Session},
+        webpki::DNSNameRef,
+        TlsConnector,
+    };
+    use pg_wire::prelude::*;
+    use smol::Async;
+    use std::{
+        fs,
+        net::{SocketAddr, TcpListener, TcpStream},
+        path::PathBuf,
+        sync::Arc,
+    };
+
+    /// `SSLRequest` message
+    const SSL_REQUEST: [u8; 8] = [0, 0, 0, 8, 4, 210, 22, 47];
+
+    fn startup_message() -> Vec<u8> {
+        let body = [&[0, 3, 0, 0][..], b"user\0username\0\0"].concat();
+        [&((body.len() + 4) as u32).to_be_bytes()[..], &body].concat()
+    }
+
+    fn certificate(path: &str) -> Vec<u8> {
+        let pem = fs::read(path).expect("PEM file");
+        pemfile::certs(&mut pem.as_slice()).expect("certificates")[0].0.clone()
+    }
+
+    fn listener() -> (PgWireListener, SocketAddr) {
+        let listener = Async::<TcpListener>::bind(([127, 0, 0, 1], 0)).expect("bound listener");
+        let address = listener.get_ref().local_addr().expect("local address");
+        let config = ProtocolConfiguration::with_ssl(PathBuf::from("./etc/identity.pem"), String::new())
+            .with_sni_identities(vec![
+                ("alpha.test".to_owned(), PathBuf::from("./etc/sni/alpha.pem")),
+                ("beta.test".to_owned(), PathBuf::from("./etc/sni/beta.pem")),
+            ]);
+        (
+            PgWireListener::new(listener, config, ConnSupervisor::new(0, 10)),
+            address,
+        )
+    }
+
+    fn client_config() -> ClientConfig {
+        let mut config = ClientConfig::new();
+        let ca = fs::read("./etc/sni/ca.pem").expect("CA file");
+        config
+            .root_store
+            .add_pem_file(&mut ca.as_slice())
+            .expect("CA certificate");
+        config
+    }
+
+    /// Returns server name that the server negotiated with the client and
+    /// certificate that the client received
+    async fn connect(listener: &PgWireListener, address: SocketAddr, server_name: &str) -> (Option<String>, Vec<u8>) {
+        let (negotiated, _, presented) = connect_with(listener, address, server_name, client_config()).await;
+        (negotiated, presented)
+    }
+
+    /// Returns server name that the server negotiated with the client,
+    /// certificate that the server received and the one that the client received
+    async fn connect_with(
+        listener: &PgWireListener,
+        address: SocketAddr,
+        server_name: &str,
+        config: ClientConfig,
+    ) -> (Option<String>, Option<Vec<u8>>, Vec<u8>) {
+        let client = async {
+            let mut tcp = Async::<TcpStream>::connect(address).await.expect("connected");
+            tcp.write_all(&SSL_REQUEST).await.expect("SSL request sent");
+            let mut response = [0u8; 1];
+            tcp.read_exact(&mut response).await.expect("SSL response");
+            assert_eq!(&response, b"S");
+
+            let mut tls = TlsConnector::from(Arc::new(config))
+                .connect(DNSNameRef::try_from_ascii_str(server_name).expect("DNS name"), tcp)
+                .await
+                .expect("TLS handshake");
+            tls.write_all(&startup_message()).await.expect("startup message sent");
+            tls.flush().await.expect("flushed");
+            let certificates = tls.get_ref().1.get_peer_certificates().expect("server certificates");
+            (tls, certificates[0].0.clone())
+        };
+        let (accepted, (_tls, presented)) = future::zip(listener.accept(), client).await;
+        match accepted {
+            Ok(Ok(ClientRequest::Connect((channel, _, _, _, _)))) => {
+                (channel.server_name(), channel.peer_certificate(), presented)
+            }
+            _ => panic!("client is not connected"),
+        }
+    }
+
+    #[test]
+    fn certificate_is_selected_by_server_name() {
+        smol::block_on(async {
+            let (listener, address) = listener();
+
+            assert_eq!(
+                connect(&listener, address, "alpha.test").await,
+                (Some("alpha.test".to_owned()), certificate("./etc/sni/alpha.pem"))
+            );
+            assert_eq!(
+                connect(&listener, address, "beta.test").await,
+                (Some("beta.test".to_owned()), certificate("./etc/sni/beta.pem"))
+            );
+        });
+    }
+
+    #[test]
+    fn client_certificate_is_verified_by_client_ca() {
+        smol::block_on(async {
+            let listener = Async::<TcpListener>::bind(([127, 0, 0, 1], 0)).expect("bound listener");
+            let address = listener.get_ref().local_addr().expect("local address");
+            let config = ProtocolConfiguration::with_ssl(PathBuf::from("./etc/sni/alpha.pem"), String::new())
+                .with_client_ca(PathBuf::from("./etc/client/ca.pem"));
+            let listener = PgWireListener::new(listener, config, ConnSupervisor::new(0, 10));
+
+            let pem = fs::read("./etc/client/client.pem").expect("client identity");
+            let certificates = pemfile::certs(&mut pem.as_slice()).expect("certificates");
+            let key = pemfile::pkcs8_private_keys(&mut pem.as_slice()).expect("private keys")[0].clone();
+            let mut config = client_config();
+            config
+                .set_single_client_cert(certificates, key)
+                .expect("client certificate");
+
+            let (_, received, _) = connect_with(&listener, address, "alpha.test", config).await;
+
+            assert_eq!(received, Some(certificate("./etc/client/client.pem")));
+        });
+    }
+}
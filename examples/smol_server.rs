@@ -19,10 +19,7 @@ fn main() {
     smol::block_on(async {
         use async_mutex::Mutex as AsyncMutex;
         use futures_lite::{AsyncReadExt, AsyncWriteExt};
-        use pg_wire::{
-            ClientRequest, CommandMessage, ConnSupervisor, Connection, PgWireListener, ProtocolConfiguration, Sender,
-        };
-        use pg_wire_payload::{BackendMessage, ColumnMetadata, PgType};
+        use pg_wire::prelude::*;
         use smol::Async;
         use std::{net::TcpListener, sync::Arc};
 
@@ -37,7 +34,7 @@ fn main() {
             match pg_wire_listener.accept().await {
                 Err(io_error) => eprintln!("IO error {:?}", io_error),
                 Ok(Err(protocol_error)) => eprintln!("protocol error {}", protocol_error),
-                Ok(Ok(ClientRequest::Connect((mut channel, props, conn_supervisor, address)))) => {
+                Ok(Ok(ClientRequest::Connect((mut channel, props, conn_supervisor, address, settings)))) => {
                     channel
                         .write_all(BackendMessage::AuthenticationCleartextPassword.as_vec().as_slice())
                         .await
@@ -63,21 +60,11 @@ fn main() {
                         .expect("to read message body");
 
                     // we are ok with any password that user sent
-                    channel
-                        .write_all(BackendMessage::AuthenticationOk.as_vec().as_slice())
+                    pg_wire_listener
+                        .complete_authentication(&mut channel)
                         .await
                         .expect("Auth Ok");
 
-                    // pretend to be a PostgreSQL version 12.4
-                    channel
-                        .write_all(
-                            BackendMessage::ParameterStatus("server_version".to_owned(), "12.4".to_owned())
-                                .as_vec()
-                                .as_slice(),
-                        )
-                        .await
-                        .expect("send server version");
-
                     let (conn_id, secret_key) = match conn_supervisor.alloc() {
                         Ok((c, s)) => (c, s),
                         Err(()) => {
@@ -98,7 +85,8 @@ fn main() {
                         .expect("to notify that we ready to handle query");
 
                     let channel = Arc::new(AsyncMutex::new(channel));
-                    let mut connection = Connection::new(conn_id, props, address, channel, conn_supervisor);
+                    let mut connection =
+                        Connection::new(conn_id, secret_key, props, address, channel, conn_supervisor, settings);
                     println!("client connected from {:?}", connection.address());
                     let sender = connection.sender();
                     loop {
@@ -107,8 +95,9 @@ fn main() {
                                 eprintln!("Err(e) UNEXPECTED ERROR: {:?}", e);
                                 return;
                             }
-                            Ok(Err(e)) => {
-                                eprintln!("Ok(Err(e)) UNEXPECTED ERROR: {:?}", e);
+                            Ok(Err(error)) => {
+                                eprintln!("malformed client message: {}", error);
+                                sender.send(BackendMessage::from(&error)).expect("Ok");
                                 return;
                             }
                             Ok(Ok(command)) => match command {
@@ -122,9 +111,7 @@ fn main() {
                                         )]))
                                         .expect("Ok");
                                     sender.send(BackendMessage::DataRow(vec!["1".to_owned()])).expect("Ok");
-                                    sender
-                                        .send(BackendMessage::CommandComplete("SELECT 1".to_owned()))
-                                        .expect("Ok");
+                                    sender.send(CommandComplete::select(1)).expect("Ok");
                                     sender.send(BackendMessage::ReadyForQuery).expect("Ok");
                                 }
                                 CommandMessage::Terminate => {
@@ -133,7 +120,13 @@ fn main() {
                                 }
                                 other => {
                                     println!("{:?} is not supported. Only simple query is supported", other);
-                                    sender.send(BackendMessage::NoticeResponse).expect("Ok");
+                                    sender
+                                        .send(BackendMessage::NoticeResponse(ErrorFields::new(
+                                            "WARNING",
+                                            "01000",
+                                            "only simple query is supported",
+                                        )))
+                                        .expect("Ok");
                                     sender.send(BackendMessage::ReadyForQuery).expect("Ok");
                                 }
                             },
@@ -18,10 +18,7 @@ fn main() {
     #[cfg(feature = "tokio_net")]
     {
         use async_mutex::Mutex as AsyncMutex;
-        use pg_wire::{
-            ClientRequest, CommandMessage, ConnSupervisor, Connection, PgWireListener, ProtocolConfiguration, Sender,
-        };
-        use pg_wire_payload::{BackendMessage, ColumnMetadata, PgType};
+        use pg_wire::prelude::*;
         use std::{env, sync::Arc};
         use tokio::{
             io::{AsyncReadExt, AsyncWriteExt},
@@ -45,7 +42,7 @@ fn main() {
                     Err(io_error) => eprintln!("IO error {:?}", io_error),
                     Ok(Err(protocol_error)) => eprintln!("protocol error {:?}", protocol_error),
 
-                    Ok(Ok(ClientRequest::Connect((mut channel, props, conn_supervisor, address)))) => {
+                    Ok(Ok(ClientRequest::Connect((mut channel, props, conn_supervisor, address, settings)))) => {
                         channel
                             .write_all(BackendMessage::AuthenticationCleartextPassword.as_vec().as_slice())
                             .await
@@ -71,21 +68,11 @@ fn main() {
                             .expect("to read message body");
 
                         // we are ok with any password that user sent
-                        channel
-                            .write_all(BackendMessage::AuthenticationOk.as_vec().as_slice())
+                        connection_manager
+                            .complete_authentication(&mut channel)
                             .await
                             .expect("Auth Ok");
 
-                        // pretend to be a PostgreSQL version 12.4
-                        channel
-                            .write_all(
-                                BackendMessage::ParameterStatus("server_version".to_owned(), "12.4".to_owned())
-                                    .as_vec()
-                                    .as_slice(),
-                            )
-                            .await
-                            .expect("send server version");
-
                         let (conn_id, secret_key) = match conn_supervisor.alloc() {
                             Ok((c, s)) => (c, s),
                             Err(()) => {
@@ -106,7 +93,8 @@ fn main() {
                             .expect("to notify that we ready to handle query");
 
                         let channel = Arc::new(AsyncMutex::new(channel));
-                        let mut connection = Connection::new(conn_id, props, address, channel, conn_supervisor);
+                        let mut connection =
+                            Connection::new(conn_id, secret_key, props, address, channel, conn_supervisor, settings);
                         println!("client connected from {:?}", connection.address());
                         let sender = connection.sender();
                         loop {
@@ -115,8 +103,9 @@ fn main() {
                                     eprintln!("Err(e) UNEXPECTED ERROR: {:?}", e);
                                     return;
                                 }
-                                Ok(Err(e)) => {
-                                    eprintln!("Ok(Err(e)) UNEXPECTED ERROR: {:?}", e);
+                                Ok(Err(error)) => {
+                                    eprintln!("malformed client message: {}", error);
+                                    sender.send(BackendMessage::from(&error)).expect("Ok");
                                     return;
                                 }
                                 Ok(Ok(command)) => match command {
@@ -130,9 +119,7 @@ fn main() {
                                             )]))
                                             .expect("Ok");
                                         sender.send(BackendMessage::DataRow(vec!["1".to_owned()])).expect("Ok");
-                                        sender
-                                            .send(BackendMessage::CommandComplete("SELECT 1".to_owned()))
-                                            .expect("Ok");
+                                        sender.send(CommandComplete::select(1)).expect("Ok");
                                         sender.send(BackendMessage::ReadyForQuery).expect("Ok");
                                     }
                                     CommandMessage::Terminate => {
@@ -141,7 +128,13 @@ fn main() {
                                     }
                                     other => {
                                         println!("{:?} is not supported. Only simple query is supported", other);
-                                        sender.send(BackendMessage::NoticeResponse).expect("Ok");
+                                        sender
+                                            .send(BackendMessage::NoticeResponse(ErrorFields::new(
+                                                "WARNING",
+                                                "01000",
+                                                "only simple query is supported",
+                                            )))
+                                            .expect("Ok");
                                         sender.send(BackendMessage::ReadyForQuery).expect("Ok");
                                     }
                                 },
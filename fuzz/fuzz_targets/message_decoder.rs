@@ -0,0 +1,61 @@
+// Copyright 2020 - 2021 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Feeds arbitrary bytes through `MessageDecoder` and `MessageStream` as if a
+//! client sent them over the wire. Decoding has to end up with either decoded
+//! messages or an error, but never with a panic
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pg_wire::{MessageDecoder, MessageDecoderMode, MessageDecoderStatus, MessageStream};
+
+const MODES: [MessageDecoderMode; 4] = [
+    MessageDecoderMode::Command,
+    MessageDecoderMode::SaslInitialResponse,
+    MessageDecoderMode::SaslResponse,
+    MessageDecoderMode::GssResponse,
+];
+
+fuzz_target!(|data: &[u8]| {
+    // the first byte chooses how `PasswordMessage` family of messages is decoded
+    let (mode, mut channel) = match data.split_first() {
+        Some((mode, rest)) => (MODES[*mode as usize % MODES.len()], rest),
+        None => return,
+    };
+
+    let mut message_decoder = MessageDecoder::default();
+    message_decoder.set_mode(mode);
+    let mut current: Option<&[u8]> = None;
+    loop {
+        match message_decoder.next_stage(current.take()) {
+            Ok(MessageDecoderStatus::Requesting(len)) => {
+                if channel.len() < len {
+                    // client has not sent the whole message
+                    break;
+                }
+                let (payload, rest) = channel.split_at(len);
+                current = Some(payload);
+                channel = rest;
+            }
+            Ok(MessageDecoderStatus::Done(_message)) => {}
+            Err(_error) => break,
+        }
+    }
+
+    let mut stream = MessageStream::new(&data[1..]);
+    stream.set_mode(mode);
+    for _message in &mut stream {}
+    assert!(stream.consumed() < data.len());
+});
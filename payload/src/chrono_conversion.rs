@@ -0,0 +1,398 @@
+// Copyright 2020 - 2021 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversions of temporal [Value]s into `chrono` types and back. Sub-microsecond
+//! precision of `chrono` types is truncated
+
+use crate::{
+    datetime::{MICROS_PER_DAY, MICROS_PER_SECOND},
+    Date, Value,
+};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+};
+
+const NANOS_PER_MICRO: i64 = 1_000;
+
+/// An error which can be returned when converting [Value]s into `chrono`
+/// types and back
+#[derive(Debug, PartialEq)]
+pub struct ChronoConversionError {
+    kind: ChronoConversionErrorKind,
+}
+
+impl From<ChronoConversionErrorKind> for ChronoConversionError {
+    fn from(kind: ChronoConversionErrorKind) -> ChronoConversionError {
+        ChronoConversionError { kind }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ChronoConversionErrorKind {
+    NotConvertible { value: Value, target: &'static str },
+    DurationOutOfRange(Duration),
+}
+
+impl Display for ChronoConversionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ChronoConversionErrorKind::NotConvertible { value, target } => {
+                write!(f, "{:?} value can not be converted to {}", value, target)
+            }
+            ChronoConversionErrorKind::DurationOutOfRange(duration) => {
+                write!(f, "duration {} is out of range of interval type", duration)
+            }
+        }
+    }
+}
+
+fn not_convertible(value: Value, target: &'static str) -> ChronoConversionError {
+    ChronoConversionError::from(ChronoConversionErrorKind::NotConvertible { value, target })
+}
+
+/// PostgreSQL epoch `2000-01-01 00:00:00`
+fn pg_epoch() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2000, 1, 1)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .expect("valid date")
+}
+
+fn from_pg_epoch(micros: i64) -> Option<NaiveDateTime> {
+    pg_epoch().checked_add_signed(Duration::microseconds(micros))
+}
+
+fn since_pg_epoch(datetime: NaiveDateTime) -> i64 {
+    (datetime - pg_epoch())
+        .num_microseconds()
+        .expect("chrono date and time range fits into microseconds since 2000-01-01")
+}
+
+/// `infinity` and `-infinity` do not have `chrono` counterparts
+impl TryFrom<Value> for NaiveDate {
+    type Error = ChronoConversionError;
+
+    fn try_from(value: Value) -> Result<NaiveDate, ChronoConversionError> {
+        match value {
+            Value::Date(Date::Day { year, month, day }) => {
+                NaiveDate::from_ymd_opt(year, month, day).ok_or_else(|| not_convertible(value, "NaiveDate"))
+            }
+            _ => Err(not_convertible(value, "NaiveDate")),
+        }
+    }
+}
+
+impl From<NaiveDate> for Value {
+    fn from(date: NaiveDate) -> Value {
+        Value::Date(Date::Day {
+            year: date.year(),
+            month: date.month(),
+            day: date.day(),
+        })
+    }
+}
+
+impl TryFrom<Value> for NaiveDateTime {
+    type Error = ChronoConversionError;
+
+    fn try_from(value: Value) -> Result<NaiveDateTime, ChronoConversionError> {
+        match value {
+            Value::Timestamp(micros) => from_pg_epoch(micros).ok_or_else(|| not_convertible(value, "NaiveDateTime")),
+            _ => Err(not_convertible(value, "NaiveDateTime")),
+        }
+    }
+}
+
+impl From<NaiveDateTime> for Value {
+    fn from(datetime: NaiveDateTime) -> Value {
+        Value::Timestamp(since_pg_epoch(datetime))
+    }
+}
+
+impl TryFrom<Value> for DateTime<Utc> {
+    type Error = ChronoConversionError;
+
+    fn try_from(value: Value) -> Result<DateTime<Utc>, ChronoConversionError> {
+        match value {
+            Value::TimestampTz(micros) => from_pg_epoch(micros)
+                .map(|datetime| datetime.and_utc())
+                .ok_or_else(|| not_convertible(value, "DateTime<Utc>")),
+            _ => Err(not_convertible(value, "DateTime<Utc>")),
+        }
+    }
+}
+
+impl From<DateTime<Utc>> for Value {
+    fn from(datetime: DateTime<Utc>) -> Value {
+        Value::TimestampTz(since_pg_epoch(datetime.naive_utc()))
+    }
+}
+
+/// `24:00:00` that PostgreSQL allows does not have `chrono` counterpart
+impl TryFrom<Value> for NaiveTime {
+    type Error = ChronoConversionError;
+
+    fn try_from(value: Value) -> Result<NaiveTime, ChronoConversionError> {
+        match value {
+            Value::Time(micros) if (0..MICROS_PER_DAY).contains(&micros) => {
+                NaiveTime::from_num_seconds_from_midnight_opt(
+                    (micros / MICROS_PER_SECOND) as u32,
+                    ((micros % MICROS_PER_SECOND) * NANOS_PER_MICRO) as u32,
+                )
+                .ok_or_else(|| not_convertible(value, "NaiveTime"))
+            }
+            _ => Err(not_convertible(value, "NaiveTime")),
+        }
+    }
+}
+
+impl From<NaiveTime> for Value {
+    fn from(time: NaiveTime) -> Value {
+        Value::Time(
+            time.num_seconds_from_midnight() as i64 * MICROS_PER_SECOND + time.nanosecond() as i64 / NANOS_PER_MICRO,
+        )
+    }
+}
+
+/// Only intervals without months are converted, since month length varies,
+/// days are considered as 24 hours
+impl TryFrom<Value> for Duration {
+    type Error = ChronoConversionError;
+
+    fn try_from(value: Value) -> Result<Duration, ChronoConversionError> {
+        match value {
+            Value::Interval {
+                months: 0,
+                days,
+                micros,
+            } => Duration::days(days as i64)
+                .checked_add(&Duration::microseconds(micros))
+                .ok_or_else(|| not_convertible(value, "Duration")),
+            _ => Err(not_convertible(value, "Duration")),
+        }
+    }
+}
+
+impl TryFrom<Duration> for Value {
+    type Error = ChronoConversionError;
+
+    fn try_from(duration: Duration) -> Result<Value, ChronoConversionError> {
+        match duration.num_microseconds() {
+            Some(micros) => Ok(Value::Interval {
+                months: 0,
+                days: 0,
+                micros,
+            }),
+            None => Err(ChronoConversionError::from(
+                ChronoConversionErrorKind::DurationOutOfRange(duration),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `2021-04-13 10:20:30.5`
+    const MICROS: i64 = 671_624_430_500_000;
+
+    fn datetime() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2021, 4, 13)
+            .and_then(|date| date.and_hms_micro_opt(10, 20, 30, 500_000))
+            .unwrap()
+    }
+
+    #[test]
+    fn date() {
+        let date = NaiveDate::from_ymd_opt(2021, 4, 13).unwrap();
+        let value = || {
+            Value::Date(Date::Day {
+                year: 2021,
+                month: 4,
+                day: 13,
+            })
+        };
+
+        assert_eq!(NaiveDate::try_from(value()), Ok(date));
+        assert_eq!(Value::from(date), value());
+    }
+
+    #[test]
+    fn date_round_trip() {
+        for date in &[
+            NaiveDate::from_ymd_opt(2000, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(1999, 12, 31).unwrap(),
+            NaiveDate::from_ymd_opt(2020, 2, 29).unwrap(),
+            NaiveDate::from_ymd_opt(-4713, 11, 24).unwrap(),
+            NaiveDate::MIN,
+            NaiveDate::MAX,
+        ] {
+            assert_eq!(NaiveDate::try_from(Value::from(*date)), Ok(*date));
+        }
+    }
+
+    #[test]
+    fn date_from_days_round_trip() {
+        for days in &[0, -1, 7_773, -2_451_545] {
+            let date = NaiveDate::try_from(Value::Date(Date::from_days(*days))).unwrap();
+            assert_eq!(Value::from(date), Value::Date(Date::from_days(*days)));
+            assert_eq!(
+                date,
+                NaiveDate::from_ymd_opt(2000, 1, 1).unwrap() + Duration::days(*days as i64)
+            );
+        }
+    }
+
+    #[test]
+    fn timestamp() {
+        assert_eq!(NaiveDateTime::try_from(Value::Timestamp(MICROS)), Ok(datetime()));
+        assert_eq!(Value::from(datetime()), Value::Timestamp(MICROS));
+    }
+
+    #[test]
+    fn timestamp_round_trip_without_drift() {
+        for micros in &[MICROS, -MICROS, 0, 1, -1, 999_999] {
+            let datetime = NaiveDateTime::try_from(Value::Timestamp(*micros)).unwrap();
+            assert_eq!(Value::from(datetime), Value::Timestamp(*micros));
+        }
+    }
+
+    #[test]
+    fn timestamp_before_pg_epoch() {
+        assert_eq!(
+            NaiveDateTime::try_from(Value::Timestamp(-1)),
+            Ok(NaiveDate::from_ymd_opt(1999, 12, 31)
+                .and_then(|date| date.and_hms_micro_opt(23, 59, 59, 999_999))
+                .unwrap())
+        );
+    }
+
+    #[test]
+    fn timestamp_with_time_zone() {
+        assert_eq!(
+            DateTime::<Utc>::try_from(Value::TimestampTz(MICROS)),
+            Ok(datetime().and_utc())
+        );
+        assert_eq!(Value::from(datetime().and_utc()), Value::TimestampTz(MICROS));
+    }
+
+    #[test]
+    fn time() {
+        let time = NaiveTime::from_hms_micro_opt(10, 20, 30, 500_000).unwrap();
+        let micros = MICROS % MICROS_PER_DAY;
+
+        assert_eq!(NaiveTime::try_from(Value::Time(micros)), Ok(time));
+        assert_eq!(Value::from(time), Value::Time(micros));
+    }
+
+    #[test]
+    fn sub_microseconds_are_truncated() {
+        let time = NaiveTime::from_hms_nano_opt(0, 0, 0, 1_999).unwrap();
+
+        assert_eq!(Value::from(time), Value::Time(1));
+    }
+
+    #[test]
+    fn interval() {
+        let duration = Duration::days(2) + Duration::microseconds(1_500);
+
+        assert_eq!(
+            Duration::try_from(Value::Interval {
+                months: 0,
+                days: 2,
+                micros: 1_500
+            }),
+            Ok(duration)
+        );
+        assert_eq!(
+            Value::try_from(duration),
+            Ok(Value::Interval {
+                months: 0,
+                days: 0,
+                micros: 2 * MICROS_PER_DAY + 1_500
+            })
+        );
+    }
+
+    #[test]
+    fn not_convertible_dates() {
+        for date in &[
+            Date::Infinity,
+            Date::NegativeInfinity,
+            Date::Day {
+                year: 2021,
+                month: 2,
+                day: 29,
+            },
+        ] {
+            assert_eq!(
+                NaiveDate::try_from(Value::Date(*date)),
+                Err(not_convertible(Value::Date(*date), "NaiveDate"))
+            );
+        }
+        assert_eq!(
+            NaiveDate::try_from(Value::Timestamp(0)),
+            Err(not_convertible(Value::Timestamp(0), "NaiveDate"))
+        );
+    }
+
+    #[test]
+    fn not_convertible_values() {
+        assert_eq!(
+            NaiveDateTime::try_from(Value::TimestampTz(0)),
+            Err(not_convertible(Value::TimestampTz(0), "NaiveDateTime"))
+        );
+        assert_eq!(
+            NaiveDateTime::try_from(Value::Timestamp(i64::MAX)),
+            Err(not_convertible(Value::Timestamp(i64::MAX), "NaiveDateTime"))
+        );
+        assert_eq!(
+            NaiveTime::try_from(Value::Time(MICROS_PER_DAY)),
+            Err(not_convertible(Value::Time(MICROS_PER_DAY), "NaiveTime"))
+        );
+        let interval = || Value::Interval {
+            months: 1,
+            days: 0,
+            micros: 0,
+        };
+        assert_eq!(
+            Duration::try_from(interval()),
+            Err(not_convertible(interval(), "Duration"))
+        );
+    }
+
+    #[test]
+    fn duration_out_of_range() {
+        assert_eq!(
+            Value::try_from(Duration::MAX),
+            Err(ChronoConversionError::from(
+                ChronoConversionErrorKind::DurationOutOfRange(Duration::MAX)
+            ))
+        );
+    }
+
+    #[test]
+    fn formatting() {
+        assert_eq!(
+            not_convertible(Value::Time(-1), "NaiveTime").to_string(),
+            "Time(-1) value can not be converted to NaiveTime"
+        );
+        assert_eq!(
+            ChronoConversionError::from(ChronoConversionErrorKind::DurationOutOfRange(Duration::days(1))).to_string(),
+            "duration PT86400S is out of range of interval type"
+        );
+    }
+}
@@ -0,0 +1,413 @@
+// Copyright 2020 - 2021 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryFrom;
+
+pub(crate) const MICROS_PER_SECOND: i64 = 1_000_000;
+pub(crate) const SECONDS_PER_DAY: i64 = 86_400;
+pub(crate) const MICROS_PER_DAY: i64 = SECONDS_PER_DAY * MICROS_PER_SECOND;
+/// Number of days between `1970-01-01` and PostgreSQL epoch `2000-01-01`
+const UNIX_TO_PG_EPOCH_DAYS: i64 = 10_957;
+/// Era suffix of dates before `0001-01-01` in the ISO output of PostgreSQL
+const BC: &str = " BC";
+
+/// Number of days since PostgreSQL epoch `2000-01-01` of proleptic Gregorian date,
+/// `None` if the number of days does not fit into `i64`
+/// see http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> Option<i64> {
+    let year = if month <= 2 { year.checked_sub(1)? } else { year };
+    let era = if year >= 0 { year } else { year.checked_sub(399)? } / 400;
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era.checked_mul(146_097)?
+        .checked_add(day_of_era - 719_468 - UNIX_TO_PG_EPOCH_DAYS)
+}
+
+/// Proleptic Gregorian date of number of days since PostgreSQL epoch `2000-01-01`
+/// see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468 + UNIX_TO_PG_EPOCH_DAYS;
+    let era = if days >= 0 { days } else { days - 146_096 } / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(s: &str) -> Option<T> {
+    if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+        s.parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Number of days since PostgreSQL epoch if `year`, `month` and `day` make up a valid date
+pub(crate) fn checked_days_from_civil(year: i64, month: u32, day: u32) -> Option<i64> {
+    if month == 0 || month > 12 || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+    days_from_civil(year, month, day)
+}
+
+/// Splits ` BC` suffix off, returns `true` if there was one
+fn strip_era(s: &str) -> (&str, bool) {
+    match s.strip_suffix(BC) {
+        Some(s) => (s, true),
+        None => (s, false),
+    }
+}
+
+/// Parses `YYYY-MM-DD[ BC]` into number of days since PostgreSQL epoch
+pub(crate) fn parse_date(s: &str) -> Option<i64> {
+    let (s, bc) = strip_era(s);
+    parse_year_month_day(s, bc)
+}
+
+fn parse_year_month_day(s: &str, bc: bool) -> Option<i64> {
+    let mut parts = s.splitn(3, '-');
+    let year = parse_number::<i64>(parts.next()?)?;
+    let month = parse_number::<u32>(parts.next()?)?;
+    let day = parse_number::<u32>(parts.next()?)?;
+    let year = match (bc, year) {
+        (true, 0) => return None,
+        (true, year) => 1 - year,
+        (false, year) => year,
+    };
+    checked_days_from_civil(year, month, day)
+}
+
+/// Parses `HH:MM:SS[.ffffff]` into number of microseconds since midnight
+pub(crate) fn parse_time(s: &str) -> Option<i64> {
+    let (time, fraction) = match s.find('.') {
+        Some(index) => (&s[..index], Some(&s[index + 1..])),
+        None => (s, None),
+    };
+    let mut parts = time.splitn(3, ':');
+    let hours = parse_number::<i64>(parts.next()?)?;
+    let minutes = parse_number::<i64>(parts.next()?)?;
+    let seconds = parse_number::<i64>(parts.next()?)?;
+    let micros = match fraction {
+        None => 0,
+        Some(fraction) if fraction.len() > 6 => return None,
+        Some(fraction) => parse_number::<i64>(fraction)? * 10i64.pow(6 - fraction.len() as u32),
+    };
+    if minutes > 59 || seconds > 59 || hours > 24 || (hours == 24 && (minutes, seconds, micros) != (0, 0, 0)) {
+        return None;
+    }
+    Some(((hours * 60 + minutes) * 60 + seconds) * MICROS_PER_SECOND + micros)
+}
+
+/// Parses `+HH` or `+HH:MM` UTC offset into number of seconds
+fn parse_offset(s: &str) -> Option<i64> {
+    let (sign, offset) = match s.as_bytes().first()? {
+        b'+' => (1, &s[1..]),
+        b'-' => (-1, &s[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match offset.find(':') {
+        Some(index) => (&offset[..index], parse_number::<i64>(&offset[index + 1..])?),
+        None => (offset, 0),
+    };
+    let hours = parse_number::<i64>(hours)?;
+    if hours > 15 || minutes > 59 {
+        return None;
+    }
+    Some(sign * (hours * 60 + minutes) * 60)
+}
+
+/// Parses `YYYY-MM-DD HH:MM:SS[.ffffff][+HH[:MM]][ BC]` into number of microseconds
+/// since PostgreSQL epoch of the local time and UTC offset in seconds if it is specified
+pub(crate) fn parse_timestamp(s: &str) -> Option<(i64, Option<i64>)> {
+    let (s, bc) = strip_era(s.trim());
+    let index = s.find([' ', 'T'])?;
+    let days = parse_year_month_day(&s[..index], bc)?;
+    let rest = &s[index + 1..];
+    let (time, offset) = match rest.find(['+', '-']) {
+        Some(index) => (&rest[..index], Some(parse_offset(&rest[index..])?)),
+        None => (rest, None),
+    };
+    let micros = days.checked_mul(MICROS_PER_DAY)?.checked_add(parse_time(time)?)?;
+    Some((micros, offset))
+}
+
+/// Formats `[-]HH:MM:SS[.ffffff]` dropping trailing zeros of the fraction, number of hours is not limited
+pub(crate) fn format_time(micros: i64) -> String {
+    let sign = if micros < 0 { "-" } else { "" };
+    let micros = micros.unsigned_abs();
+    let micros_per_second = MICROS_PER_SECOND as u64;
+    let seconds = micros / micros_per_second;
+    let time = format!(
+        "{}{:02}:{:02}:{:02}",
+        sign,
+        seconds / 3_600,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+    match micros % micros_per_second {
+        0 => time,
+        fraction => format!("{}.{}", time, format!("{:06}", fraction).trim_end_matches('0')),
+    }
+}
+
+/// Year of era and era suffix of astronomical `year`, e.g. year `0` is `0001 BC`
+fn year_of_era(year: i64) -> (i64, &'static str) {
+    if year > 0 {
+        (year, "")
+    } else {
+        (1 - year, BC)
+    }
+}
+
+/// Formats date of astronomical `year` as `YYYY-MM-DD[ BC]`
+pub(crate) fn format_date(year: i64, month: u32, day: u32) -> String {
+    let (year, era) = year_of_era(year);
+    format!("{:04}-{:02}-{:02}{}", year, month, day, era)
+}
+
+/// Formats number of microseconds since PostgreSQL epoch as `YYYY-MM-DD HH:MM:SS[.ffffff][ BC]`
+pub(crate) fn format_timestamp(micros: i64) -> String {
+    format_datetime(micros, "")
+}
+
+/// Formats number of microseconds since PostgreSQL epoch in UTC as
+/// `YYYY-MM-DD HH:MM:SS[.ffffff]+00[ BC]`
+pub(crate) fn format_timestamp_tz(micros: i64) -> String {
+    format_datetime(micros, "+00")
+}
+
+fn format_datetime(micros: i64, offset: &str) -> String {
+    let (year, month, day) = civil_from_days(micros.div_euclid(MICROS_PER_DAY));
+    let (year, era) = year_of_era(year);
+    format!(
+        "{:04}-{:02}-{:02} {}{}{}",
+        year,
+        month,
+        day,
+        format_time(micros.rem_euclid(MICROS_PER_DAY)),
+        offset,
+        era
+    )
+}
+
+/// Formats interval the way PostgreSQL does with `IntervalStyle` set to `postgres`,
+/// e.g. `1 year 2 mons -3 days 04:05:06.5`
+pub(crate) fn format_interval(months: i32, days: i32, micros: i64) -> String {
+    fn unit(value: i32, singular: &str, plural: &str) -> Option<String> {
+        match value {
+            0 => None,
+            1 => Some(format!("1 {}", singular)),
+            value => Some(format!("{} {}", value, plural)),
+        }
+    }
+
+    let mut parts = vec![
+        unit(months / 12, "year", "years"),
+        unit(months % 12, "mon", "mons"),
+        unit(days, "day", "days"),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+    if micros != 0 || parts.is_empty() {
+        parts.push(format_time(micros));
+    }
+    parts.join(" ")
+}
+
+/// Parses PostgreSQL verbose interval representation, e.g. `1 year 2 mons -3 days 04:05:06.5`,
+/// into number of months, days and microseconds
+pub(crate) fn parse_interval(s: &str) -> Option<(i32, i32, i64)> {
+    let (mut months, mut days, mut micros) = (0i32, 0i32, 0i64);
+    let mut tokens = s.split_whitespace().peekable();
+    tokens.peek()?;
+    while let Some(token) = tokens.next() {
+        let (sign, token) = match token.as_bytes().first()? {
+            b'-' => (-1, &token[1..]),
+            b'+' => (1, &token[1..]),
+            _ => (1, token),
+        };
+        if token.contains(':') {
+            micros = micros.checked_add(sign * parse_interval_time(token)?)?;
+            continue;
+        }
+        let value = sign * parse_number::<i64>(token)?;
+        match tokens.next()?.to_lowercase().as_str() {
+            "year" | "years" => months = months.checked_add(i32::try_from(value.checked_mul(12)?).ok()?)?,
+            "mon" | "mons" | "month" | "months" => months = months.checked_add(i32::try_from(value).ok()?)?,
+            "day" | "days" => days = days.checked_add(i32::try_from(value).ok()?)?,
+            "hour" | "hours" => micros = micros.checked_add(value.checked_mul(3_600 * MICROS_PER_SECOND)?)?,
+            "min" | "mins" | "minute" | "minutes" => {
+                micros = micros.checked_add(value.checked_mul(60 * MICROS_PER_SECOND)?)?
+            }
+            "sec" | "secs" | "second" | "seconds" => {
+                micros = micros.checked_add(value.checked_mul(MICROS_PER_SECOND)?)?
+            }
+            _ => return None,
+        }
+    }
+    Some((months, days, micros))
+}
+
+/// Parses `HH:MM[:SS[.ffffff]]` time part of an interval, number of hours is not limited
+fn parse_interval_time(s: &str) -> Option<i64> {
+    let (time, fraction) = match s.find('.') {
+        Some(index) => (&s[..index], Some(&s[index + 1..])),
+        None => (s, None),
+    };
+    let mut parts = time.splitn(3, ':');
+    let hours = parse_number::<i64>(parts.next()?)?;
+    let minutes = parse_number::<i64>(parts.next()?)?;
+    let seconds = match parts.next() {
+        Some(seconds) => parse_number::<i64>(seconds)?,
+        None if fraction.is_none() => 0,
+        None => return None,
+    };
+    let micros = match fraction {
+        None => 0,
+        Some(fraction) if fraction.len() > 6 => return None,
+        Some(fraction) => parse_number::<i64>(fraction)? * 10i64.pow(6 - fraction.len() as u32),
+    };
+    if minutes > 59 || seconds > 59 {
+        return None;
+    }
+    hours
+        .checked_mul(3_600)?
+        .checked_add(minutes * 60 + seconds)?
+        .checked_mul(MICROS_PER_SECOND)?
+        .checked_add(micros)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pg_epoch() {
+        assert_eq!(days_from_civil(2000, 1, 1), Some(0));
+        assert_eq!(days_from_civil(1970, 1, 1), Some(-UNIX_TO_PG_EPOCH_DAYS));
+        assert_eq!(days_from_civil(1999, 12, 31), Some(-1));
+        assert_eq!(days_from_civil(2000, 3, 1), Some(60));
+    }
+
+    #[test]
+    fn invalid_dates() {
+        assert_eq!(parse_date("2021-02-29"), None);
+        assert_eq!(parse_date("2021-13-01"), None);
+        assert_eq!(parse_date("2021-01"), None);
+        assert_eq!(parse_date("2020-02-29"), days_from_civil(2020, 2, 29));
+    }
+
+    #[test]
+    fn times() {
+        assert_eq!(parse_time("00:00:00"), Some(0));
+        assert_eq!(parse_time("24:00:00"), Some(MICROS_PER_DAY));
+        assert_eq!(parse_time("01:02:03.5"), Some(3_723_500_000));
+        assert_eq!(parse_time("24:00:01"), None);
+        assert_eq!(parse_time("12:60:00"), None);
+        assert_eq!(parse_time("12:00:00.1234567"), None);
+    }
+
+    #[test]
+    fn offsets() {
+        assert_eq!(parse_offset("+03"), Some(3 * 3600));
+        assert_eq!(parse_offset("-05:30"), Some(-(5 * 3600 + 30 * 60)));
+        assert_eq!(parse_offset("03"), None);
+    }
+
+    #[test]
+    fn civil_dates() {
+        for (year, month, day) in [(2000, 1, 1), (1999, 12, 31), (2020, 2, 29), (1970, 1, 1), (2400, 3, 1)].iter() {
+            assert_eq!(
+                civil_from_days(days_from_civil(*year, *month, *day).unwrap()),
+                (*year, *month, *day)
+            );
+        }
+    }
+
+    #[test]
+    fn formatted_timestamps() {
+        assert_eq!(format_timestamp(0), "2000-01-01 00:00:00");
+        assert_eq!(format_timestamp(-500_000), "1999-12-31 23:59:59.5");
+        assert_eq!(
+            format_timestamp(parse_timestamp("2021-03-04 05:06:07.000123").unwrap().0),
+            "2021-03-04 05:06:07.000123"
+        );
+    }
+
+    #[test]
+    fn before_common_era() {
+        assert_eq!(parse_date("0001-12-31 BC"), days_from_civil(0, 12, 31));
+        assert_eq!(parse_date("0044-03-15 BC"), days_from_civil(-43, 3, 15));
+        assert_eq!(parse_date("0000-01-01 BC"), None);
+        assert_eq!(format_date(0, 12, 31), "0001-12-31 BC");
+        assert_eq!(format_date(1, 1, 1), "0001-01-01");
+        let micros = days_from_civil(-1, 12, 31).unwrap() * MICROS_PER_DAY + 1;
+        assert_eq!(format_timestamp(micros), "0002-12-31 00:00:00.000001 BC");
+        assert_eq!(format_timestamp_tz(micros), "0002-12-31 00:00:00.000001+00 BC");
+        assert_eq!(parse_timestamp("0002-12-31 00:00:00.000001 BC"), Some((micros, None)));
+        assert_eq!(
+            parse_timestamp("0002-12-31 00:00:00.000001+00 BC"),
+            Some((micros, Some(0)))
+        );
+    }
+
+    #[test]
+    fn formatted_intervals() {
+        assert_eq!(format_interval(14, 3, 14_706_000_000), "1 year 2 mons 3 days 04:05:06");
+        assert_eq!(format_interval(0, -1, -7_200_500_000), "-1 days -02:00:00.5");
+        assert_eq!(format_interval(1, 1, 0), "1 mon 1 day");
+        assert_eq!(format_interval(0, 0, 0), "00:00:00");
+        assert_eq!(format_interval(0, 0, 360_000_000_000), "100:00:00");
+        assert_eq!(format_interval(0, 0, i64::MIN), "-2562047788:00:54.775808");
+        assert_eq!(format_interval(0, 0, i64::MAX), "2562047788:00:54.775807");
+    }
+
+    #[test]
+    fn intervals() {
+        assert_eq!(
+            parse_interval("1 year 2 mons 3 days 04:05:06"),
+            Some((14, 3, 14_706_000_000))
+        );
+        assert_eq!(parse_interval("-1 days -02:00:00.5"), Some((0, -1, -7_200_500_000)));
+        assert_eq!(parse_interval("100:00"), Some((0, 0, 360_000_000_000)));
+        assert_eq!(parse_interval("2 hours 30 mins 15 secs"), Some((0, 0, 9_015_000_000)));
+        assert_eq!(parse_interval("1 week"), None);
+        assert_eq!(parse_interval("3"), None);
+        assert_eq!(parse_interval(""), None);
+    }
+}
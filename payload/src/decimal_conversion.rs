@@ -0,0 +1,186 @@
+// Copyright 2020 - 2021 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversions of [Value::Numeric] into `rust_decimal` type and back
+
+use crate::{numeric, Value};
+use rust_decimal::Decimal;
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+};
+
+/// An error which can be returned when converting [Value] into [Decimal]
+#[derive(Debug, PartialEq)]
+pub struct DecimalConversionError {
+    kind: DecimalConversionErrorKind,
+}
+
+impl From<DecimalConversionErrorKind> for DecimalConversionError {
+    fn from(kind: DecimalConversionErrorKind) -> DecimalConversionError {
+        DecimalConversionError { kind }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum DecimalConversionErrorKind {
+    NotNumeric(Value),
+    NotANumber,
+    OutOfRange(String),
+}
+
+impl Display for DecimalConversionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            DecimalConversionErrorKind::NotNumeric(value) => {
+                write!(f, "{:?} value can not be converted to Decimal", value)
+            }
+            DecimalConversionErrorKind::NotANumber => write!(f, "NaN numeric value can not be converted to Decimal"),
+            DecimalConversionErrorKind::OutOfRange(number) => {
+                write!(f, "numeric value {} is out of range of Decimal", number)
+            }
+        }
+    }
+}
+
+/// Numbers that have more significant digits than [Decimal] holds are out of
+/// range rather than rounded
+impl TryFrom<Value> for Decimal {
+    type Error = DecimalConversionError;
+
+    fn try_from(value: Value) -> Result<Decimal, DecimalConversionError> {
+        let number = match value {
+            Value::Numeric(number) => number,
+            _ => {
+                return Err(DecimalConversionError::from(DecimalConversionErrorKind::NotNumeric(
+                    value,
+                )))
+            }
+        };
+        match numeric::normalize_numeric(&number) {
+            Some(normalized) if normalized == "NaN" => {
+                Err(DecimalConversionError::from(DecimalConversionErrorKind::NotANumber))
+            }
+            Some(normalized) => Decimal::from_str_exact(&normalized)
+                .map_err(|_error| DecimalConversionError::from(DecimalConversionErrorKind::OutOfRange(number))),
+            None => Err(DecimalConversionError::from(DecimalConversionErrorKind::NotNumeric(
+                Value::Numeric(number),
+            ))),
+        }
+    }
+}
+
+impl From<Decimal> for Value {
+    fn from(decimal: Decimal) -> Value {
+        Value::Numeric(decimal.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn numeric_into_decimal() {
+        assert_eq!(
+            Decimal::try_from(Value::Numeric("123.45".to_owned())),
+            Ok(Decimal::new(12_345, 2))
+        );
+        assert_eq!(
+            Decimal::try_from(Value::Numeric("-0.001".to_owned())),
+            Ok(Decimal::new(-1, 3))
+        );
+    }
+
+    #[test]
+    fn decimal_into_numeric() {
+        assert_eq!(
+            Value::from(Decimal::new(12_345, 2)),
+            Value::Numeric("123.45".to_owned())
+        );
+    }
+
+    #[test]
+    fn round_trip_keeps_canonical_string() {
+        for number in &["123.45", "-0.001", "0", "1.500", "79228162514264337593543950335"] {
+            let decimal = Decimal::try_from(Value::Numeric((*number).to_owned())).unwrap();
+            assert_eq!(Value::from(decimal), Value::Numeric((*number).to_owned()));
+        }
+    }
+
+    #[test]
+    fn exponent_notation() {
+        assert_eq!(
+            Decimal::try_from(Value::Numeric("1.5e3".to_owned())),
+            Ok(Decimal::from_str("1500").unwrap())
+        );
+    }
+
+    #[test]
+    fn not_a_number() {
+        assert_eq!(
+            Decimal::try_from(Value::Numeric("NaN".to_owned())),
+            Err(DecimalConversionError::from(DecimalConversionErrorKind::NotANumber))
+        );
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert_eq!(
+            Decimal::try_from(Value::Numeric("79228162514264337593543950336".to_owned())),
+            Err(DecimalConversionError::from(DecimalConversionErrorKind::OutOfRange(
+                "79228162514264337593543950336".to_owned()
+            )))
+        );
+        assert_eq!(
+            Decimal::try_from(Value::Numeric("0.00000000000000000000000000001".to_owned())),
+            Err(DecimalConversionError::from(DecimalConversionErrorKind::OutOfRange(
+                "0.00000000000000000000000000001".to_owned()
+            )))
+        );
+    }
+
+    #[test]
+    fn not_numeric_values() {
+        assert_eq!(
+            Decimal::try_from(Value::Int32(1)),
+            Err(DecimalConversionError::from(DecimalConversionErrorKind::NotNumeric(
+                Value::Int32(1)
+            )))
+        );
+        assert_eq!(
+            Decimal::try_from(Value::Numeric("abc".to_owned())),
+            Err(DecimalConversionError::from(DecimalConversionErrorKind::NotNumeric(
+                Value::Numeric("abc".to_owned())
+            )))
+        );
+    }
+
+    #[test]
+    fn formatting() {
+        assert_eq!(
+            DecimalConversionError::from(DecimalConversionErrorKind::NotNumeric(Value::Int32(1))).to_string(),
+            "Int32(1) value can not be converted to Decimal"
+        );
+        assert_eq!(
+            DecimalConversionError::from(DecimalConversionErrorKind::NotANumber).to_string(),
+            "NaN numeric value can not be converted to Decimal"
+        );
+        assert_eq!(
+            DecimalConversionError::from(DecimalConversionErrorKind::OutOfRange("1e100".to_owned())).to_string(),
+            "numeric value 1e100 is out of range of Decimal"
+        );
+    }
+}
@@ -28,6 +28,8 @@ impl Display for UnrecognizedFormat {
     }
 }
 
+impl std::error::Error for UnrecognizedFormat {}
+
 /// PostgreSQL formats for transferring data
 /// `0` - textual representation
 /// `1` - binary representation
@@ -51,6 +53,76 @@ impl TryFrom<i16> for PgFormat {
     }
 }
 
+impl From<PgFormat> for i16 {
+    fn from(format: PgFormat) -> i16 {
+        match format {
+            PgFormat::Text => 0,
+            PgFormat::Binary => 1,
+        }
+    }
+}
+
+/// Represents an error if number of result formats of `Bind` message neither
+/// is zero, one nor matches number of result columns
+#[derive(Debug, PartialEq)]
+pub struct ResultFormatsMismatch {
+    formats: usize,
+    columns: usize,
+}
+
+impl Display for ResultFormatsMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "bind message has {} result formats but query has {} columns",
+            self.formats, self.columns
+        )
+    }
+}
+
+impl std::error::Error for ResultFormatsMismatch {}
+
+/// Formats of result columns that client requested with `Bind` message
+///
+/// # Examples
+///
+/// ```
+/// use pg_wire_payload::{PgFormat, ResultFormats};
+///
+/// let formats = ResultFormats::from(vec![PgFormat::Binary]);
+/// assert_eq!(formats.resolve(2), Ok(vec![PgFormat::Binary, PgFormat::Binary]));
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ResultFormats(Vec<PgFormat>);
+
+impl ResultFormats {
+    /// Format codes as they were sent with `Bind` message
+    pub fn as_slice(&self) -> &[PgFormat] {
+        &self.0
+    }
+
+    /// Format of each of `column_count` columns. No formats means all columns
+    /// are in text format, one format applies to all columns, otherwise each
+    /// column has its own format
+    pub fn resolve(&self, column_count: usize) -> Result<Vec<PgFormat>, ResultFormatsMismatch> {
+        match self.0.as_slice() {
+            [] => Ok(vec![PgFormat::Text; column_count]),
+            [format] => Ok(vec![*format; column_count]),
+            formats if formats.len() == column_count => Ok(formats.to_vec()),
+            formats => Err(ResultFormatsMismatch {
+                formats: formats.len(),
+                columns: column_count,
+            }),
+        }
+    }
+}
+
+impl From<Vec<PgFormat>> for ResultFormats {
+    fn from(formats: Vec<PgFormat>) -> ResultFormats {
+        ResultFormats(formats)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -59,4 +131,51 @@ mod tests {
     fn unrecognized_format() {
         assert_eq!(PgFormat::try_from(2), Err(UnrecognizedFormat(2)));
     }
+
+    #[test]
+    fn format_codes() {
+        assert_eq!(i16::from(PgFormat::Text), 0);
+        assert_eq!(i16::from(PgFormat::Binary), 1);
+    }
+
+    #[test]
+    fn no_result_formats() {
+        assert_eq!(
+            ResultFormats::default().resolve(3),
+            Ok(vec![PgFormat::Text, PgFormat::Text, PgFormat::Text])
+        );
+    }
+
+    #[test]
+    fn one_result_format() {
+        assert_eq!(
+            ResultFormats::from(vec![PgFormat::Binary]).resolve(2),
+            Ok(vec![PgFormat::Binary, PgFormat::Binary])
+        );
+        assert_eq!(ResultFormats::from(vec![PgFormat::Binary]).resolve(0), Ok(vec![]));
+    }
+
+    #[test]
+    fn result_format_per_column() {
+        assert_eq!(
+            ResultFormats::from(vec![PgFormat::Binary, PgFormat::Text]).resolve(2),
+            Ok(vec![PgFormat::Binary, PgFormat::Text])
+        );
+    }
+
+    #[test]
+    fn result_formats_mismatch() {
+        assert_eq!(
+            ResultFormats::from(vec![PgFormat::Binary, PgFormat::Text]).resolve(3),
+            Err(ResultFormatsMismatch { formats: 2, columns: 3 })
+        );
+    }
+
+    #[test]
+    fn formatting() {
+        assert_eq!(
+            ResultFormatsMismatch { formats: 2, columns: 3 }.to_string(),
+            "bind message has 2 result formats but query has 3 columns"
+        );
+    }
 }
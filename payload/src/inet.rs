@@ -0,0 +1,124 @@
+// Copyright 2020 - 2021 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::Value;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Address family of IPv4 addresses, PostgreSQL does not use `AF_INET` of
+/// the platform to keep binary representation portable
+pub(crate) const PGSQL_AF_INET: u8 = 2;
+/// Address family of IPv6 addresses
+pub(crate) const PGSQL_AF_INET6: u8 = 3;
+const HEADER_LEN: usize = 4;
+
+fn max_bits(family: u8) -> Option<u8> {
+    match family {
+        PGSQL_AF_INET => Some(32),
+        PGSQL_AF_INET6 => Some(128),
+        _ => None,
+    }
+}
+
+/// Checks that address has as many bytes as its family requires, netmask is
+/// not longer than the address and `cidr` address has no bits set to the
+/// right of netmask
+fn is_valid(family: u8, bits: u8, is_cidr: bool, addr: &[u8]) -> bool {
+    match max_bits(family) {
+        Some(max) if bits <= max && addr.len() == max as usize / 8 => {
+            !is_cidr
+                || addr.iter().enumerate().all(|(index, byte)| {
+                    let masked = (bits as usize).saturating_sub(index * 8).min(8);
+                    byte & (0xFFu8.checked_shr(masked as u32).unwrap_or(0)) == 0
+                })
+        }
+        _ => false,
+    }
+}
+
+/// Parses `address[/bits]` where address is in IPv4 or IPv6 notation. Netmask
+/// defaults to the whole address
+pub(crate) fn parse_inet(s: &str, is_cidr: bool) -> Option<Value> {
+    let s = s.trim();
+    let (address, bits) = match s.find('/') {
+        Some(pos) => (&s[..pos], Some(&s[pos + 1..])),
+        None => (s, None),
+    };
+    let (family, addr) = match address.parse::<Ipv4Addr>() {
+        Ok(address) => (PGSQL_AF_INET, address.octets().to_vec()),
+        Err(_) => (PGSQL_AF_INET6, address.parse::<Ipv6Addr>().ok()?.octets().to_vec()),
+    };
+    let bits = match bits {
+        Some(bits) if !bits.is_empty() && bits.bytes().all(|b| b.is_ascii_digit()) => bits.parse().ok()?,
+        Some(_) => return None,
+        None => max_bits(family)?,
+    };
+    if is_valid(family, bits, is_cidr, &addr) {
+        Some(Value::Inet {
+            family,
+            bits,
+            is_cidr,
+            addr,
+        })
+    } else {
+        None
+    }
+}
+
+/// Formats address in IPv4 or IPv6 notation, netmask is omitted for `inet`
+/// host addresses as PostgreSQL does
+pub(crate) fn format_inet(family: u8, bits: u8, is_cidr: bool, addr: &[u8]) -> Option<String> {
+    if !is_valid(family, bits, is_cidr, addr) {
+        return None;
+    }
+    let address = match family {
+        PGSQL_AF_INET => Ipv4Addr::from([addr[0], addr[1], addr[2], addr[3]]).to_string(),
+        _ => {
+            let mut octets = [0; 16];
+            octets.copy_from_slice(addr);
+            Ipv6Addr::from(octets).to_string()
+        }
+    };
+    if is_cidr || Some(bits) != max_bits(family) {
+        Some(format!("{}/{}", address, bits))
+    } else {
+        Some(address)
+    }
+}
+
+/// Deserializes family, netmask bits, `is_cidr` flag and address length
+/// followed by address bytes. Like PostgreSQL, whether the value is `cidr` is
+/// determined by the column type rather than the flag
+pub(crate) fn decode_inet(raw: &[u8], is_cidr: bool) -> Option<Value> {
+    let (header, addr) = (raw.get(..HEADER_LEN)?, &raw[HEADER_LEN..]);
+    let (family, bits, len) = (header[0], header[1], header[3] as usize);
+    if addr.len() == len && is_valid(family, bits, is_cidr, addr) {
+        Some(Value::Inet {
+            family,
+            bits,
+            is_cidr,
+            addr: addr.to_vec(),
+        })
+    } else {
+        None
+    }
+}
+
+/// Serializes address into binary representation that [decode_inet] reads
+pub(crate) fn encode_inet(family: u8, bits: u8, is_cidr: bool, addr: &[u8]) -> Option<Vec<u8>> {
+    if is_valid(family, bits, is_cidr, addr) {
+        Some([&[family, bits, is_cidr as u8, addr.len() as u8][..], addr].concat())
+    } else {
+        None
+    }
+}
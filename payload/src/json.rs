@@ -0,0 +1,400 @@
+// Copyright 2020 - 2021 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(feature = "json")]
+use crate::Value;
+#[cfg(feature = "json")]
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+};
+
+/// Represents an error if [Value] does not have JSON counterpart
+#[cfg(feature = "json")]
+#[derive(Debug, PartialEq)]
+pub struct NotJsonValue(Value);
+
+#[cfg(feature = "json")]
+impl Display for NotJsonValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} value can not be converted to JSON", self.0)
+    }
+}
+
+/// `json` and `jsonb` documents are parsed, strings, booleans and integers
+/// become JSON scalars and arrays are converted element by element
+#[cfg(feature = "json")]
+impl TryFrom<Value> for serde_json::Value {
+    type Error = NotJsonValue;
+
+    fn try_from(value: Value) -> Result<serde_json::Value, NotJsonValue> {
+        match value {
+            Value::Null => Ok(serde_json::Value::Null),
+            Value::Bool(value) => Ok(serde_json::Value::Bool(value)),
+            Value::Int16(value) => Ok(serde_json::Value::from(value)),
+            Value::Int32(value) => Ok(serde_json::Value::from(value)),
+            Value::Int64(value) => Ok(serde_json::Value::from(value)),
+            Value::Oid(value) => Ok(serde_json::Value::from(value)),
+            Value::String(value) => Ok(serde_json::Value::String(value)),
+            Value::Json(document) => match serde_json::from_str(&document) {
+                Ok(json) => Ok(json),
+                Err(_) => Err(NotJsonValue(Value::Json(document))),
+            },
+            Value::Jsonb(document) => match serde_json::from_str(&document) {
+                Ok(json) => Ok(json),
+                Err(_) => Err(NotJsonValue(Value::Jsonb(document))),
+            },
+            Value::Array(values) => values
+                .into_iter()
+                .map(serde_json::Value::try_from)
+                .collect::<Result<Vec<_>, _>>()
+                .map(serde_json::Value::Array),
+            other => Err(NotJsonValue(other)),
+        }
+    }
+}
+
+/// JSON scalars become [Value::Null], [Value::Bool], [Value::Int64] or
+/// [Value::String], other numbers, arrays and objects become [Value::Json]
+#[cfg(feature = "json")]
+impl From<serde_json::Value> for Value {
+    fn from(json: serde_json::Value) -> Value {
+        match json {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(value) => Value::Bool(value),
+            serde_json::Value::Number(number) => match number.as_i64() {
+                Some(value) => Value::Int64(value),
+                None => Value::Json(number.to_string()),
+            },
+            serde_json::Value::String(value) => Value::String(value),
+            json => Value::Json(json.to_string()),
+        }
+    }
+}
+
+/// Maximum number of nested arrays and objects in a document. The parser is
+/// recursive, deeper documents are rejected instead of overflowing the stack
+const MAX_NESTING_DEPTH: usize = 512;
+
+/// Checks that `s` is syntactically well-formed JSON document that nests
+/// arrays and objects at most [MAX_NESTING_DEPTH] levels deep
+/// see https://www.json.org/json-en.html
+pub(crate) fn is_valid(s: &str) -> bool {
+    let mut parser = Parser {
+        bytes: s.as_bytes(),
+        pos: 0,
+        depth: 0,
+    };
+    parser.skip_whitespaces();
+    if !parser.value() {
+        return false;
+    }
+    parser.skip_whitespaces();
+    parser.pos == parser.bytes.len()
+}
+
+struct Parser<'p> {
+    bytes: &'p [u8],
+    pos: usize,
+    depth: usize,
+}
+
+impl<'p> Parser<'p> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn consume(&mut self, byte: u8) -> bool {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn value(&mut self) -> bool {
+        match self.peek() {
+            Some(b'{') => self.nested(Self::object),
+            Some(b'[') => self.nested(Self::array),
+            Some(b'"') => self.string(),
+            Some(b't') => self.literal(b"true"),
+            Some(b'f') => self.literal(b"false"),
+            Some(b'n') => self.literal(b"null"),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            _ => false,
+        }
+    }
+
+    fn nested(&mut self, parse: fn(&mut Self) -> bool) -> bool {
+        if self.depth == MAX_NESTING_DEPTH {
+            return false;
+        }
+        self.depth += 1;
+        let valid = parse(self);
+        self.depth -= 1;
+        valid
+    }
+
+    fn literal(&mut self, literal: &[u8]) -> bool {
+        if self.bytes[self.pos..].starts_with(literal) {
+            self.pos += literal.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn object(&mut self) -> bool {
+        self.pos += 1;
+        self.skip_whitespaces();
+        if self.consume(b'}') {
+            return true;
+        }
+        loop {
+            self.skip_whitespaces();
+            if self.peek() != Some(b'"') || !self.string() {
+                return false;
+            }
+            self.skip_whitespaces();
+            if !self.consume(b':') {
+                return false;
+            }
+            self.skip_whitespaces();
+            if !self.value() {
+                return false;
+            }
+            self.skip_whitespaces();
+            if self.consume(b'}') {
+                return true;
+            }
+            if !self.consume(b',') {
+                return false;
+            }
+        }
+    }
+
+    fn array(&mut self) -> bool {
+        self.pos += 1;
+        self.skip_whitespaces();
+        if self.consume(b']') {
+            return true;
+        }
+        loop {
+            self.skip_whitespaces();
+            if !self.value() {
+                return false;
+            }
+            self.skip_whitespaces();
+            if self.consume(b']') {
+                return true;
+            }
+            if !self.consume(b',') {
+                return false;
+            }
+        }
+    }
+
+    fn string(&mut self) -> bool {
+        self.pos += 1;
+        while let Some(byte) = self.peek() {
+            self.pos += 1;
+            match byte {
+                b'"' => return true,
+                b'\\' => match self.peek() {
+                    Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => self.pos += 1,
+                    Some(b'u') => {
+                        self.pos += 1;
+                        for _ in 0..4 {
+                            match self.peek() {
+                                Some(digit) if digit.is_ascii_hexdigit() => self.pos += 1,
+                                _ => return false,
+                            }
+                        }
+                    }
+                    _ => return false,
+                },
+                0x00..=0x1f => return false,
+                _ => {}
+            }
+        }
+        false
+    }
+
+    fn number(&mut self) -> bool {
+        self.consume(b'-');
+        if !self.consume(b'0') && !self.digits() {
+            return false;
+        }
+        if self.consume(b'.') && !self.digits() {
+            return false;
+        }
+        if self.consume(b'e') || self.consume(b'E') {
+            if !self.consume(b'+') {
+                self.consume(b'-');
+            }
+            if !self.digits() {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn digits(&mut self) -> bool {
+        let start = self.pos;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+        self.pos > start
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scalars() {
+        assert!(is_valid("null"));
+        assert!(is_valid(" true "));
+        assert!(is_valid("false"));
+        assert!(is_valid("\"string with \\\"escapes\\\" and \\u00e9\""));
+        assert!(is_valid("0"));
+        assert!(is_valid("-12.5e+3"));
+    }
+
+    #[test]
+    fn objects_and_arrays() {
+        assert!(is_valid("{}"));
+        assert!(is_valid("[]"));
+        assert!(is_valid("{\"a\": [1, 2, {\"b\": null}], \"c\": \"d\"}"));
+        assert!(is_valid("[[], {}, \"\"]"));
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(!is_valid(""));
+        assert!(!is_valid("{\"a\": 1,}"));
+        assert!(!is_valid("[1 2]"));
+        assert!(!is_valid("{a: 1}"));
+        assert!(!is_valid("01"));
+        assert!(!is_valid("1."));
+        assert!(!is_valid("\"unterminated"));
+        assert!(!is_valid("\"\\x\""));
+        assert!(!is_valid("tru"));
+        assert!(!is_valid("{} {}"));
+    }
+
+    #[test]
+    fn nesting_depth() {
+        let nested = |depth: usize| "[".repeat(depth) + &"]".repeat(depth);
+        assert!(is_valid(&nested(MAX_NESTING_DEPTH)));
+        assert!(!is_valid(&nested(MAX_NESTING_DEPTH + 1)));
+        let objects = "{\"a\":".repeat(MAX_NESTING_DEPTH - 1) + "[]" + &"}".repeat(MAX_NESTING_DEPTH - 1);
+        assert!(is_valid(&objects));
+        assert!(!is_valid(&"[".repeat(1_000_000)));
+    }
+
+    #[cfg(feature = "json")]
+    mod serde_json_conversion {
+        use super::*;
+
+        #[test]
+        fn object_round_trip() {
+            let document = r#"{"a":[1,"b",null],"c":{"d":true}}"#;
+            let json = serde_json::Value::try_from(Value::Json(document.to_owned())).unwrap();
+
+            assert_eq!(json["a"][1], serde_json::json!("b"));
+            assert_eq!(Value::from(json), Value::Json(document.to_owned()));
+        }
+
+        #[test]
+        fn jsonb_document() {
+            assert_eq!(
+                serde_json::Value::try_from(Value::Jsonb("[1, 2]".to_owned())),
+                Ok(serde_json::json!([1, 2]))
+            );
+        }
+
+        #[test]
+        fn scalars() {
+            assert_eq!(serde_json::Value::try_from(Value::Null), Ok(serde_json::Value::Null));
+            assert_eq!(
+                serde_json::Value::try_from(Value::Bool(true)),
+                Ok(serde_json::json!(true))
+            );
+            assert_eq!(serde_json::Value::try_from(Value::Int16(-1)), Ok(serde_json::json!(-1)));
+            assert_eq!(
+                serde_json::Value::try_from(Value::Int64(1 << 40)),
+                Ok(serde_json::json!(1i64 << 40))
+            );
+            assert_eq!(
+                serde_json::Value::try_from(Value::String("abc".to_owned())),
+                Ok(serde_json::json!("abc"))
+            );
+
+            assert_eq!(Value::from(serde_json::json!(null)), Value::Null);
+            assert_eq!(Value::from(serde_json::json!(false)), Value::Bool(false));
+            assert_eq!(Value::from(serde_json::json!(42)), Value::Int64(42));
+            assert_eq!(Value::from(serde_json::json!(1.5)), Value::Json("1.5".to_owned()));
+            assert_eq!(Value::from(serde_json::json!("abc")), Value::String("abc".to_owned()));
+        }
+
+        #[test]
+        fn array() {
+            assert_eq!(
+                serde_json::Value::try_from(Value::Array(vec![
+                    Value::Int32(1),
+                    Value::Null,
+                    Value::Json("{}".to_owned())
+                ])),
+                Ok(serde_json::json!([1, null, {}]))
+            );
+        }
+
+        #[test]
+        fn not_json_value() {
+            assert_eq!(
+                serde_json::Value::try_from(Value::Time(0)),
+                Err(NotJsonValue(Value::Time(0)))
+            );
+            assert_eq!(
+                serde_json::Value::try_from(Value::Array(vec![Value::Money(100)])),
+                Err(NotJsonValue(Value::Money(100)))
+            );
+            assert_eq!(
+                serde_json::Value::try_from(Value::Json("{".to_owned())),
+                Err(NotJsonValue(Value::Json("{".to_owned())))
+            );
+            assert_eq!(
+                serde_json::Value::try_from(Value::Jsonb("{".to_owned())),
+                Err(NotJsonValue(Value::Jsonb("{".to_owned())))
+            );
+        }
+
+        #[test]
+        fn formatting() {
+            assert_eq!(
+                NotJsonValue(Value::Time(0)).to_string(),
+                "Time(0) value can not be converted to JSON"
+            );
+        }
+    }
+}
@@ -12,11 +12,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "chrono")]
+mod chrono_conversion;
+mod datetime;
+#[cfg(feature = "decimal")]
+mod decimal_conversion;
 mod format;
+mod inet;
+mod json;
 mod messages;
+mod money;
+mod numeric;
 mod types;
 
+#[cfg(feature = "chrono")]
+pub use chrono_conversion::ChronoConversionError;
+#[cfg(feature = "decimal")]
+pub use decimal_conversion::DecimalConversionError;
 pub use format::*;
+#[cfg(feature = "json")]
+pub use json::NotJsonValue;
 pub use messages::*;
 pub use types::*;
 
@@ -12,7 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{types::PgType, ConnId, ConnSecretKey};
+use crate::{
+    types::{DatetimeEncoding, EncodeError, EncodeErrorKind, PgType, Value},
+    ConnId, ConnSecretKey, PgFormat, ResultFormats, ResultFormatsMismatch,
+};
 
 const COMMAND_COMPLETE: u8 = b'C';
 const DATA_ROW: u8 = b'D';
@@ -20,6 +23,18 @@ const ERROR_RESPONSE: u8 = b'E';
 const SEVERITY: u8 = b'S';
 const CODE: u8 = b'C';
 const MESSAGE: u8 = b'M';
+const DETAIL: u8 = b'D';
+const HINT: u8 = b'H';
+const POSITION: u8 = b'P';
+const WHERE: u8 = b'W';
+const SCHEMA: u8 = b's';
+const TABLE: u8 = b't';
+const COLUMN: u8 = b'c';
+const DATA_TYPE: u8 = b'd';
+const CONSTRAINT: u8 = b'n';
+const FILE: u8 = b'F';
+const LINE: u8 = b'L';
+const ROUTINE: u8 = b'R';
 const EMPTY_QUERY_RESPONSE: u8 = b'I';
 const NOTICE_RESPONSE: u8 = b'N';
 const AUTHENTICATION: u8 = b'R';
@@ -32,15 +47,21 @@ const NO_DATA: u8 = b'n';
 const PARSE_COMPLETE: u8 = b'1';
 const BIND_COMPLETE: u8 = b'2';
 const CLOSE_COMPLETE: u8 = b'3';
+const PORTAL_SUSPENDED: u8 = b's';
+const COPY_IN_RESPONSE: u8 = b'G';
+const COPY_OUT_RESPONSE: u8 = b'H';
+const COPY_BOTH_RESPONSE: u8 = b'W';
+const FUNCTION_CALL_RESPONSE: u8 = b'V';
+const NEGOTIATE_PROTOCOL_VERSION: u8 = b'v';
 
 /// Backend PostgreSQL Wire Protocol messages
 /// see [Protocol Flow](https://www.postgresql.org/docs/current/protocol-flow.html)
 #[allow(dead_code)]
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum BackendMessage {
     /// A warning message has been issued. The frontend should display the message
     /// but continue listening for ReadyForQuery or ErrorResponse.
-    NoticeResponse,
+    NoticeResponse(ErrorFields),
     /// The frontend must now send a PasswordMessage containing the password in
     /// clear-text form. If this is the correct password, the server responds
     /// with an AuthenticationOk, otherwise it responds with an ErrorResponse.
@@ -53,16 +74,39 @@ pub enum BackendMessage {
     /// can be computed in SQL as concat('md5', md5(concat(md5(concat(password,
     /// username)), random-salt))). (Keep in mind the md5() function returns its
     /// result as a hex string.)
-    AuthenticationMd5Password,
+    AuthenticationMd5Password([u8; 4]),
     /// The authentication exchange is successfully completed.
     AuthenticationOk,
+    /// The frontend must now initiate a SASL negotiation, using one of the
+    /// listed SASL authentication mechanisms.
+    AuthenticationSasl(Vec<String>),
+    /// This message contains challenge data of the previous step of SASL
+    /// negotiation, e.g. `server-first-message` of `SCRAM-SHA-256`.
+    AuthenticationSaslContinue(Vec<u8>),
+    /// SASL authentication has completed with additional mechanism-specific
+    /// data for the client, e.g. `server-final-message` of `SCRAM-SHA-256`.
+    AuthenticationSaslFinal(Vec<u8>),
+    /// The frontend must now initiate a GSSAPI negotiation. The frontend will
+    /// send a GSSResponse message with the first part of the GSSAPI data
+    /// stream in response to this.
+    AuthenticationGss,
+    /// This message contains the response data from the previous step of
+    /// GSSAPI or SSPI negotiation. If the GSSAPI context is not complete
+    /// the frontend must send another GSSResponse message.
+    AuthenticationGssContinue(Vec<u8>),
     /// Identifies as cancellation key data. The frontend must save these values
     /// if it wishes to be able to issue CancelRequest messages later.
     BackendKeyData(ConnId, ConnSecretKey),
     /// Start-up is completed. The frontend can now issue commands.
+    /// Backend reports that it is not in a transaction block.
     ReadyForQuery,
+    /// The same as `ReadyForQuery` with explicit backend transaction status.
+    ReadyForQueryWithStatus(TransactionStatus),
     /// One of the set of rows returned by a SELECT, FETCH, etc query.
     DataRow(Vec<String>),
+    /// The same as `DataRow` with column values already encoded in text or
+    /// binary format. `None` represents NULL, see [BackendMessage::typed_data_row]
+    RawDataRow(Vec<Option<Vec<u8>>>),
     /// Indicates that rows are about to be returned in response to a SELECT, FETCH,
     /// etc query. The contents of this message describe the column layout of
     /// the rows. This will be followed by a DataRow message for each row being
@@ -72,9 +116,8 @@ pub enum BackendMessage {
     CommandComplete(String),
     /// An empty query string was recognized.
     EmptyQueryResponse,
-    /// An error has occurred. Contains (`Severity`, `Error Code`, `Error Message`)
-    /// all of them are optional
-    ErrorResponse(Option<&'static str>, Option<&'static str>, Option<String>),
+    /// An error has occurred.
+    ErrorResponse(ErrorFields),
     /// This message informs the frontend about the current (initial) setting of
     /// backend parameters, such as client_encoding or DateStyle
     ///
@@ -94,16 +137,77 @@ pub enum BackendMessage {
     /// This message informs the frontend about the previous `Close` frontend
     /// message is successful.
     CloseComplete,
+    /// Indicates that the `Execute` frontend message row limit was reached
+    /// while the portal still has rows to return. It is sent instead of
+    /// `CommandComplete`, which is sent only when the portal is exhausted or
+    /// when `Execute` has no row limit.
+    PortalSuspended,
+    /// Indicates that the backend is ready to copy data from the frontend to
+    /// a table. The frontend should then send zero or more CopyData messages.
+    CopyInResponse {
+        /// format of the whole copy data
+        overall_format: PgFormat,
+        /// format of each column in the copy data
+        column_formats: Vec<PgFormat>,
+    },
+    /// Indicates that the backend is ready to copy data from a table to the
+    /// frontend. It will then send zero or more CopyData messages.
+    CopyOutResponse {
+        /// format of the whole copy data
+        overall_format: PgFormat,
+        /// format of each column in the copy data
+        column_formats: Vec<PgFormat>,
+    },
+    /// Indicates that the backend starts bidirectional copy mode that is used
+    /// only by streaming replication. Both sides then may send CopyData messages.
+    CopyBothResponse {
+        /// format of the whole copy data
+        overall_format: PgFormat,
+        /// format of each column in the copy data
+        column_formats: Vec<PgFormat>,
+    },
+    /// Contains the result of the `FunctionCall` frontend message. `None`
+    /// indicates that the function returned NULL.
+    FunctionCallResponse(Option<Vec<u8>>),
+    /// Informs the frontend that the backend does not support the requested
+    /// minor protocol version or some of `_pq_.` protocol options of the
+    /// startup packet. It is sent before authentication starts.
+    NegotiateProtocolVersion {
+        /// newest minor protocol version supported by the backend for the
+        /// major protocol version requested by the frontend
+        newest_minor_version: i32,
+        /// protocol options that were not recognized by the backend
+        unrecognized_options: Vec<String>,
+    },
 }
 
 impl BackendMessage {
     /// returns binary representation of a backend message
     pub fn as_vec(&self) -> Vec<u8> {
         match self {
-            BackendMessage::NoticeResponse => vec![NOTICE_RESPONSE],
+            BackendMessage::NoticeResponse(fields) => fields.as_vec(NOTICE_RESPONSE),
             BackendMessage::AuthenticationCleartextPassword => vec![AUTHENTICATION, 0, 0, 0, 8, 0, 0, 0, 3],
-            BackendMessage::AuthenticationMd5Password => vec![AUTHENTICATION, 0, 0, 0, 12, 0, 0, 0, 5, 1, 1, 1, 1],
+            BackendMessage::AuthenticationMd5Password(salt) => {
+                [&[AUTHENTICATION, 0, 0, 0, 12, 0, 0, 0, 5][..], &salt[..]].concat()
+            }
             BackendMessage::AuthenticationOk => vec![AUTHENTICATION, 0, 0, 0, 8, 0, 0, 0, 0],
+            BackendMessage::AuthenticationSasl(mechanisms) => {
+                let mut buff = Vec::new();
+                buff.extend_from_slice(&10i32.to_be_bytes());
+                for mechanism in mechanisms {
+                    buff.extend_from_slice(mechanism.as_bytes());
+                    buff.push(0);
+                }
+                buff.push(0);
+                let mut message = vec![AUTHENTICATION];
+                message.extend_from_slice(&(4 + buff.len() as i32).to_be_bytes());
+                message.extend_from_slice(&buff);
+                message
+            }
+            BackendMessage::AuthenticationSaslContinue(data) => authentication_data(11, data),
+            BackendMessage::AuthenticationSaslFinal(data) => authentication_data(12, data),
+            BackendMessage::AuthenticationGss => vec![AUTHENTICATION, 0, 0, 0, 8, 0, 0, 0, 7],
+            BackendMessage::AuthenticationGssContinue(data) => authentication_data(8, data),
             BackendMessage::BackendKeyData(conn_id, secret_key) => {
                 let mut buff = vec![BACKEND_KEY_DATA, 0, 0, 0, 12];
                 buff.extend_from_slice(&conn_id.to_be_bytes());
@@ -111,30 +215,20 @@ impl BackendMessage {
                 buff
             }
             BackendMessage::ReadyForQuery => vec![READY_FOR_QUERY, 0, 0, 0, 5, EMPTY_QUERY_RESPONSE],
-            BackendMessage::DataRow(row) => {
-                let mut row_buff = Vec::new();
-                for field in row.iter() {
-                    row_buff.extend_from_slice(&(field.len() as i32).to_be_bytes());
-                    row_buff.extend_from_slice(field.as_str().as_bytes());
-                }
-                let mut len_buff = Vec::new();
-                len_buff.extend_from_slice(&[DATA_ROW]);
-                len_buff.extend_from_slice(&(6 + row_buff.len() as i32).to_be_bytes());
-                len_buff.extend_from_slice(&(row.len() as i16).to_be_bytes());
-                len_buff.extend_from_slice(&row_buff);
-                len_buff
-            }
+            BackendMessage::ReadyForQueryWithStatus(status) => vec![READY_FOR_QUERY, 0, 0, 0, 5, u8::from(*status)],
+            BackendMessage::DataRow(row) => data_row(row.iter().map(|field| Some(field.as_bytes()))),
+            BackendMessage::RawDataRow(row) => data_row(row.iter().map(Option::as_deref)),
             BackendMessage::RowDescription(description) => {
                 let mut buff = Vec::new();
                 for field in description.iter() {
                     buff.extend_from_slice(field.name.as_str().as_bytes());
                     buff.extend_from_slice(&[0]); // end of c string
-                    buff.extend_from_slice(&(0i32).to_be_bytes()); // table id
-                    buff.extend_from_slice(&(0i16).to_be_bytes()); // column id
+                    buff.extend_from_slice(&field.table_oid.to_be_bytes());
+                    buff.extend_from_slice(&field.column_attnum.to_be_bytes());
                     buff.extend_from_slice(&field.type_id.to_be_bytes());
                     buff.extend_from_slice(&field.type_size.to_be_bytes());
-                    buff.extend_from_slice(&(-1i32).to_be_bytes()); // type modifier
-                    buff.extend_from_slice(&0i16.to_be_bytes());
+                    buff.extend_from_slice(&field.type_modifier.to_be_bytes());
+                    buff.extend_from_slice(&i16::from(field.format).to_be_bytes());
                 }
                 let mut len_buff = Vec::new();
                 len_buff.extend_from_slice(&[ROW_DESCRIPTION]);
@@ -152,30 +246,7 @@ impl BackendMessage {
                 command_buff
             }
             BackendMessage::EmptyQueryResponse => vec![EMPTY_QUERY_RESPONSE, 0, 0, 0, 4],
-            BackendMessage::ErrorResponse(severity, code, message) => {
-                let mut error_response_buff = Vec::new();
-                error_response_buff.extend_from_slice(&[ERROR_RESPONSE]);
-                let mut message_buff = Vec::new();
-                if let Some(severity) = severity.as_ref() {
-                    message_buff.extend_from_slice(&[SEVERITY]);
-                    message_buff.extend_from_slice(severity.as_bytes());
-                    message_buff.extend_from_slice(&[0]);
-                }
-                if let Some(code) = code.as_ref() {
-                    message_buff.extend_from_slice(&[CODE]);
-                    message_buff.extend_from_slice(code.as_bytes());
-                    message_buff.extend_from_slice(&[0]);
-                }
-                if let Some(message) = message.as_ref() {
-                    message_buff.extend_from_slice(&[MESSAGE]);
-                    message_buff.extend_from_slice(message.as_bytes());
-                    message_buff.extend_from_slice(&[0]);
-                }
-                error_response_buff.extend_from_slice(&(message_buff.len() as i32 + 4 + 1).to_be_bytes());
-                error_response_buff.extend_from_slice(message_buff.as_ref());
-                error_response_buff.extend_from_slice(&[0]);
-                error_response_buff.to_vec()
-            }
+            BackendMessage::ErrorResponse(fields) => fields.as_vec(ERROR_RESPONSE),
             BackendMessage::ParameterStatus(name, value) => {
                 let mut parameter_status_buff = Vec::new();
                 parameter_status_buff.extend_from_slice(&[PARAMETER_STATUS]);
@@ -204,7 +275,418 @@ impl BackendMessage {
             BackendMessage::ParseComplete => vec![PARSE_COMPLETE, 0, 0, 0, 4],
             BackendMessage::BindComplete => vec![BIND_COMPLETE, 0, 0, 0, 4],
             BackendMessage::CloseComplete => vec![CLOSE_COMPLETE, 0, 0, 0, 4],
+            BackendMessage::PortalSuspended => vec![PORTAL_SUSPENDED, 0, 0, 0, 4],
+            BackendMessage::CopyInResponse {
+                overall_format,
+                column_formats,
+            } => copy_response(COPY_IN_RESPONSE, overall_format, column_formats),
+            BackendMessage::CopyOutResponse {
+                overall_format,
+                column_formats,
+            } => copy_response(COPY_OUT_RESPONSE, overall_format, column_formats),
+            BackendMessage::CopyBothResponse {
+                overall_format,
+                column_formats,
+            } => copy_response(COPY_BOTH_RESPONSE, overall_format, column_formats),
+            BackendMessage::FunctionCallResponse(result) => {
+                let mut buff = Vec::new();
+                buff.extend_from_slice(&[FUNCTION_CALL_RESPONSE]);
+                match result {
+                    Some(value) => {
+                        buff.extend_from_slice(&(8 + value.len() as i32).to_be_bytes());
+                        buff.extend_from_slice(&(value.len() as i32).to_be_bytes());
+                        buff.extend_from_slice(value);
+                    }
+                    None => {
+                        buff.extend_from_slice(&8i32.to_be_bytes());
+                        buff.extend_from_slice(&(-1i32).to_be_bytes());
+                    }
+                }
+                buff
+            }
+            BackendMessage::NegotiateProtocolVersion {
+                newest_minor_version,
+                unrecognized_options,
+            } => {
+                let mut buff = Vec::new();
+                buff.extend_from_slice(&newest_minor_version.to_be_bytes());
+                buff.extend_from_slice(&(unrecognized_options.len() as i32).to_be_bytes());
+                for option in unrecognized_options {
+                    buff.extend_from_slice(option.as_bytes());
+                    buff.push(0);
+                }
+                let mut message = vec![NEGOTIATE_PROTOCOL_VERSION];
+                message.extend_from_slice(&(4 + buff.len() as i32).to_be_bytes());
+                message.extend_from_slice(&buff);
+                message
+            }
+        }
+    }
+
+    /// Creates [BackendMessage::RawDataRow] encoding each of `values` as its column type
+    /// in its column format, see [ResultFormats::resolve]
+    pub fn typed_data_row<'e>(
+        values: &'e [Value],
+        types: &[PgType],
+        formats: &ResultFormats,
+    ) -> Result<BackendMessage, EncodeError<'e>> {
+        Self::typed_data_row_with_datetimes(values, types, formats, DatetimeEncoding::default())
+    }
+
+    /// Creates [BackendMessage::RawDataRow] as [BackendMessage::typed_data_row]
+    /// does, binary `timestamp` and `timestamptz` values are represented according
+    /// to `datetimes`, see [PgType::encode_binary_with_datetimes]
+    pub fn typed_data_row_with_datetimes<'e>(
+        values: &'e [Value],
+        types: &[PgType],
+        formats: &ResultFormats,
+        datetimes: DatetimeEncoding,
+    ) -> Result<BackendMessage, EncodeError<'e>> {
+        let column_count_mismatch = || {
+            EncodeError::from(EncodeErrorKind::ColumnCountMismatch {
+                values: values.len(),
+                types: types.len(),
+                formats: formats.as_slice().len(),
+            })
+        };
+        if values.len() != types.len() {
+            return Err(column_count_mismatch());
+        }
+        let formats = formats.resolve(values.len()).map_err(|_| column_count_mismatch())?;
+        let mut row = Vec::with_capacity(values.len());
+        for ((value, pg_type), format) in values.iter().zip(types.iter()).zip(formats) {
+            row.push(match (value, format) {
+                (Value::Null, _) => None,
+                (_, PgFormat::Text) => Some(pg_type.encode_text(value)?),
+                (_, PgFormat::Binary) => Some(pg_type.encode_binary_with_datetimes(value, datetimes)?),
+            });
+        }
+        Ok(BackendMessage::RawDataRow(row))
+    }
+}
+
+fn data_row<'f, F: ExactSizeIterator<Item = Option<&'f [u8]>>>(fields: F) -> Vec<u8> {
+    let mut row_buff = Vec::new();
+    row_buff.extend_from_slice(&(fields.len() as i16).to_be_bytes());
+    for field in fields {
+        match field {
+            Some(bytes) => {
+                row_buff.extend_from_slice(&(bytes.len() as i32).to_be_bytes());
+                row_buff.extend_from_slice(bytes);
+            }
+            None => row_buff.extend_from_slice(&(-1i32).to_be_bytes()),
+        }
+    }
+    let mut len_buff = Vec::new();
+    len_buff.extend_from_slice(&[DATA_ROW]);
+    len_buff.extend_from_slice(&(4 + row_buff.len() as i32).to_be_bytes());
+    len_buff.extend_from_slice(&row_buff);
+    len_buff
+}
+
+fn authentication_data(method: i32, data: &[u8]) -> Vec<u8> {
+    let mut buff = vec![AUTHENTICATION];
+    buff.extend_from_slice(&(8 + data.len() as i32).to_be_bytes());
+    buff.extend_from_slice(&method.to_be_bytes());
+    buff.extend_from_slice(data);
+    buff
+}
+
+fn copy_response(tag: u8, overall_format: &PgFormat, column_formats: &[PgFormat]) -> Vec<u8> {
+    let mut buff = Vec::new();
+    buff.extend_from_slice(&[tag]);
+    buff.extend_from_slice(&(7 + 2 * column_formats.len() as i32).to_be_bytes());
+    buff.extend_from_slice(&[i16::from(*overall_format) as u8]);
+    buff.extend_from_slice(&(column_formats.len() as i16).to_be_bytes());
+    for format in column_formats.iter() {
+        buff.extend_from_slice(&i16::from(*format).to_be_bytes());
+    }
+    buff
+}
+
+/// Backend transaction status reported by `ReadyForQuery` message
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransactionStatus {
+    /// Not in a transaction block
+    Idle,
+    /// In a transaction block
+    InTransaction,
+    /// In a failed transaction block, queries will be rejected until block is ended
+    Failed,
+}
+
+impl From<TransactionStatus> for u8 {
+    fn from(status: TransactionStatus) -> u8 {
+        match status {
+            TransactionStatus::Idle => b'I',
+            TransactionStatus::InTransaction => b'T',
+            TransactionStatus::Failed => b'E',
+        }
+    }
+}
+
+/// Fields of [BackendMessage::ErrorResponse] and [BackendMessage::NoticeResponse]
+/// messages, each of them is optional
+/// see [Error and Notice Message Fields](https://www.postgresql.org/docs/current/protocol-error-fields.html)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ErrorFields {
+    /// `ERROR`, `FATAL`, or `PANIC` for errors, or `WARNING`, `NOTICE`,
+    /// `DEBUG`, `INFO`, or `LOG` for notices
+    pub severity: Option<String>,
+    /// SQLSTATE code
+    pub code: Option<String>,
+    /// primary human-readable message
+    pub message: Option<String>,
+    /// secondary message carrying more detail about the problem
+    pub detail: Option<String>,
+    /// suggestion what to do about the problem
+    pub hint: Option<String>,
+    /// cursor position, measured in characters starting from 1, in the original query string
+    pub position: Option<String>,
+    /// context in which the error occurred
+    pub r#where: Option<String>,
+    /// name of the schema if the error was associated with a specific database object
+    pub schema: Option<String>,
+    /// name of the table if the error was associated with a specific table
+    pub table: Option<String>,
+    /// name of the column if the error was associated with a specific table column
+    pub column: Option<String>,
+    /// name of the data type if the error was associated with a specific data type
+    pub data_type: Option<String>,
+    /// name of the constraint if the error was associated with a specific constraint
+    pub constraint: Option<String>,
+    /// file name of the source-code location where the error was reported
+    pub file: Option<String>,
+    /// line number of the source-code location where the error was reported
+    pub line: Option<String>,
+    /// name of the source-code routine reporting the error
+    pub routine: Option<String>,
+}
+
+impl ErrorFields {
+    /// Creates fields with required `severity`, `code` and `message`
+    pub fn new<S: ToString, C: ToString, M: ToString>(severity: S, code: C, message: M) -> ErrorFields {
+        ErrorFields {
+            severity: Some(severity.to_string()),
+            code: Some(code.to_string()),
+            message: Some(message.to_string()),
+            ..ErrorFields::default()
+        }
+    }
+
+    /// Sets secondary message with details
+    pub fn with_detail<S: ToString>(mut self, detail: S) -> ErrorFields {
+        self.detail = Some(detail.to_string());
+        self
+    }
+
+    /// Sets suggestion what to do about the problem
+    pub fn with_hint<S: ToString>(mut self, hint: S) -> ErrorFields {
+        self.hint = Some(hint.to_string());
+        self
+    }
+
+    /// Sets position of the problem in the query string
+    pub fn with_position(mut self, position: u32) -> ErrorFields {
+        self.position = Some(position.to_string());
+        self
+    }
+
+    /// Sets context in which the error occurred
+    pub fn with_where<S: ToString>(mut self, r#where: S) -> ErrorFields {
+        self.r#where = Some(r#where.to_string());
+        self
+    }
+
+    /// Sets schema name of the database object
+    pub fn with_schema<S: ToString>(mut self, schema: S) -> ErrorFields {
+        self.schema = Some(schema.to_string());
+        self
+    }
+
+    /// Sets table name
+    pub fn with_table<S: ToString>(mut self, table: S) -> ErrorFields {
+        self.table = Some(table.to_string());
+        self
+    }
+
+    /// Sets column name
+    pub fn with_column<S: ToString>(mut self, column: S) -> ErrorFields {
+        self.column = Some(column.to_string());
+        self
+    }
+
+    /// Sets data type name
+    pub fn with_data_type<S: ToString>(mut self, data_type: S) -> ErrorFields {
+        self.data_type = Some(data_type.to_string());
+        self
+    }
+
+    /// Sets constraint name
+    pub fn with_constraint<S: ToString>(mut self, constraint: S) -> ErrorFields {
+        self.constraint = Some(constraint.to_string());
+        self
+    }
+
+    /// Sets source-code location where the error was reported
+    pub fn with_location<S: ToString>(mut self, file: S, line: u32) -> ErrorFields {
+        self.file = Some(file.to_string());
+        self.line = Some(line.to_string());
+        self
+    }
+
+    /// Sets source-code routine reporting the error
+    pub fn with_routine<S: ToString>(mut self, routine: S) -> ErrorFields {
+        self.routine = Some(routine.to_string());
+        self
+    }
+
+    fn as_vec(&self, tag: u8) -> Vec<u8> {
+        let fields = [
+            (SEVERITY, &self.severity),
+            (CODE, &self.code),
+            (MESSAGE, &self.message),
+            (DETAIL, &self.detail),
+            (HINT, &self.hint),
+            (POSITION, &self.position),
+            (WHERE, &self.r#where),
+            (SCHEMA, &self.schema),
+            (TABLE, &self.table),
+            (COLUMN, &self.column),
+            (DATA_TYPE, &self.data_type),
+            (CONSTRAINT, &self.constraint),
+            (FILE, &self.file),
+            (LINE, &self.line),
+            (ROUTINE, &self.routine),
+        ];
+        let mut message_buff = Vec::new();
+        for (field_type, value) in fields.iter() {
+            if let Some(value) = value {
+                message_buff.extend_from_slice(&[*field_type]);
+                message_buff.extend_from_slice(value.as_bytes());
+                message_buff.extend_from_slice(&[0]);
+            }
         }
+        let mut buff = Vec::new();
+        buff.extend_from_slice(&[tag]);
+        buff.extend_from_slice(&(message_buff.len() as i32 + 4 + 1).to_be_bytes());
+        buff.extend_from_slice(message_buff.as_ref());
+        buff.extend_from_slice(&[0]);
+        buff
+    }
+}
+
+/// Builders of [BackendMessage::CommandComplete] messages with command tags
+/// formatted the way PostgreSQL clients expect them
+pub struct CommandComplete;
+
+impl CommandComplete {
+    /// `INSERT oid rows` tag, `oid` is always `0` since PostgreSQL 12
+    pub fn insert(oid: u32, rows: u64) -> BackendMessage {
+        BackendMessage::CommandComplete(format!("INSERT {} {}", oid, rows))
+    }
+
+    /// `UPDATE rows` tag
+    pub fn update(rows: u64) -> BackendMessage {
+        BackendMessage::CommandComplete(format!("UPDATE {}", rows))
+    }
+
+    /// `DELETE rows` tag
+    pub fn delete(rows: u64) -> BackendMessage {
+        BackendMessage::CommandComplete(format!("DELETE {}", rows))
+    }
+
+    /// `SELECT rows` tag, is also used for `CREATE TABLE AS` command
+    pub fn select(rows: u64) -> BackendMessage {
+        BackendMessage::CommandComplete(format!("SELECT {}", rows))
+    }
+
+    /// `COPY rows` tag
+    pub fn copy(rows: u64) -> BackendMessage {
+        BackendMessage::CommandComplete(format!("COPY {}", rows))
+    }
+
+    /// Extracts the number of affected rows from a tag of
+    /// [BackendMessage::CommandComplete], tags without it and malformed ones
+    /// are [CommandTag::Other]
+    pub fn parse(tag: &str) -> CommandTag {
+        let mut parts = tag.split(' ');
+        let command = parts.next().unwrap_or_default();
+        let numbers = parts.map(str::parse::<u64>).collect::<Result<Vec<u64>, _>>();
+        match (command, numbers.as_deref()) {
+            ("INSERT", Ok(&[oid, rows])) if oid <= u32::MAX as u64 => CommandTag::Insert { oid: oid as u32, rows },
+            ("UPDATE", Ok(&[rows])) => CommandTag::Update { rows },
+            ("DELETE", Ok(&[rows])) => CommandTag::Delete { rows },
+            ("SELECT", Ok(&[rows])) => CommandTag::Select { rows },
+            ("COPY", Ok(&[rows])) => CommandTag::Copy { rows },
+            _ => CommandTag::Other(tag.to_owned()),
+        }
+    }
+}
+
+/// Command tag of [BackendMessage::CommandComplete] with the number of rows
+/// the command affected, see [CommandComplete::parse]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CommandTag {
+    /// `INSERT oid rows` tag
+    Insert {
+        /// `oid` of the inserted row, always `0` since PostgreSQL 12
+        oid: u32,
+        /// number of inserted rows
+        rows: u64,
+    },
+    /// `UPDATE rows` tag
+    Update {
+        /// number of updated rows
+        rows: u64,
+    },
+    /// `DELETE rows` tag
+    Delete {
+        /// number of deleted rows
+        rows: u64,
+    },
+    /// `SELECT rows` tag
+    Select {
+        /// number of selected rows
+        rows: u64,
+    },
+    /// `COPY rows` tag
+    Copy {
+        /// number of copied rows
+        rows: u64,
+    },
+    /// Any other tag, e.g. `BEGIN` or `CREATE TABLE`
+    Other(String),
+}
+
+/// Builder of [BackendMessage::RowDescription] messages
+pub struct RowDescription;
+
+impl RowDescription {
+    /// Describes columns of `(name, type, format)` that are not identified as
+    /// columns of a specific table
+    pub fn from_columns(columns: &[(&str, PgType, PgFormat)]) -> BackendMessage {
+        BackendMessage::RowDescription(
+            columns
+                .iter()
+                .map(|(name, pg_type, format)| ColumnMetadata::with_format(name, *pg_type, *format))
+                .collect(),
+        )
+    }
+
+    /// Describes columns of `(name, type)` in formats that client requested
+    /// with `Bind` message, see [ResultFormats::resolve]
+    pub fn with_result_formats(
+        columns: &[(&str, PgType)],
+        formats: &ResultFormats,
+    ) -> Result<BackendMessage, ResultFormatsMismatch> {
+        Ok(BackendMessage::RowDescription(
+            columns
+                .iter()
+                .zip(formats.resolve(columns.len())?)
+                .map(|((name, pg_type), format)| ColumnMetadata::with_format(name, *pg_type, format))
+                .collect(),
+        ))
     }
 }
 
@@ -214,19 +696,52 @@ impl BackendMessage {
 pub struct ColumnMetadata {
     /// name of the column that was specified in query
     pub name: String,
+    /// [Oid](crate::Oid) of the table if the column can be identified as a
+    /// column of a specific table; otherwise zero
+    pub table_oid: u32,
+    /// attribute number of the column if the column can be identified as a
+    /// column of a specific table; otherwise zero
+    pub column_attnum: i16,
     /// PostgreSQL data type id
     pub type_id: u32,
     /// PostgreSQL data type size
     pub type_size: i16,
+    /// type specific modifier, e.g. declared length of `varchar(n)`; `-1` if
+    /// the type has no modifier
+    pub type_modifier: i32,
+    /// format in which column values are sent to the client
+    pub format: PgFormat,
 }
 
 impl ColumnMetadata {
-    /// Creates new column metadata
+    /// Creates new column metadata which values are sent in text format
     pub fn new<S: ToString>(name: S, pg_type: PgType) -> ColumnMetadata {
+        Self::with_format(name, pg_type, PgFormat::Text)
+    }
+
+    /// Creates new column metadata which values are sent in specified format
+    pub fn with_format<S: ToString>(name: S, pg_type: PgType, format: PgFormat) -> ColumnMetadata {
+        Self::new_full(name, pg_type, format, 0, 0, -1)
+    }
+
+    /// Creates new column metadata that identifies the table column it
+    /// originates from
+    pub fn new_full<S: ToString>(
+        name: S,
+        pg_type: PgType,
+        format: PgFormat,
+        table_oid: u32,
+        column_attnum: i16,
+        type_modifier: i32,
+    ) -> ColumnMetadata {
         Self {
             name: name.to_string(),
+            table_oid,
+            column_attnum,
             type_id: pg_type.type_oid(),
             type_size: pg_type.type_len(),
+            type_modifier,
+            format,
         }
     }
 }
@@ -237,7 +752,35 @@ mod serializing_backend_messages {
 
     #[test]
     fn notice() {
-        assert_eq!(BackendMessage::NoticeResponse.as_vec(), vec![NOTICE_RESPONSE]);
+        assert_eq!(
+            BackendMessage::NoticeResponse(ErrorFields::new("NOTICE", "00000", "m")).as_vec(),
+            vec![
+                NOTICE_RESPONSE,
+                0,
+                0,
+                0,
+                23,
+                SEVERITY,
+                78,
+                79,
+                84,
+                73,
+                67,
+                69,
+                0,
+                CODE,
+                48,
+                48,
+                48,
+                48,
+                48,
+                0,
+                MESSAGE,
+                109,
+                0,
+                0
+            ]
+        );
     }
 
     #[test]
@@ -251,8 +794,8 @@ mod serializing_backend_messages {
     #[test]
     fn authentication_md5_password() {
         assert_eq!(
-            BackendMessage::AuthenticationMd5Password.as_vec(),
-            vec![AUTHENTICATION, 0, 0, 0, 12, 0, 0, 0, 5, 1, 1, 1, 1]
+            BackendMessage::AuthenticationMd5Password([1, 2, 3, 4]).as_vec(),
+            vec![AUTHENTICATION, 0, 0, 0, 12, 0, 0, 0, 5, 1, 2, 3, 4]
         )
     }
 
@@ -264,6 +807,79 @@ mod serializing_backend_messages {
         )
     }
 
+    #[test]
+    fn authentication_sasl() {
+        assert_eq!(
+            BackendMessage::AuthenticationSasl(vec!["SCRAM-SHA-256".to_owned()]).as_vec(),
+            [
+                &[AUTHENTICATION, 0, 0, 0, 23, 0, 0, 0, 10][..],
+                b"SCRAM-SHA-256",
+                &[0, 0]
+            ]
+            .concat()
+        )
+    }
+
+    #[test]
+    fn authentication_sasl_with_many_mechanisms() {
+        assert_eq!(
+            BackendMessage::AuthenticationSasl(vec!["SCRAM-SHA-256-PLUS".to_owned(), "SCRAM-SHA-256".to_owned()])
+                .as_vec(),
+            [
+                &[AUTHENTICATION, 0, 0, 0, 42, 0, 0, 0, 10][..],
+                b"SCRAM-SHA-256-PLUS",
+                &[0],
+                b"SCRAM-SHA-256",
+                &[0, 0]
+            ]
+            .concat()
+        )
+    }
+
+    #[test]
+    fn authentication_sasl_continue() {
+        assert_eq!(
+            BackendMessage::AuthenticationSaslContinue(b"r=nonce,s=salt,i=4096".to_vec()).as_vec(),
+            [
+                &[AUTHENTICATION, 0, 0, 0, 29, 0, 0, 0, 11][..],
+                b"r=nonce,s=salt,i=4096"
+            ]
+            .concat()
+        )
+    }
+
+    #[test]
+    fn authentication_sasl_final() {
+        assert_eq!(
+            BackendMessage::AuthenticationSaslFinal(b"v=signature".to_vec()).as_vec(),
+            [&[AUTHENTICATION, 0, 0, 0, 19, 0, 0, 0, 12][..], b"v=signature"].concat()
+        )
+    }
+
+    #[test]
+    fn authentication_sasl_final_without_data() {
+        assert_eq!(
+            BackendMessage::AuthenticationSaslFinal(vec![]).as_vec(),
+            vec![AUTHENTICATION, 0, 0, 0, 8, 0, 0, 0, 12]
+        )
+    }
+
+    #[test]
+    fn authentication_gss() {
+        assert_eq!(
+            BackendMessage::AuthenticationGss.as_vec(),
+            vec![AUTHENTICATION, 0, 0, 0, 8, 0, 0, 0, 7]
+        )
+    }
+
+    #[test]
+    fn authentication_gss_continue() {
+        assert_eq!(
+            BackendMessage::AuthenticationGssContinue(vec![0x60, 0x81, 0x01]).as_vec(),
+            vec![AUTHENTICATION, 0, 0, 0, 11, 0, 0, 0, 8, 0x60, 0x81, 0x01]
+        )
+    }
+
     #[test]
     fn backend_key_data() {
         assert_eq!(
@@ -315,6 +931,22 @@ mod serializing_backend_messages {
         )
     }
 
+    #[test]
+    fn ready_for_query_with_status() {
+        assert_eq!(
+            BackendMessage::ReadyForQueryWithStatus(TransactionStatus::Idle).as_vec(),
+            BackendMessage::ReadyForQuery.as_vec()
+        );
+        assert_eq!(
+            BackendMessage::ReadyForQueryWithStatus(TransactionStatus::InTransaction).as_vec(),
+            vec![READY_FOR_QUERY, 0, 0, 0, 5, b'T']
+        );
+        assert_eq!(
+            BackendMessage::ReadyForQueryWithStatus(TransactionStatus::Failed).as_vec(),
+            vec![READY_FOR_QUERY, 0, 0, 0, 5, b'E']
+        );
+    }
+
     #[test]
     fn data_row() {
         assert_eq!(
@@ -323,6 +955,112 @@ mod serializing_backend_messages {
         )
     }
 
+    #[test]
+    fn typed_data_row() {
+        let row = BackendMessage::typed_data_row(
+            &[Value::Int32(12), Value::Int32(258), Value::Null],
+            &[PgType::Integer, PgType::Integer, PgType::Integer],
+            &ResultFormats::from(vec![PgFormat::Text, PgFormat::Binary, PgFormat::Binary]),
+        );
+
+        assert_eq!(
+            row,
+            Ok(BackendMessage::RawDataRow(vec![
+                Some(b"12".to_vec()),
+                Some(vec![0, 0, 1, 2]),
+                None
+            ]))
+        );
+        assert_eq!(
+            row.unwrap().as_vec(),
+            vec![DATA_ROW, 0, 0, 0, 24, 0, 3, 0, 0, 0, 2, 49, 50, 0, 0, 0, 4, 0, 0, 1, 2, 255, 255, 255, 255]
+        );
+    }
+
+    #[test]
+    fn typed_data_row_single_format() {
+        assert_eq!(
+            BackendMessage::typed_data_row(
+                &[Value::Int16(1), Value::Bool(true)],
+                &[PgType::SmallInt, PgType::Bool],
+                &ResultFormats::from(vec![PgFormat::Binary]),
+            ),
+            Ok(BackendMessage::RawDataRow(vec![Some(vec![0, 1]), Some(vec![1])]))
+        );
+    }
+
+    #[test]
+    fn typed_data_row_default_format() {
+        assert_eq!(
+            BackendMessage::typed_data_row(&[Value::Bool(true)], &[PgType::Bool], &ResultFormats::default()),
+            Ok(BackendMessage::RawDataRow(vec![Some(b"t".to_vec())]))
+        );
+    }
+
+    #[test]
+    fn typed_data_row_with_float_datetimes() {
+        assert_eq!(
+            BackendMessage::typed_data_row_with_datetimes(
+                &[Value::Timestamp(1_500_000), Value::Timestamp(1_500_000)],
+                &[PgType::Timestamp, PgType::Timestamp],
+                &ResultFormats::from(vec![PgFormat::Binary, PgFormat::Text]),
+                DatetimeEncoding::Float,
+            ),
+            Ok(BackendMessage::RawDataRow(vec![
+                Some(1.5f64.to_be_bytes().to_vec()),
+                Some(b"2000-01-01 00:00:01.5".to_vec())
+            ]))
+        );
+    }
+
+    #[test]
+    fn typed_data_row_column_count_mismatch() {
+        assert_eq!(
+            BackendMessage::typed_data_row(
+                &[Value::Int32(1)],
+                &[PgType::Integer, PgType::Integer],
+                &ResultFormats::default()
+            ),
+            Err(EncodeError::from(EncodeErrorKind::ColumnCountMismatch {
+                values: 1,
+                types: 2,
+                formats: 0
+            }))
+        );
+    }
+
+    #[test]
+    fn typed_data_row_result_formats_mismatch() {
+        assert_eq!(
+            BackendMessage::typed_data_row(
+                &[Value::Int32(1)],
+                &[PgType::Integer],
+                &ResultFormats::from(vec![PgFormat::Binary, PgFormat::Text])
+            ),
+            Err(EncodeError::from(EncodeErrorKind::ColumnCountMismatch {
+                values: 1,
+                types: 1,
+                formats: 2
+            }))
+        );
+    }
+
+    #[test]
+    fn typed_data_row_type_mismatch() {
+        let values = [Value::Bool(true)];
+        assert_eq!(
+            BackendMessage::typed_data_row(
+                &values,
+                &[PgType::Integer],
+                &ResultFormats::from(vec![PgFormat::Binary])
+            ),
+            Err(EncodeError::from(EncodeErrorKind::TypeMismatch {
+                value: &values[0],
+                pg_type: PgType::Integer
+            }))
+        );
+    }
+
     #[test]
     fn row_description() {
         assert_eq!(
@@ -360,6 +1098,113 @@ mod serializing_backend_messages {
         );
     }
 
+    #[test]
+    fn row_description_with_table_column() {
+        assert_eq!(
+            BackendMessage::RowDescription(vec![ColumnMetadata::new_full(
+                "c1",
+                PgType::VarChar,
+                PgFormat::Text,
+                16384,
+                2,
+                14
+            )])
+            .as_vec(),
+            vec![
+                ROW_DESCRIPTION,
+                0,
+                0,
+                0,
+                27,
+                0,
+                1,
+                99,
+                49,
+                0,
+                0,
+                0,
+                64,
+                0,
+                0,
+                2,
+                0,
+                0,
+                4,
+                19,
+                255,
+                255,
+                0,
+                0,
+                0,
+                14,
+                0,
+                0
+            ]
+        );
+    }
+
+    #[test]
+    fn row_description_with_mixed_formats() {
+        assert_eq!(
+            BackendMessage::RowDescription(vec![
+                ColumnMetadata::new("c1", PgType::Integer),
+                ColumnMetadata::with_format("c2", PgType::Integer, PgFormat::Binary)
+            ])
+            .as_vec(),
+            vec![
+                ROW_DESCRIPTION,
+                0,
+                0,
+                0,
+                48,
+                0,
+                2,
+                99,
+                49,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                23,
+                0,
+                4,
+                255,
+                255,
+                255,
+                255,
+                0,
+                0,
+                99,
+                50,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                23,
+                0,
+                4,
+                255,
+                255,
+                255,
+                255,
+                0,
+                1
+            ]
+        );
+    }
+
     #[test]
     fn command_complete() {
         assert_eq!(
@@ -379,11 +1224,38 @@ mod serializing_backend_messages {
     #[test]
     fn error_response() {
         assert_eq!(
-            BackendMessage::ErrorResponse(None, None, None).as_vec(),
+            BackendMessage::ErrorResponse(ErrorFields::default()).as_vec(),
             vec![ERROR_RESPONSE, 0, 0, 0, 5, 0]
         )
     }
 
+    #[test]
+    fn error_response_with_detail_and_position() {
+        assert_eq!(
+            BackendMessage::ErrorResponse(
+                ErrorFields::new("ERROR", "42601", "syntax error")
+                    .with_detail("d")
+                    .with_position(8)
+            )
+            .as_vec(),
+            [
+                &[ERROR_RESPONSE, 0, 0, 0, 39][..],
+                &[SEVERITY],
+                b"ERROR\0",
+                &[CODE],
+                b"42601\0",
+                &[MESSAGE],
+                b"syntax error\0",
+                &[DETAIL],
+                b"d\0",
+                &[POSITION],
+                b"8\0",
+                &[0],
+            ]
+            .concat()
+        )
+    }
+
     #[test]
     fn parameter_description() {
         assert_eq!(
@@ -411,4 +1283,272 @@ mod serializing_backend_messages {
     fn close_complete() {
         assert_eq!(BackendMessage::CloseComplete.as_vec(), vec![CLOSE_COMPLETE, 0, 0, 0, 4])
     }
+
+    #[test]
+    fn portal_suspended() {
+        assert_eq!(
+            BackendMessage::PortalSuspended.as_vec(),
+            vec![PORTAL_SUSPENDED, 0, 0, 0, 4]
+        )
+    }
+
+    #[test]
+    fn copy_in_response() {
+        assert_eq!(
+            BackendMessage::CopyInResponse {
+                overall_format: PgFormat::Binary,
+                column_formats: vec![PgFormat::Binary, PgFormat::Text, PgFormat::Binary],
+            }
+            .as_vec(),
+            vec![COPY_IN_RESPONSE, 0, 0, 0, 13, 1, 0, 3, 0, 1, 0, 0, 0, 1]
+        )
+    }
+
+    #[test]
+    fn copy_out_response() {
+        assert_eq!(
+            BackendMessage::CopyOutResponse {
+                overall_format: PgFormat::Text,
+                column_formats: vec![PgFormat::Text, PgFormat::Binary],
+            }
+            .as_vec(),
+            vec![COPY_OUT_RESPONSE, 0, 0, 0, 11, 0, 0, 2, 0, 0, 0, 1]
+        )
+    }
+
+    #[test]
+    fn copy_both_response() {
+        assert_eq!(
+            BackendMessage::CopyBothResponse {
+                overall_format: PgFormat::Binary,
+                column_formats: vec![],
+            }
+            .as_vec(),
+            vec![COPY_BOTH_RESPONSE, 0, 0, 0, 7, 1, 0, 0]
+        )
+    }
+
+    #[test]
+    fn copy_out_response_without_columns() {
+        assert_eq!(
+            BackendMessage::CopyOutResponse {
+                overall_format: PgFormat::Text,
+                column_formats: vec![],
+            }
+            .as_vec(),
+            vec![COPY_OUT_RESPONSE, 0, 0, 0, 7, 0, 0, 0]
+        )
+    }
+
+    #[test]
+    fn function_call_response() {
+        assert_eq!(
+            BackendMessage::FunctionCallResponse(Some(vec![0, 0, 0, 42])).as_vec(),
+            vec![FUNCTION_CALL_RESPONSE, 0, 0, 0, 12, 0, 0, 0, 4, 0, 0, 0, 42]
+        )
+    }
+
+    #[test]
+    fn function_call_null_response() {
+        assert_eq!(
+            BackendMessage::FunctionCallResponse(None).as_vec(),
+            vec![FUNCTION_CALL_RESPONSE, 0, 0, 0, 8, 255, 255, 255, 255]
+        )
+    }
+
+    #[test]
+    fn negotiate_protocol_version() {
+        assert_eq!(
+            BackendMessage::NegotiateProtocolVersion {
+                newest_minor_version: 0,
+                unrecognized_options: vec!["_pq_.foo".to_owned(), "_pq_.bar".to_owned()],
+            }
+            .as_vec(),
+            [
+                &[NEGOTIATE_PROTOCOL_VERSION, 0, 0, 0, 30, 0, 0, 0, 0, 0, 0, 0, 2][..],
+                b"_pq_.foo",
+                &[0],
+                b"_pq_.bar",
+                &[0]
+            ]
+            .concat()
+        )
+    }
+
+    #[test]
+    fn negotiate_protocol_version_without_options() {
+        assert_eq!(
+            BackendMessage::NegotiateProtocolVersion {
+                newest_minor_version: 0,
+                unrecognized_options: vec![],
+            }
+            .as_vec(),
+            vec![NEGOTIATE_PROTOCOL_VERSION, 0, 0, 0, 12, 0, 0, 0, 0, 0, 0, 0, 0]
+        )
+    }
+}
+
+#[cfg(test)]
+mod row_description_builder {
+    use super::*;
+
+    #[test]
+    fn no_columns() {
+        assert_eq!(
+            RowDescription::from_columns(&[]),
+            BackendMessage::RowDescription(vec![])
+        )
+    }
+
+    #[test]
+    fn columns_in_different_formats() {
+        let built = RowDescription::from_columns(&[
+            ("id", PgType::BigInt, PgFormat::Binary),
+            ("name", PgType::Text, PgFormat::Text),
+            ("created", PgType::Date, PgFormat::Binary),
+        ]);
+        let by_hand = BackendMessage::RowDescription(vec![
+            ColumnMetadata::new_full("id", PgType::BigInt, PgFormat::Binary, 0, 0, -1),
+            ColumnMetadata::new_full("name", PgType::Text, PgFormat::Text, 0, 0, -1),
+            ColumnMetadata::new_full("created", PgType::Date, PgFormat::Binary, 0, 0, -1),
+        ]);
+
+        assert_eq!(built.as_vec(), by_hand.as_vec());
+        assert_eq!(built, by_hand);
+    }
+
+    #[test]
+    fn columns_in_result_formats() {
+        assert_eq!(
+            RowDescription::with_result_formats(
+                &[("id", PgType::BigInt), ("name", PgType::Text)],
+                &ResultFormats::from(vec![PgFormat::Binary])
+            ),
+            Ok(RowDescription::from_columns(&[
+                ("id", PgType::BigInt, PgFormat::Binary),
+                ("name", PgType::Text, PgFormat::Binary),
+            ]))
+        );
+        assert_eq!(
+            RowDescription::with_result_formats(&[("id", PgType::BigInt)], &ResultFormats::default()),
+            Ok(RowDescription::from_columns(&[("id", PgType::BigInt, PgFormat::Text)]))
+        );
+    }
+
+    #[test]
+    fn result_formats_mismatch() {
+        assert_eq!(
+            RowDescription::with_result_formats(
+                &[("id", PgType::BigInt)],
+                &ResultFormats::from(vec![PgFormat::Binary, PgFormat::Text])
+            )
+            .map_err(|error| error.to_string()),
+            Err("bind message has 2 result formats but query has 1 columns".to_owned())
+        );
+    }
+}
+
+#[cfg(test)]
+mod command_complete_tags {
+    use super::*;
+
+    #[test]
+    fn insert() {
+        assert_eq!(
+            CommandComplete::insert(0, 5),
+            BackendMessage::CommandComplete("INSERT 0 5".to_owned())
+        )
+    }
+
+    #[test]
+    fn update() {
+        assert_eq!(
+            CommandComplete::update(3),
+            BackendMessage::CommandComplete("UPDATE 3".to_owned())
+        )
+    }
+
+    #[test]
+    fn delete() {
+        assert_eq!(
+            CommandComplete::delete(0),
+            BackendMessage::CommandComplete("DELETE 0".to_owned())
+        )
+    }
+
+    #[test]
+    fn select() {
+        assert_eq!(
+            CommandComplete::select(1),
+            BackendMessage::CommandComplete("SELECT 1".to_owned())
+        )
+    }
+
+    #[test]
+    fn copy() {
+        assert_eq!(
+            CommandComplete::copy(u64::MAX),
+            BackendMessage::CommandComplete("COPY 18446744073709551615".to_owned())
+        )
+    }
+
+    #[test]
+    fn parse_insert() {
+        assert_eq!(
+            CommandComplete::parse("INSERT 0 5"),
+            CommandTag::Insert { oid: 0, rows: 5 }
+        )
+    }
+
+    #[test]
+    fn parse_select() {
+        assert_eq!(CommandComplete::parse("SELECT 10"), CommandTag::Select { rows: 10 })
+    }
+
+    #[test]
+    fn parse_row_counts() {
+        assert_eq!(CommandComplete::parse("UPDATE 3"), CommandTag::Update { rows: 3 });
+        assert_eq!(CommandComplete::parse("DELETE 0"), CommandTag::Delete { rows: 0 });
+        assert_eq!(
+            CommandComplete::parse("COPY 18446744073709551615"),
+            CommandTag::Copy { rows: u64::MAX }
+        );
+    }
+
+    #[test]
+    fn parse_without_rows() {
+        assert_eq!(CommandComplete::parse("BEGIN"), CommandTag::Other("BEGIN".to_owned()));
+        assert_eq!(
+            CommandComplete::parse("CREATE TABLE"),
+            CommandTag::Other("CREATE TABLE".to_owned())
+        );
+    }
+
+    #[test]
+    fn parse_malformed() {
+        for malformed in &[
+            "SELECT",
+            "SELECT x",
+            "INSERT 5",
+            "UPDATE 1 2",
+            "DELETE -1",
+            "INSERT 4294967296 1",
+            "",
+        ] {
+            assert_eq!(
+                CommandComplete::parse(malformed),
+                CommandTag::Other((*malformed).to_owned())
+            );
+        }
+    }
+
+    #[test]
+    fn parse_built_tags() {
+        match CommandComplete::insert(0, 7) {
+            BackendMessage::CommandComplete(tag) => {
+                assert_eq!(CommandComplete::parse(&tag), CommandTag::Insert { oid: 0, rows: 7 })
+            }
+            other => panic!("unexpected message {:?}", other),
+        }
+    }
 }
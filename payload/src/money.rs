@@ -0,0 +1,76 @@
+// Copyright 2020 - 2021 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{convert::TryFrom, str};
+
+const CURRENCY_SYMBOL: char = '$';
+const THOUSANDS_SEPARATOR: char = ',';
+const CENTS_PER_UNIT: i128 = 100;
+
+/// Parses `[-]$1,234.56` into number of cents. Currency symbol and thousands
+/// separators are optional, fractional part can have up to two digits
+pub(crate) fn parse_money(s: &str) -> Option<i64> {
+    let s = s.trim();
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let s = s.strip_prefix(CURRENCY_SYMBOL).unwrap_or(s);
+    let (whole, fraction) = match s.find('.') {
+        Some(pos) => (&s[..pos], &s[pos + 1..]),
+        None => (s, ""),
+    };
+    if !whole.starts_with(|c: char| c.is_ascii_digit())
+        || whole.ends_with(THOUSANDS_SEPARATOR)
+        || whole.contains(",,")
+        || !whole.chars().all(|c| c.is_ascii_digit() || c == THOUSANDS_SEPARATOR)
+        || fraction.len() > 2
+        || !fraction.chars().all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let units = whole
+        .chars()
+        .filter(|c| *c != THOUSANDS_SEPARATOR)
+        .collect::<String>()
+        .parse::<i128>()
+        .ok()?;
+    let cents = match fraction.len() {
+        0 => 0,
+        1 => fraction.parse::<i128>().ok()? * 10,
+        _ => fraction.parse::<i128>().ok()?,
+    };
+    let total = units.checked_mul(CENTS_PER_UNIT)?.checked_add(cents)?;
+    i64::try_from(if negative { -total } else { total }).ok()
+}
+
+/// Formats number of cents as `[-]$1,234.56`
+pub(crate) fn format_money(cents: i64) -> String {
+    let magnitude = (cents as i128).abs();
+    let units = (magnitude / CENTS_PER_UNIT).to_string();
+    let grouped = units
+        .as_bytes()
+        .rchunks(3)
+        .rev()
+        .map(|group| str::from_utf8(group).expect("ascii digits"))
+        .collect::<Vec<_>>()
+        .join(&THOUSANDS_SEPARATOR.to_string());
+    format!(
+        "{}{}{}.{:02}",
+        if cents < 0 { "-" } else { "" },
+        CURRENCY_SYMBOL,
+        grouped,
+        magnitude % CENTS_PER_UNIT
+    )
+}
@@ -0,0 +1,217 @@
+// Copyright 2020 - 2021 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryFrom;
+
+const NAN: &str = "NaN";
+/// Base of digits in binary representation
+const NBASE: i16 = 10_000;
+/// Decimal digits per one `NBASE` digit
+const DEC_DIGITS: usize = 4;
+const NUMERIC_POS: u16 = 0x0000;
+const NUMERIC_NEG: u16 = 0x4000;
+const NUMERIC_NAN: u16 = 0xC000;
+/// The same limit of exponent as PostgreSQL has
+const MAX_EXPONENT: i64 = 1_000;
+const HEADER_LEN: usize = 8;
+
+enum Numeric {
+    NaN,
+    Number {
+        negative: bool,
+        /// Integral digits without leading zeros
+        integral: String,
+        /// Fractional digits as they were written
+        fraction: String,
+    },
+}
+
+/// Parses `[+-]digits[.digits][e[+-]digits]` or `NaN`
+fn parse(s: &str) -> Option<Numeric> {
+    let s = s.trim();
+    if s.eq_ignore_ascii_case(NAN) {
+        return Some(Numeric::NaN);
+    }
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let (mantissa, exponent) = match s.find(['e', 'E']) {
+        Some(pos) => {
+            let exponent = &s[pos + 1..];
+            let digits = exponent.strip_prefix(|c| c == '+' || c == '-').unwrap_or(exponent);
+            if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            (
+                &s[..pos],
+                exponent.parse::<i64>().ok().filter(|e| e.abs() <= MAX_EXPONENT)?,
+            )
+        }
+        None => (s, 0),
+    };
+    let (integral, fraction) = match mantissa.find('.') {
+        Some(pos) => (&mantissa[..pos], &mantissa[pos + 1..]),
+        None => (mantissa, ""),
+    };
+    if (integral.is_empty() && fraction.is_empty())
+        || !integral.bytes().all(|b| b.is_ascii_digit())
+        || !fraction.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    let digits = format!("{}{}", integral, fraction);
+    let point = integral.len() as i64 + exponent;
+    let (integral, fraction) = if point <= 0 {
+        (String::new(), format!("{}{}", "0".repeat(-point as usize), digits))
+    } else if point as usize >= digits.len() {
+        (
+            format!("{}{}", digits, "0".repeat(point as usize - digits.len())),
+            String::new(),
+        )
+    } else {
+        (digits[..point as usize].to_owned(), digits[point as usize..].to_owned())
+    };
+    let integral = integral.trim_start_matches('0').to_owned();
+    let zero = integral.is_empty() && fraction.bytes().all(|b| b == b'0');
+    Some(Numeric::Number {
+        negative: negative && !zero,
+        integral,
+        fraction,
+    })
+}
+
+fn format(negative: bool, integral: &str, fraction: &str) -> String {
+    format!(
+        "{}{}{}{}",
+        if negative { "-" } else { "" },
+        if integral.is_empty() { "0" } else { integral },
+        if fraction.is_empty() { "" } else { "." },
+        fraction
+    )
+}
+
+/// Brings decimal notation of a number into the form PostgreSQL outputs,
+/// i.e. without exponent and leading zeros
+pub(crate) fn normalize_numeric(s: &str) -> Option<String> {
+    match parse(s)? {
+        Numeric::NaN => Some(NAN.to_owned()),
+        Numeric::Number {
+            negative,
+            integral,
+            fraction,
+        } => Some(format(negative, &integral, &fraction)),
+    }
+}
+
+/// Serializes decimal notation of a number into `NBASE` digits with header of
+/// digits count, weight of the first digit, sign and display scale. Trailing
+/// zeros of the fractional part are not kept
+pub(crate) fn encode_numeric(s: &str) -> Option<Vec<u8>> {
+    let (negative, integral, fraction) = match parse(s)? {
+        Numeric::NaN => return Some(header(0, 0, NUMERIC_NAN, 0)),
+        Numeric::Number {
+            negative,
+            integral,
+            fraction,
+        } => (negative, integral, fraction),
+    };
+    let fraction = fraction.trim_end_matches('0');
+    let integral_groups = integral.len().div_ceil(DEC_DIGITS);
+    let padded = format!(
+        "{}{}{}{}",
+        "0".repeat(integral_groups * DEC_DIGITS - integral.len()),
+        integral,
+        fraction,
+        "0".repeat((DEC_DIGITS - fraction.len() % DEC_DIGITS) % DEC_DIGITS)
+    );
+    let mut digits = padded
+        .as_bytes()
+        .chunks(DEC_DIGITS)
+        .map(|group| group.iter().fold(0, |acc, digit| acc * 10 + (digit - b'0') as i16))
+        .collect::<Vec<i16>>();
+    let mut weight = integral_groups as i64 - 1;
+    let leading_zeros = digits.iter().take_while(|digit| **digit == 0).count();
+    digits.drain(..leading_zeros);
+    weight -= leading_zeros as i64;
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    if digits.is_empty() {
+        weight = 0;
+    }
+    let mut buff = header(
+        i16::try_from(digits.len()).ok()?,
+        i16::try_from(weight).ok()?,
+        if negative { NUMERIC_NEG } else { NUMERIC_POS },
+        u16::try_from(fraction.len()).ok()?,
+    );
+    for digit in digits {
+        buff.extend_from_slice(&digit.to_be_bytes());
+    }
+    Some(buff)
+}
+
+fn header(ndigits: i16, weight: i16, sign: u16, dscale: u16) -> Vec<u8> {
+    [
+        ndigits.to_be_bytes(),
+        weight.to_be_bytes(),
+        sign.to_be_bytes(),
+        dscale.to_be_bytes(),
+    ]
+    .concat()
+}
+
+/// Deserializes `NBASE` digits into decimal notation with `dscale` fractional digits
+pub(crate) fn decode_numeric(raw: &[u8]) -> Option<String> {
+    let field = |index: usize| raw.get(index..index + 2).map(|bytes| [bytes[0], bytes[1]]);
+    let ndigits = i16::from_be_bytes(field(0)?);
+    let weight = i16::from_be_bytes(field(2)?) as i64;
+    let sign = u16::from_be_bytes(field(4)?);
+    let dscale = u16::from_be_bytes(field(6)?) as usize;
+    if ndigits < 0 || raw.len() != HEADER_LEN + 2 * ndigits as usize {
+        return None;
+    }
+    let digits = raw[HEADER_LEN..]
+        .chunks(2)
+        .map(|digit| i16::from_be_bytes([digit[0], digit[1]]))
+        .collect::<Vec<i16>>();
+    if digits.iter().any(|digit| !(0..NBASE).contains(digit)) {
+        return None;
+    }
+    let negative = match sign {
+        NUMERIC_NAN => return Some(NAN.to_owned()),
+        NUMERIC_POS => false,
+        NUMERIC_NEG => true,
+        _ => return None,
+    };
+    let digit = |index: i64| {
+        if index < 0 {
+            0
+        } else {
+            digits.get(index as usize).copied().unwrap_or(0)
+        }
+    };
+    let integral = (0..=weight)
+        .map(|index| format!("{:04}", digit(index)))
+        .collect::<String>();
+    let fraction_groups = dscale.div_ceil(DEC_DIGITS);
+    let mut fraction = (1..=fraction_groups as i64)
+        .map(|position| format!("{:04}", digit(weight + position)))
+        .collect::<String>();
+    fraction.truncate(dscale);
+    let integral = integral.trim_start_matches('0');
+    let zero = integral.is_empty() && fraction.bytes().all(|b| b == b'0');
+    Some(format(negative && !zero, integral, &fraction))
+}
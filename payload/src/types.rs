@@ -12,15 +12,25 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{Oid, PgFormat};
+use crate::{
+    datetime::{self, MICROS_PER_SECOND},
+    inet, json, money, numeric, Oid, PgFormat,
+};
 use std::{
+    borrow::Cow,
+    convert::{TryFrom, TryInto},
     fmt::{self, Display, Formatter},
     num::ParseIntError,
-    str::{self, Utf8Error},
+    str::{self, FromStr, Utf8Error},
 };
 
 const BOOL_TRUE: &[&str] = &["t", "tr", "tru", "true", "y", "ye", "yes", "on", "1"];
 const BOOL_FALSE: &[&str] = &["f", "fa", "fal", "fals", "false", "n", "no", "of", "off", "0"];
+/// Version of `jsonb` binary representation that prefixes JSON text
+const JSONB_VERSION: u8 = 1;
+/// Size of varlena header that PostgreSQL adds to the maximum length of
+/// `char(n)` and `varchar(n)` type modifiers
+const VARHDRSZ: i32 = 4;
 
 /// Represents an error if frontend sent [Oid] that is not supported
 #[derive(Debug, PartialEq)]
@@ -32,6 +42,79 @@ impl Display for NotSupportedOid {
     }
 }
 
+impl std::error::Error for NotSupportedOid {}
+
+/// Represents an error if type name is not known
+#[derive(Debug, PartialEq)]
+pub struct UnknownTypeName(String);
+
+impl Display for UnknownTypeName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "type \"{}\" does not exist", self.0)
+    }
+}
+
+/// An error which can be returned when encoding [Value](crate::types::Value)s into raw bytes
+#[derive(Debug, PartialEq)]
+pub struct EncodeError<'e> {
+    kind: EncodeErrorKind<'e>,
+}
+
+impl<'e> From<EncodeErrorKind<'e>> for EncodeError<'e> {
+    fn from(kind: EncodeErrorKind<'e>) -> EncodeError<'e> {
+        EncodeError { kind }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum EncodeErrorKind<'e> {
+    NullValue {
+        pg_type: PgType,
+    },
+    TypeMismatch {
+        value: &'e Value,
+        pg_type: PgType,
+    },
+    NotRectangularArray {
+        pg_type: PgType,
+    },
+    ColumnCountMismatch {
+        values: usize,
+        types: usize,
+        formats: usize,
+    },
+    MalformedValue {
+        value: &'e Value,
+        pg_type: PgType,
+    },
+}
+
+impl<'e> Display for EncodeError<'e> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            EncodeErrorKind::NullValue { pg_type } => {
+                write!(f, "NULL value of {} type does not have text representation", pg_type)
+            }
+            EncodeErrorKind::TypeMismatch { value, pg_type } => {
+                write!(f, "{:?} value can not be encoded as {} type", value, pg_type)
+            }
+            EncodeErrorKind::NotRectangularArray { pg_type } => write!(
+                f,
+                "multidimensional {} value must have sub-arrays with matching dimensions",
+                pg_type
+            ),
+            EncodeErrorKind::ColumnCountMismatch { values, types, formats } => write!(
+                f,
+                "row of {} values can not be encoded with {} column types and {} formats",
+                values, types, formats
+            ),
+            EncodeErrorKind::MalformedValue { value, pg_type } => {
+                write!(f, "{:?} is not a valid value of {} type", value, pg_type)
+            }
+        }
+    }
+}
+
 /// An error which can be returned when decoding [Value](crate::types::Value)s from raw bytes
 #[derive(Debug, PartialEq)]
 pub struct TypeValueDecodeError<'e> {
@@ -63,6 +146,37 @@ pub(crate) enum TypeValueDecodeErrorKind<'e> {
         source: &'e str,
         pg_type: PgType,
     },
+    InvalidSyntax {
+        source: &'e str,
+        pg_type: PgType,
+    },
+    InvalidJson {
+        source: &'e str,
+        pg_type: PgType,
+    },
+    UnsupportedJsonbVersion {
+        version: u8,
+    },
+    MalformedArray {
+        source: &'e [u8],
+        pg_type: PgType,
+    },
+    TimestampOutOfRange {
+        source: &'e [u8],
+        pg_type: PgType,
+    },
+    MalformedNumeric {
+        source: &'e [u8],
+    },
+    MalformedNetworkAddress {
+        source: &'e [u8],
+        pg_type: PgType,
+    },
+    StringTooLong {
+        length: usize,
+        max_length: usize,
+        pg_type: PgType,
+    },
 }
 
 impl<'e> Display for TypeValueDecodeError<'e> {
@@ -94,6 +208,46 @@ impl<'e> Display for TypeValueDecodeError<'e> {
                     pg_type, source, cause
                 )
             }
+            TypeValueDecodeErrorKind::InvalidSyntax { source, pg_type } => {
+                write!(f, "invalid input syntax for type {}: \"{}\"", pg_type, source)
+            }
+            TypeValueDecodeErrorKind::InvalidJson { source, pg_type } => {
+                write!(f, "invalid input syntax for type {}: \"{}\"", pg_type, source)
+            }
+            TypeValueDecodeErrorKind::UnsupportedJsonbVersion { version } => {
+                write!(f, "unsupported jsonb version number {}", version)
+            }
+            TypeValueDecodeErrorKind::MalformedArray { source, pg_type } => {
+                write!(
+                    f,
+                    "{} type can not be decoded from malformed array {:?}",
+                    pg_type, source
+                )
+            }
+            TypeValueDecodeErrorKind::TimestampOutOfRange { source, pg_type } => {
+                write!(f, "{} value {:?} is out of range", pg_type, source)
+            }
+            TypeValueDecodeErrorKind::MalformedNumeric { source } => {
+                write!(f, "numeric type can not be decoded from malformed value {:?}", source)
+            }
+            TypeValueDecodeErrorKind::MalformedNetworkAddress { source, pg_type } => {
+                write!(
+                    f,
+                    "{} type can not be decoded from malformed value {:?}",
+                    pg_type, source
+                )
+            }
+            TypeValueDecodeErrorKind::StringTooLong {
+                length,
+                max_length,
+                pg_type,
+            } => {
+                write!(
+                    f,
+                    "value of {} characters is too long for type {}({})",
+                    length, pg_type, max_length
+                )
+            }
         }
     }
 }
@@ -149,10 +303,170 @@ mod formatting {
             "integer type can not be parsed from \'1.0\'. The cause: \"invalid digit found in string\""
         )
     }
+
+    #[test]
+    fn invalid_syntax() {
+        assert_eq!(
+            TypeValueDecodeError::from(TypeValueDecodeErrorKind::InvalidSyntax {
+                source: "abc",
+                pg_type: PgType::Timestamp,
+            })
+            .to_string(),
+            "invalid input syntax for type timestamp without time zone: \"abc\""
+        )
+    }
+
+    #[test]
+    fn invalid_json() {
+        assert_eq!(
+            TypeValueDecodeError::from(TypeValueDecodeErrorKind::InvalidJson {
+                source: "{a}",
+                pg_type: PgType::Json
+            })
+            .to_string(),
+            "invalid input syntax for type json: \"{a}\""
+        )
+    }
+
+    #[test]
+    fn null_value() {
+        assert_eq!(
+            EncodeError::from(EncodeErrorKind::NullValue {
+                pg_type: PgType::Integer
+            })
+            .to_string(),
+            "NULL value of integer type does not have text representation"
+        )
+    }
+
+    #[test]
+    fn type_mismatch() {
+        assert_eq!(
+            EncodeError::from(EncodeErrorKind::TypeMismatch {
+                value: &Value::Int32(1),
+                pg_type: PgType::Bool
+            })
+            .to_string(),
+            "Int32(1) value can not be encoded as boolean type"
+        )
+    }
+
+    #[test]
+    fn not_rectangular_array() {
+        assert_eq!(
+            EncodeError::from(EncodeErrorKind::NotRectangularArray {
+                pg_type: PgType::Int4Array
+            })
+            .to_string(),
+            "multidimensional integer[] value must have sub-arrays with matching dimensions"
+        )
+    }
+
+    #[test]
+    fn column_count_mismatch() {
+        assert_eq!(
+            EncodeError::from(EncodeErrorKind::ColumnCountMismatch {
+                values: 2,
+                types: 3,
+                formats: 1
+            })
+            .to_string(),
+            "row of 2 values can not be encoded with 3 column types and 1 formats"
+        )
+    }
+
+    #[test]
+    fn unsupported_jsonb_version() {
+        assert_eq!(
+            TypeValueDecodeError::from(TypeValueDecodeErrorKind::UnsupportedJsonbVersion { version: 2 }).to_string(),
+            "unsupported jsonb version number 2"
+        )
+    }
+
+    #[test]
+    fn malformed_array() {
+        assert_eq!(
+            TypeValueDecodeError::from(TypeValueDecodeErrorKind::MalformedArray {
+                source: &[0, 0, 0, 1],
+                pg_type: PgType::Int4Array,
+            })
+            .to_string(),
+            "integer[] type can not be decoded from malformed array [0, 0, 0, 1]"
+        )
+    }
+
+    #[test]
+    fn timestamp_out_of_range() {
+        assert_eq!(
+            TypeValueDecodeError::from(TypeValueDecodeErrorKind::TimestampOutOfRange {
+                source: &[127, 240, 0, 0, 0, 0, 0, 0],
+                pg_type: PgType::Timestamp,
+            })
+            .to_string(),
+            "timestamp without time zone value [127, 240, 0, 0, 0, 0, 0, 0] is out of range"
+        )
+    }
+
+    #[test]
+    fn malformed_numeric() {
+        assert_eq!(
+            TypeValueDecodeError::from(TypeValueDecodeErrorKind::MalformedNumeric { source: &[0, 1] }).to_string(),
+            "numeric type can not be decoded from malformed value [0, 1]"
+        )
+    }
+
+    #[test]
+    fn malformed_network_address() {
+        assert_eq!(
+            TypeValueDecodeError::from(TypeValueDecodeErrorKind::MalformedNetworkAddress {
+                source: &[2, 33, 0, 4],
+                pg_type: PgType::Inet
+            })
+            .to_string(),
+            "inet type can not be decoded from malformed value [2, 33, 0, 4]"
+        )
+    }
+
+    #[test]
+    fn string_too_long() {
+        assert_eq!(
+            TypeValueDecodeError::from(TypeValueDecodeErrorKind::StringTooLong {
+                length: 11,
+                max_length: 10,
+                pg_type: PgType::VarChar
+            })
+            .to_string(),
+            "value of 11 characters is too long for type variable character(10)"
+        )
+    }
+
+    #[test]
+    fn malformed_value() {
+        assert_eq!(
+            EncodeError::from(EncodeErrorKind::MalformedValue {
+                value: &Value::Numeric("1.2.3".to_owned()),
+                pg_type: PgType::Numeric
+            })
+            .to_string(),
+            "Numeric(\"1.2.3\") is not a valid value of numeric type"
+        )
+    }
+}
+
+/// Binary representation of date and time values, it is advertised to clients by
+/// the `integer_datetimes` parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DatetimeEncoding {
+    /// Microseconds as 8-byte integers, the `integer_datetimes = on` form
+    #[default]
+    Integer,
+    /// Seconds as 8-byte floating point numbers, the `integer_datetimes = off` form
+    Float,
 }
 
 /// Represents PostgreSQL data type and methods to send over wire
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PgType {
     /// Represents PostgreSQL `smallint` (or `int2`) data type
     SmallInt,
@@ -160,14 +474,58 @@ pub enum PgType {
     Integer,
     /// Represents PostgreSQL `bigint` (or `int8`) data type
     BigInt,
-
-    /// Represents PostgreSQL `character(n)` (or `char(n)`) data type
+    /// Represents PostgreSQL `oid` data type of object identifiers
+    Oid,
+    /// Represents PostgreSQL `money` data type of currency amount with
+    /// fixed fractional precision
+    Money,
+    /// Represents PostgreSQL `numeric` (or `decimal`) data type of arbitrary
+    /// precision numbers
+    Numeric,
+
+    /// Represents PostgreSQL single-byte `"char"` data type of system catalogs
     Char,
+    /// Represents PostgreSQL `character(n)` (or `char(n)` or `bpchar`) data
+    /// type of blank-padded strings
+    BpChar,
     /// Represents PostgreSQL `character varying(n)` (or `varchar(n)`) data type
     VarChar,
+    /// Represents PostgreSQL `text` data type
+    Text,
+    /// Represents PostgreSQL `name` data type of identifiers in system catalogs
+    Name,
 
     /// Represents PostgreSQL `boolean` data type
     Bool,
+
+    /// Represents PostgreSQL `date` data type
+    Date,
+    /// Represents PostgreSQL `timestamp` (or `timestamp without time zone`) data type
+    Timestamp,
+    /// Represents PostgreSQL `timestamptz` (or `timestamp with time zone`) data type
+    TimestampTz,
+    /// Represents PostgreSQL `time` (or `time without time zone`) data type
+    Time,
+    /// Represents PostgreSQL `interval` data type
+    Interval,
+
+    /// Represents PostgreSQL `inet` data type of IPv4 or IPv6 host address
+    /// with optional netmask
+    Inet,
+    /// Represents PostgreSQL `cidr` data type of IPv4 or IPv6 network address
+    Cidr,
+
+    /// Represents PostgreSQL `json` data type
+    Json,
+    /// Represents PostgreSQL `jsonb` data type
+    Jsonb,
+
+    /// Represents PostgreSQL `integer[]` (or `int4[]`) data type
+    Int4Array,
+    /// Represents PostgreSQL `boolean[]` (or `bool[]`) data type
+    BoolArray,
+    /// Represents PostgreSQL `text[]` data type
+    TextArray,
 }
 
 impl PgType {
@@ -176,10 +534,28 @@ impl PgType {
         match self {
             PgType::Bool => 16,
             PgType::Char => 18,
+            PgType::BpChar => 1042,
             PgType::BigInt => 20,
             PgType::SmallInt => 21,
             PgType::Integer => 23,
+            PgType::Oid => 26,
+            PgType::Money => 790,
+            PgType::Numeric => 1700,
             PgType::VarChar => 1043,
+            PgType::Text => 25,
+            PgType::Name => 19,
+            PgType::Date => 1082,
+            PgType::Timestamp => 1114,
+            PgType::TimestampTz => 1184,
+            PgType::Time => 1083,
+            PgType::Interval => 1186,
+            PgType::Inet => 869,
+            PgType::Cidr => 650,
+            PgType::Json => 114,
+            PgType::Jsonb => 3802,
+            PgType::Int4Array => 1007,
+            PgType::BoolArray => 1000,
+            PgType::TextArray => 1009,
         }
     }
 
@@ -188,31 +564,295 @@ impl PgType {
         match self {
             PgType::Bool => 1,
             PgType::Char => 1,
+            PgType::BpChar => -1,
             PgType::BigInt => 8,
             PgType::SmallInt => 2,
             PgType::Integer => 4,
+            PgType::Oid => 4,
+            PgType::Money => 8,
+            PgType::Numeric => -1,
             PgType::VarChar => -1,
+            PgType::Text => -1,
+            PgType::Name => 64,
+            PgType::Date => 4,
+            PgType::Timestamp => 8,
+            PgType::TimestampTz => 8,
+            PgType::Time => 8,
+            PgType::Interval => 16,
+            PgType::Inet => -1,
+            PgType::Cidr => -1,
+            PgType::Json => -1,
+            PgType::Jsonb => -1,
+            PgType::Int4Array => -1,
+            PgType::BoolArray => -1,
+            PgType::TextArray => -1,
         }
     }
 
     /// Deserializes a value of this type from `raw` using the specified `format`.
     pub fn decode<'d>(&'d self, format: &'d PgFormat, raw: &'d [u8]) -> Result<Value, TypeValueDecodeError<'d>> {
-        match format {
-            PgFormat::Binary => self.decode_binary(raw).map_err(Into::into),
-            PgFormat::Text => self.decode_text(raw).map_err(Into::into),
+        self.decode_with_datetimes(format, raw, DatetimeEncoding::default())
+    }
+
+    /// Deserializes a value of this type as [PgType::decode] does, binary
+    /// `timestamp` and `timestamptz` values are interpreted according to `datetimes`
+    pub fn decode_with_datetimes<'d>(
+        &'d self,
+        format: &'d PgFormat,
+        raw: &'d [u8],
+        datetimes: DatetimeEncoding,
+    ) -> Result<Value, TypeValueDecodeError<'d>> {
+        match (format, self, datetimes) {
+            (PgFormat::Binary, PgType::Timestamp, DatetimeEncoding::Float)
+            | (PgFormat::Binary, PgType::TimestampTz, DatetimeEncoding::Float) => {
+                self.decode_float_timestamp(raw).map_err(Into::into)
+            }
+            (PgFormat::Binary, _, _) => self.decode_binary(raw).map_err(Into::into),
+            (PgFormat::Text, _, _) => self.decode_text(raw).map_err(Into::into),
+        }
+    }
+
+    /// Deserializes a value of this type as [PgType::decode] does and checks that
+    /// `char` and `varchar` string does not exceed the length of the `typmod`
+    /// type modifier, that is `typmod - 4` characters. `None` or negative
+    /// `typmod` means that length is not constrained
+    pub fn decode_with_typmod<'d>(
+        &'d self,
+        format: &'d PgFormat,
+        raw: &'d [u8],
+        typmod: Option<i32>,
+    ) -> Result<Value, TypeValueDecodeError<'d>> {
+        let value = self.decode(format, raw)?;
+        match (&value, typmod) {
+            (Value::String(s), Some(typmod))
+                if matches!(self, PgType::Char | PgType::BpChar | PgType::VarChar) && typmod >= VARHDRSZ =>
+            {
+                let length = s.chars().count();
+                let max_length = (typmod - VARHDRSZ) as usize;
+                if length > max_length {
+                    return Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::StringTooLong {
+                        length,
+                        max_length,
+                        pg_type: *self,
+                    }));
+                }
+                Ok(value)
+            }
+            _ => Ok(value),
+        }
+    }
+
+    /// Serializes `value` of this type into text format.
+    pub fn encode_text<'e>(&self, value: &'e Value) -> Result<Vec<u8>, EncodeError<'e>> {
+        let text = match (self, value) {
+            (_, Value::Null) => return Err(EncodeError::from(EncodeErrorKind::NullValue { pg_type: *self })),
+            (PgType::Bool, Value::Bool(true)) => "t".to_owned(),
+            (PgType::Bool, Value::Bool(false)) => "f".to_owned(),
+            (PgType::SmallInt, Value::Int16(v)) => v.to_string(),
+            (PgType::Integer, Value::Int32(v)) => v.to_string(),
+            (PgType::Oid, Value::Oid(v)) => v.to_string(),
+            (PgType::Money, Value::Money(v)) => money::format_money(*v),
+            (PgType::Numeric, Value::Numeric(v)) => v.clone(),
+            (PgType::BigInt, Value::Int64(v)) => v.to_string(),
+            (PgType::Char, Value::String(v))
+            | (PgType::BpChar, Value::String(v))
+            | (PgType::VarChar, Value::String(v))
+            | (PgType::Text, Value::String(v))
+            | (PgType::Name, Value::String(v)) => v.clone(),
+            (PgType::Date, Value::Date(date)) => match date {
+                Date::NegativeInfinity => "-infinity".to_owned(),
+                Date::Day { year, month, day } => datetime::format_date(*year as i64, *month, *day),
+                Date::Infinity => "infinity".to_owned(),
+            },
+            (PgType::Timestamp, Value::Timestamp(v)) => datetime::format_timestamp(*v),
+            (PgType::TimestampTz, Value::TimestampTz(v)) => datetime::format_timestamp_tz(*v),
+            (PgType::Time, Value::Time(v)) => datetime::format_time(*v),
+            (PgType::Interval, Value::Interval { months, days, micros }) => {
+                datetime::format_interval(*months, *days, *micros)
+            }
+            (PgType::Inet, Value::Inet { family, bits, addr, .. }) => inet::format_inet(*family, *bits, false, addr)
+                .ok_or_else(|| EncodeError::from(EncodeErrorKind::MalformedValue { value, pg_type: *self }))?,
+            (PgType::Cidr, Value::Inet { family, bits, addr, .. }) => inet::format_inet(*family, *bits, true, addr)
+                .ok_or_else(|| EncodeError::from(EncodeErrorKind::MalformedValue { value, pg_type: *self }))?,
+            (PgType::Json, Value::Json(v)) | (PgType::Jsonb, Value::Jsonb(v)) => v.clone(),
+            (PgType::Int4Array, Value::Array(items)) => Self::encode_text_array(&PgType::Integer, items)?,
+            (PgType::BoolArray, Value::Array(items)) => Self::encode_text_array(&PgType::Bool, items)?,
+            (PgType::TextArray, Value::Array(items)) => Self::encode_text_array(&PgType::Text, items)?,
+            _ => {
+                return Err(EncodeError::from(EncodeErrorKind::TypeMismatch {
+                    value,
+                    pg_type: *self,
+                }))
+            }
+        };
+        Ok(text.into_bytes())
+    }
+
+    /// Serializes `value` of this type into binary format.
+    pub fn encode_binary<'e>(&self, value: &'e Value) -> Result<Vec<u8>, EncodeError<'e>> {
+        match (self, value) {
+            (_, Value::Null) => Err(EncodeError::from(EncodeErrorKind::NullValue { pg_type: *self })),
+            (PgType::Bool, Value::Bool(v)) => Ok(vec![*v as u8]),
+            (PgType::SmallInt, Value::Int16(v)) => Ok(v.to_be_bytes().to_vec()),
+            (PgType::Integer, Value::Int32(v)) => Ok(v.to_be_bytes().to_vec()),
+            (PgType::Oid, Value::Oid(v)) => Ok(v.to_be_bytes().to_vec()),
+            (PgType::BigInt, Value::Int64(v))
+            | (PgType::Money, Value::Money(v))
+            | (PgType::Timestamp, Value::Timestamp(v))
+            | (PgType::TimestampTz, Value::TimestampTz(v))
+            | (PgType::Time, Value::Time(v)) => Ok(v.to_be_bytes().to_vec()),
+            (PgType::Char, Value::String(v))
+            | (PgType::BpChar, Value::String(v))
+            | (PgType::VarChar, Value::String(v))
+            | (PgType::Text, Value::String(v))
+            | (PgType::Name, Value::String(v))
+            | (PgType::Json, Value::Json(v)) => Ok(v.as_bytes().to_vec()),
+            (PgType::Jsonb, Value::Jsonb(v)) => Ok([&[JSONB_VERSION], v.as_bytes()].concat()),
+            (PgType::Date, Value::Date(date)) => date
+                .days()
+                .map(|days| days.to_be_bytes().to_vec())
+                .ok_or_else(|| EncodeError::from(EncodeErrorKind::MalformedValue { value, pg_type: *self })),
+            (PgType::Numeric, Value::Numeric(v)) => numeric::encode_numeric(v)
+                .ok_or_else(|| EncodeError::from(EncodeErrorKind::MalformedValue { value, pg_type: *self })),
+            (PgType::Interval, Value::Interval { months, days, micros }) => {
+                Ok([&micros.to_be_bytes()[..], &days.to_be_bytes(), &months.to_be_bytes()].concat())
+            }
+            (PgType::Inet, Value::Inet { family, bits, addr, .. }) => inet::encode_inet(*family, *bits, false, addr)
+                .ok_or_else(|| EncodeError::from(EncodeErrorKind::MalformedValue { value, pg_type: *self })),
+            (PgType::Cidr, Value::Inet { family, bits, addr, .. }) => inet::encode_inet(*family, *bits, true, addr)
+                .ok_or_else(|| EncodeError::from(EncodeErrorKind::MalformedValue { value, pg_type: *self })),
+            (PgType::Int4Array, Value::Array(items)) => self.encode_binary_array(&PgType::Integer, items),
+            (PgType::BoolArray, Value::Array(items)) => self.encode_binary_array(&PgType::Bool, items),
+            (PgType::TextArray, Value::Array(items)) => self.encode_binary_array(&PgType::Text, items),
+            _ => Err(EncodeError::from(EncodeErrorKind::TypeMismatch {
+                value,
+                pg_type: *self,
+            })),
+        }
+    }
+
+    /// Serializes `value` of this type into binary format as [PgType::encode_binary]
+    /// does, `timestamp` and `timestamptz` values are represented according to `datetimes`
+    pub fn encode_binary_with_datetimes<'e>(
+        &self,
+        value: &'e Value,
+        datetimes: DatetimeEncoding,
+    ) -> Result<Vec<u8>, EncodeError<'e>> {
+        match (self, value, datetimes) {
+            (PgType::Timestamp, Value::Timestamp(micros), DatetimeEncoding::Float)
+            | (PgType::TimestampTz, Value::TimestampTz(micros), DatetimeEncoding::Float) => {
+                Ok((*micros as f64 / MICROS_PER_SECOND as f64).to_be_bytes().to_vec())
+            }
+            _ => self.encode_binary(value),
         }
     }
 
+    fn encode_binary_array<'e>(&self, element: &PgType, items: &'e [Value]) -> Result<Vec<u8>, EncodeError<'e>> {
+        fn flatten<'e>(items: &'e [Value], dims: &[usize], elements: &mut Vec<&'e Value>) -> bool {
+            if items.len() != dims[0] {
+                return false;
+            }
+            for item in items {
+                match (item, dims.len()) {
+                    (Value::Array(items), depth) if depth > 1 => {
+                        if !flatten(items, &dims[1..], elements) {
+                            return false;
+                        }
+                    }
+                    (Value::Array(_), _) => return false,
+                    (_, 1) => elements.push(item),
+                    _ => return false,
+                }
+            }
+            true
+        }
+
+        let mut dims = vec![];
+        let mut current = items;
+        while !current.is_empty() {
+            dims.push(current.len());
+            match &current[0] {
+                Value::Array(items) => current = items,
+                _ => break,
+            }
+        }
+        let mut elements = vec![];
+        if !dims.is_empty() && !flatten(items, &dims, &mut elements) {
+            return Err(EncodeError::from(EncodeErrorKind::NotRectangularArray {
+                pg_type: *self,
+            }));
+        }
+
+        let has_null = elements.iter().any(|element| **element == Value::Null);
+        let mut buff = vec![];
+        buff.extend_from_slice(&(dims.len() as i32).to_be_bytes());
+        buff.extend_from_slice(&(has_null as i32).to_be_bytes());
+        buff.extend_from_slice(&element.type_oid().to_be_bytes());
+        for len in dims.iter() {
+            buff.extend_from_slice(&(*len as i32).to_be_bytes());
+            // lower bound of the dimension
+            buff.extend_from_slice(&1i32.to_be_bytes());
+        }
+        for item in elements {
+            if *item == Value::Null {
+                // As a special case, -1 indicates a NULL element.
+                buff.extend_from_slice(&(-1i32).to_be_bytes());
+            } else {
+                let encoded = element.encode_binary(item)?;
+                buff.extend_from_slice(&(encoded.len() as i32).to_be_bytes());
+                buff.extend_from_slice(&encoded);
+            }
+        }
+        Ok(buff)
+    }
+
+    fn encode_text_array<'e>(element: &PgType, items: &'e [Value]) -> Result<String, EncodeError<'e>> {
+        let mut encoded = vec![];
+        for item in items {
+            encoded.push(match item {
+                Value::Null => "NULL".to_owned(),
+                Value::Array(items) => Self::encode_text_array(element, items)?,
+                item => {
+                    let text = String::from_utf8(element.encode_text(item)?).expect("text representation is UTF-8");
+                    if needs_quotes(&text) {
+                        format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+                    } else {
+                        text
+                    }
+                }
+            });
+        }
+        Ok(format!("{{{}}}", encoded.join(",")))
+    }
+
     /// Returns the type corresponding to the provided [Oid], if the it is known.
     pub fn from_oid(oid: Oid) -> Result<Option<PgType>, NotSupportedOid> {
         match oid {
             0 => Ok(None),
             16 => Ok(Some(PgType::Bool)),
             18 => Ok(Some(PgType::Char)),
+            19 => Ok(Some(PgType::Name)),
             20 => Ok(Some(PgType::BigInt)),
             21 => Ok(Some(PgType::SmallInt)),
             23 => Ok(Some(PgType::Integer)),
+            25 => Ok(Some(PgType::Text)),
+            26 => Ok(Some(PgType::Oid)),
+            114 => Ok(Some(PgType::Json)),
+            650 => Ok(Some(PgType::Cidr)),
+            790 => Ok(Some(PgType::Money)),
+            869 => Ok(Some(PgType::Inet)),
+            1700 => Ok(Some(PgType::Numeric)),
+            1000 => Ok(Some(PgType::BoolArray)),
+            1007 => Ok(Some(PgType::Int4Array)),
+            1009 => Ok(Some(PgType::TextArray)),
+            1042 => Ok(Some(PgType::BpChar)),
             1043 => Ok(Some(PgType::VarChar)),
+            1082 => Ok(Some(PgType::Date)),
+            1083 => Ok(Some(PgType::Time)),
+            1114 => Ok(Some(PgType::Timestamp)),
+            1184 => Ok(Some(PgType::TimestampTz)),
+            1186 => Ok(Some(PgType::Interval)),
+            3802 => Ok(Some(PgType::Jsonb)),
             _ => Err(NotSupportedOid(oid)),
         }
     }
@@ -230,18 +870,61 @@ impl PgType {
                     Ok(Value::Bool(raw[0] != 0))
                 }
             }
-            PgType::Char | PgType::VarChar => str::from_utf8(raw)
+            PgType::Char | PgType::BpChar | PgType::VarChar | PgType::Text | PgType::Name => str::from_utf8(raw)
                 .map(|s| Value::String(s.into()))
                 .map_err(|cause| TypeValueDecodeErrorKind::CannotDecodeString { cause, source: raw }),
+            PgType::Json => match str::from_utf8(raw) {
+                Ok(s) => self.decode_json(s),
+                Err(cause) => Err(TypeValueDecodeErrorKind::CannotDecodeString { cause, source: raw }),
+            },
+            PgType::Jsonb => match raw.split_first() {
+                None => Err(TypeValueDecodeErrorKind::NotEnoughBytes {
+                    required_bytes: 1,
+                    source: raw,
+                    pg_type: *self,
+                }),
+                Some((&JSONB_VERSION, raw)) => match str::from_utf8(raw) {
+                    Ok(s) => self.decode_json(s),
+                    Err(cause) => Err(TypeValueDecodeErrorKind::CannotDecodeString { cause, source: raw }),
+                },
+                Some((&version, _)) => Err(TypeValueDecodeErrorKind::UnsupportedJsonbVersion { version }),
+            },
+            PgType::Numeric => numeric::decode_numeric(raw)
+                .map(Value::Numeric)
+                .ok_or(TypeValueDecodeErrorKind::MalformedNumeric { source: raw }),
+            PgType::Inet | PgType::Cidr => {
+                inet::decode_inet(raw, *self == PgType::Cidr).ok_or(TypeValueDecodeErrorKind::MalformedNetworkAddress {
+                    source: raw,
+                    pg_type: *self,
+                })
+            }
+            PgType::Int4Array => self.decode_binary_array(&PgType::Integer, raw),
+            PgType::BoolArray => self.decode_binary_array(&PgType::Bool, raw),
+            PgType::TextArray => self.decode_binary_array(&PgType::Text, raw),
+            PgType::Interval => {
+                if raw.len() < 16 {
+                    Err(TypeValueDecodeErrorKind::NotEnoughBytes {
+                        required_bytes: 16,
+                        source: raw,
+                        pg_type: *self,
+                    })
+                } else {
+                    Ok(Value::Interval {
+                        micros: i64::from_be_bytes([raw[0], raw[1], raw[2], raw[3], raw[4], raw[5], raw[6], raw[7]]),
+                        days: i32::from_be_bytes([raw[8], raw[9], raw[10], raw[11]]),
+                        months: i32::from_be_bytes([raw[12], raw[13], raw[14], raw[15]]),
+                    })
+                }
+            }
             PgType::SmallInt => {
-                if raw.len() < 4 {
+                if raw.len() < 2 {
                     Err(TypeValueDecodeErrorKind::NotEnoughBytes {
-                        required_bytes: 4,
+                        required_bytes: 2,
                         source: raw,
                         pg_type: *self,
                     })
                 } else {
-                    Ok(Value::Int16(i32::from_be_bytes([raw[0], raw[1], raw[2], raw[3]]) as i16))
+                    Ok(Value::Int16(i16::from_be_bytes([raw[0], raw[1]])))
                 }
             }
             PgType::Integer => {
@@ -255,43 +938,231 @@ impl PgType {
                     Ok(Value::Int32(i32::from_be_bytes([raw[0], raw[1], raw[2], raw[3]])))
                 }
             }
-            PgType::BigInt => {
-                if raw.len() < 8 {
-                    Err(TypeValueDecodeErrorKind::NotEnoughBytes {
+            PgType::Date => match raw.get(0..4).and_then(|bytes| bytes.try_into().ok()) {
+                Some(bytes) => Ok(Value::Date(Date::from_days(i32::from_be_bytes(bytes)))),
+                None => Err(TypeValueDecodeErrorKind::NotEnoughBytes {
+                    required_bytes: 4,
+                    source: raw,
+                    pg_type: *self,
+                }),
+            },
+            PgType::Oid => match raw.get(0..4).and_then(|bytes| bytes.try_into().ok()) {
+                Some(bytes) => Ok(Value::Oid(u32::from_be_bytes(bytes))),
+                None => Err(TypeValueDecodeErrorKind::NotEnoughBytes {
+                    required_bytes: 4,
+                    source: raw,
+                    pg_type: *self,
+                }),
+            },
+            PgType::BigInt | PgType::Money | PgType::Timestamp | PgType::TimestampTz | PgType::Time => {
+                match raw.get(0..8).and_then(|bytes| bytes.try_into().ok()) {
+                    Some(bytes) => {
+                        let value = i64::from_be_bytes(bytes);
+                        match self {
+                            PgType::Money => Ok(Value::Money(value)),
+                            PgType::Timestamp => Ok(Value::Timestamp(value)),
+                            PgType::TimestampTz => Ok(Value::TimestampTz(value)),
+                            PgType::Time => Ok(Value::Time(value)),
+                            _ => Ok(Value::Int64(value)),
+                        }
+                    }
+                    None => Err(TypeValueDecodeErrorKind::NotEnoughBytes {
                         required_bytes: 8,
                         source: raw,
                         pg_type: *self,
-                    })
-                } else {
-                    Ok(Value::Int64(i64::from_be_bytes([
-                        raw[0], raw[1], raw[2], raw[3], raw[4], raw[5], raw[6], raw[7],
-                    ])))
+                    }),
                 }
             }
         }
     }
 
-    fn decode_text<'d>(&'d self, raw: &'d [u8]) -> Result<Value, TypeValueDecodeErrorKind<'d>> {
-        let s = match str::from_utf8(raw) {
-            Ok(s) => s,
-            Err(cause) => return Err(TypeValueDecodeErrorKind::CannotDecodeString { cause, source: raw }),
+    fn decode_float_timestamp<'d>(&'d self, raw: &'d [u8]) -> Result<Value, TypeValueDecodeErrorKind<'d>> {
+        let seconds = match raw.get(0..8).and_then(|bytes| bytes.try_into().ok()) {
+            Some(bytes) => f64::from_be_bytes(bytes),
+            None => {
+                return Err(TypeValueDecodeErrorKind::NotEnoughBytes {
+                    required_bytes: 8,
+                    source: raw,
+                    pg_type: *self,
+                })
+            }
         };
-
+        let micros = (seconds * MICROS_PER_SECOND as f64).round();
+        if !micros.is_finite() || micros < i64::MIN as f64 || micros >= i64::MAX as f64 {
+            return Err(TypeValueDecodeErrorKind::TimestampOutOfRange {
+                source: raw,
+                pg_type: *self,
+            });
+        }
         match self {
-            PgType::Bool => {
-                let v = s.trim().to_lowercase();
-                if BOOL_TRUE.contains(&v.as_str()) {
-                    Ok(Value::Bool(true))
-                } else if BOOL_FALSE.contains(&v.as_str()) {
-                    Ok(Value::Bool(false))
-                } else {
-                    Err(TypeValueDecodeErrorKind::CannotParseBool { source: s })
-                }
+            PgType::TimestampTz => Ok(Value::TimestampTz(micros as i64)),
+            _ => Ok(Value::Timestamp(micros as i64)),
+        }
+    }
+
+    fn decode_binary_array<'d>(
+        &'d self,
+        element: &'static PgType,
+        raw: &'d [u8],
+    ) -> Result<Value, TypeValueDecodeErrorKind<'d>> {
+        fn read_i32(raw: &[u8], pos: &mut usize) -> Option<i32> {
+            let bytes = raw.get(*pos..*pos + 4)?;
+            *pos += 4;
+            Some(i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        }
+
+        fn nest(mut elements: Vec<Value>, dims: &[usize]) -> Value {
+            if dims.len() <= 1 || elements.is_empty() {
+                return Value::Array(elements);
             }
-            PgType::Char => Ok(Value::String(s.into())),
-            PgType::VarChar => Ok(Value::String(s.into())),
-            PgType::SmallInt => {
-                s.trim()
+            let size = elements.len() / dims[0];
+            let mut arrays = Vec::with_capacity(dims[0]);
+            while !elements.is_empty() {
+                let rest = elements.split_off(size);
+                arrays.push(nest(elements, &dims[1..]));
+                elements = rest;
+            }
+            Value::Array(arrays)
+        }
+
+        let malformed = || TypeValueDecodeErrorKind::MalformedArray {
+            source: raw,
+            pg_type: *self,
+        };
+        let mut pos = 0;
+        let ndim = read_i32(raw, &mut pos).ok_or_else(malformed)?;
+        let _has_null = read_i32(raw, &mut pos).ok_or_else(malformed)?;
+        let element_oid = read_i32(raw, &mut pos).ok_or_else(malformed)? as Oid;
+        if ndim < 0 || element_oid != element.type_oid() {
+            return Err(malformed());
+        }
+
+        let mut dims = vec![];
+        for _ in 0..ndim {
+            let len = read_i32(raw, &mut pos).ok_or_else(malformed)?;
+            let _lower_bound = read_i32(raw, &mut pos).ok_or_else(malformed)?;
+            if len < 0 {
+                return Err(malformed());
+            }
+            dims.push(len as usize);
+        }
+
+        let total = if dims.is_empty() {
+            0
+        } else {
+            dims.iter()
+                .try_fold(1usize, |total, len| total.checked_mul(*len))
+                .ok_or_else(malformed)?
+        };
+        let mut elements = vec![];
+        for _ in 0..total {
+            let len = read_i32(raw, &mut pos).ok_or_else(malformed)?;
+            if len == -1 {
+                // As a special case, -1 indicates a NULL element.
+                elements.push(Value::Null);
+            } else if len < 0 {
+                return Err(malformed());
+            } else {
+                let bytes = raw.get(pos..pos + len as usize).ok_or_else(malformed)?;
+                pos += len as usize;
+                elements.push(element.decode_binary(bytes)?);
+            }
+        }
+        if pos != raw.len() {
+            return Err(malformed());
+        }
+
+        Ok(nest(elements, &dims))
+    }
+
+    fn decode_text_array<'d>(
+        &'d self,
+        element: &'static PgType,
+        s: &'d str,
+    ) -> Result<Value, TypeValueDecodeErrorKind<'d>> {
+        let items = match s.trim().strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            Some(items) => items,
+            None => {
+                return Err(TypeValueDecodeErrorKind::InvalidSyntax {
+                    source: s,
+                    pg_type: *self,
+                })
+            }
+        };
+        if items.trim().is_empty() {
+            return Ok(Value::Array(vec![]));
+        }
+        let invalid_syntax = || TypeValueDecodeErrorKind::InvalidSyntax {
+            source: s,
+            pg_type: *self,
+        };
+        split_text_array(items)
+            .ok_or_else(invalid_syntax)?
+            .into_iter()
+            .map(|item| match item {
+                None => Ok(Value::Null),
+                Some(Cow::Borrowed(item)) => element.decode_text(item.as_bytes()),
+                Some(Cow::Owned(item)) => match element {
+                    PgType::Char | PgType::BpChar | PgType::VarChar | PgType::Text | PgType::Name => {
+                        Ok(Value::String(item))
+                    }
+                    _ => Err(invalid_syntax()),
+                },
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array)
+    }
+
+    fn decode_json<'d>(&self, s: &'d str) -> Result<Value, TypeValueDecodeErrorKind<'d>> {
+        if !json::is_valid(s) {
+            Err(TypeValueDecodeErrorKind::InvalidJson {
+                source: s,
+                pg_type: *self,
+            })
+        } else if *self == PgType::Jsonb {
+            Ok(Value::Jsonb(s.into()))
+        } else {
+            Ok(Value::Json(s.into()))
+        }
+    }
+
+    fn decode_text<'d>(&'d self, raw: &'d [u8]) -> Result<Value, TypeValueDecodeErrorKind<'d>> {
+        let s = match str::from_utf8(raw) {
+            Ok(s) => s,
+            Err(cause) => return Err(TypeValueDecodeErrorKind::CannotDecodeString { cause, source: raw }),
+        };
+
+        match self {
+            PgType::Bool => {
+                let v = s.trim().to_lowercase();
+                if BOOL_TRUE.contains(&v.as_str()) {
+                    Ok(Value::Bool(true))
+                } else if BOOL_FALSE.contains(&v.as_str()) {
+                    Ok(Value::Bool(false))
+                } else {
+                    Err(TypeValueDecodeErrorKind::CannotParseBool { source: s })
+                }
+            }
+            PgType::Char => Ok(Value::String(s.into())),
+            PgType::BpChar | PgType::VarChar | PgType::Text | PgType::Name => Ok(Value::String(s.into())),
+            PgType::Json | PgType::Jsonb => self.decode_json(s),
+            PgType::Inet | PgType::Cidr => {
+                inet::parse_inet(s, *self == PgType::Cidr).ok_or(TypeValueDecodeErrorKind::InvalidSyntax {
+                    source: s,
+                    pg_type: *self,
+                })
+            }
+            PgType::Int4Array => self.decode_text_array(&PgType::Integer, s),
+            PgType::BoolArray => self.decode_text_array(&PgType::Bool, s),
+            PgType::TextArray => self.decode_text_array(&PgType::Text, s),
+            PgType::Interval => datetime::parse_interval(s)
+                .map(|(months, days, micros)| Value::Interval { months, days, micros })
+                .ok_or(TypeValueDecodeErrorKind::InvalidSyntax {
+                    source: s,
+                    pg_type: *self,
+                }),
+            PgType::SmallInt => {
+                s.trim()
                     .parse()
                     .map(Value::Int16)
                     .map_err(|cause| TypeValueDecodeErrorKind::CannotParseInt {
@@ -310,6 +1181,15 @@ impl PgType {
                         pg_type: *self,
                     })
             }
+            PgType::Oid => s
+                .trim()
+                .parse()
+                .map(Value::Oid)
+                .map_err(|cause| TypeValueDecodeErrorKind::CannotParseInt {
+                    cause,
+                    source: s,
+                    pg_type: *self,
+                }),
             PgType::BigInt => {
                 s.trim()
                     .parse()
@@ -320,34 +1200,333 @@ impl PgType {
                         pg_type: *self,
                     })
             }
+            PgType::Money => money::parse_money(s)
+                .map(Value::Money)
+                .ok_or(TypeValueDecodeErrorKind::InvalidSyntax {
+                    source: s,
+                    pg_type: *self,
+                }),
+            PgType::Numeric => {
+                numeric::normalize_numeric(s)
+                    .map(Value::Numeric)
+                    .ok_or(TypeValueDecodeErrorKind::InvalidSyntax {
+                        source: s,
+                        pg_type: *self,
+                    })
+            }
+            PgType::Date => match s.trim() {
+                "-infinity" => Ok(Value::Date(Date::NegativeInfinity)),
+                "infinity" => Ok(Value::Date(Date::Infinity)),
+                date => datetime::parse_date(date)
+                    .and_then(|days| i32::try_from(days).ok())
+                    .map(|days| Value::Date(Date::from_days(days)))
+                    .ok_or(TypeValueDecodeErrorKind::InvalidSyntax {
+                        source: s,
+                        pg_type: *self,
+                    }),
+            },
+            PgType::Timestamp => datetime::parse_timestamp(s)
+                .map(|(local, _offset)| Value::Timestamp(local))
+                .ok_or(TypeValueDecodeErrorKind::InvalidSyntax {
+                    source: s,
+                    pg_type: *self,
+                }),
+            PgType::TimestampTz => datetime::parse_timestamp(s)
+                .and_then(|(local, offset)| local.checked_sub(offset.unwrap_or(0) * MICROS_PER_SECOND))
+                .map(Value::TimestampTz)
+                .ok_or(TypeValueDecodeErrorKind::InvalidSyntax {
+                    source: s,
+                    pg_type: *self,
+                }),
+            PgType::Time => {
+                datetime::parse_time(s.trim())
+                    .map(Value::Time)
+                    .ok_or(TypeValueDecodeErrorKind::InvalidSyntax {
+                        source: s,
+                        pg_type: *self,
+                    })
+            }
+        }
+    }
+}
+
+/// Splits items of one dimensional array text form without surrounding braces.
+/// `None` represents unquoted `NULL`. Quoted items may contain commas, braces
+/// and `\"` or `\\` escapes, they are borrowed unless escapes are unescaped
+fn split_text_array(items: &str) -> Option<Vec<Option<Cow<'_, str>>>> {
+    let mut result = vec![];
+    let mut rest = items.trim_start();
+    loop {
+        let item = match rest.strip_prefix('"') {
+            Some(quoted) => {
+                let mut unescaped: Option<String> = None;
+                let mut end = None;
+                let mut chars = quoted.char_indices();
+                while let Some((index, c)) = chars.next() {
+                    match c {
+                        '"' => {
+                            end = Some(index);
+                            break;
+                        }
+                        '\\' => {
+                            let (_, escaped) = chars.next()?;
+                            unescaped
+                                .get_or_insert_with(|| quoted[..index].to_owned())
+                                .push(escaped);
+                        }
+                        c => {
+                            if let Some(unescaped) = unescaped.as_mut() {
+                                unescaped.push(c);
+                            }
+                        }
+                    }
+                }
+                let end = end?;
+                rest = quoted[end + 1..].trim_start();
+                Some(match unescaped {
+                    Some(unescaped) => Cow::Owned(unescaped),
+                    None => Cow::Borrowed(&quoted[..end]),
+                })
+            }
+            None => {
+                let end = rest.find(',').unwrap_or(rest.len());
+                let item = rest[..end].trim();
+                rest = &rest[end..];
+                if item.is_empty() || item.contains(['"', '{', '}', '\\']) {
+                    return None;
+                }
+                if item.eq_ignore_ascii_case("NULL") {
+                    None
+                } else {
+                    Some(Cow::Borrowed(item))
+                }
+            }
+        };
+        result.push(item);
+        match rest.strip_prefix(',') {
+            Some(next) => rest = next.trim_start(),
+            None if rest.is_empty() => return Some(result),
+            None => return None,
         }
     }
 }
 
+/// Whether array element has to be quoted in array text form
+fn needs_quotes(item: &str) -> bool {
+    item.is_empty()
+        || item.eq_ignore_ascii_case("NULL")
+        || item.contains(|c: char| c == '{' || c == '}' || c == ',' || c == '"' || c == '\\' || c.is_whitespace())
+}
+
 impl Display for PgType {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             PgType::Bool => write!(f, "boolean"),
-            PgType::Char => write!(f, "character"),
+            PgType::Char | PgType::BpChar => write!(f, "character"),
             PgType::BigInt => write!(f, "bigint"),
             PgType::SmallInt => write!(f, "smallint"),
             PgType::Integer => write!(f, "integer"),
+            PgType::Oid => write!(f, "oid"),
+            PgType::Money => write!(f, "money"),
+            PgType::Numeric => write!(f, "numeric"),
             PgType::VarChar => write!(f, "variable character"),
+            PgType::Text => write!(f, "text"),
+            PgType::Name => write!(f, "name"),
+            PgType::Date => write!(f, "date"),
+            PgType::Timestamp => write!(f, "timestamp without time zone"),
+            PgType::TimestampTz => write!(f, "timestamp with time zone"),
+            PgType::Time => write!(f, "time without time zone"),
+            PgType::Interval => write!(f, "interval"),
+            PgType::Inet => write!(f, "inet"),
+            PgType::Cidr => write!(f, "cidr"),
+            PgType::Json => write!(f, "json"),
+            PgType::Jsonb => write!(f, "jsonb"),
+            PgType::Int4Array => write!(f, "integer[]"),
+            PgType::BoolArray => write!(f, "boolean[]"),
+            PgType::TextArray => write!(f, "text[]"),
+        }
+    }
+}
+
+impl FromStr for PgType {
+    type Err = UnknownTypeName;
+
+    /// Parses type name with its common aliases, e.g. `int4`, `integer` and `int`
+    fn from_str(name: &str) -> Result<PgType, UnknownTypeName> {
+        match name.trim().to_lowercase().as_str() {
+            "bool" | "boolean" => Ok(PgType::Bool),
+            "char" | "character" => Ok(PgType::Char),
+            "int8" | "bigint" => Ok(PgType::BigInt),
+            "int2" | "smallint" => Ok(PgType::SmallInt),
+            "int4" | "int" | "integer" => Ok(PgType::Integer),
+            "oid" => Ok(PgType::Oid),
+            "money" => Ok(PgType::Money),
+            "numeric" | "decimal" => Ok(PgType::Numeric),
+            "varchar" | "character varying" | "variable character" => Ok(PgType::VarChar),
+            "text" => Ok(PgType::Text),
+            "name" => Ok(PgType::Name),
+            "bpchar" => Ok(PgType::BpChar),
+            "date" => Ok(PgType::Date),
+            "timestamp" | "timestamp without time zone" => Ok(PgType::Timestamp),
+            "timestamptz" | "timestamp with time zone" => Ok(PgType::TimestampTz),
+            "time" | "time without time zone" => Ok(PgType::Time),
+            "interval" => Ok(PgType::Interval),
+            "inet" => Ok(PgType::Inet),
+            "cidr" => Ok(PgType::Cidr),
+            "json" => Ok(PgType::Json),
+            "jsonb" => Ok(PgType::Jsonb),
+            "int4[]" | "int[]" | "integer[]" => Ok(PgType::Int4Array),
+            "bool[]" | "boolean[]" => Ok(PgType::BoolArray),
+            "text[]" => Ok(PgType::TextArray),
+            _ => Err(UnknownTypeName(name.to_owned())),
         }
     }
 }
 
 /// Represents PostgreSQL data values sent and received over wire
+///
+/// All values are `Eq` and `Hash`. Floating point values, once supported,
+/// have to be compared and hashed by their bit patterns to keep it so
 #[allow(missing_docs)]
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "value"))]
 pub enum Value {
     Null,
     Bool(bool),
     Int16(i16),
     Int32(i32),
     Int64(i64),
+    /// Object identifier, unlike integers it is unsigned
+    Oid(u32),
+    /// Currency amount in the smallest currency unit, i.e. cents
+    Money(i64),
+    /// Arbitrary precision number in decimal notation or `NaN`
+    Numeric(String),
     /// Supports only UTF-8 encoding
     String(String),
+    /// Calendar date without time of day
+    Date(Date),
+    /// Number of microseconds since `2000-01-01 00:00:00` without time zone
+    Timestamp(i64),
+    /// Number of microseconds since `2000-01-01 00:00:00 UTC`
+    TimestampTz(i64),
+    /// Number of microseconds since midnight
+    Time(i64),
+    /// Time span, months and days are kept apart since their length varies
+    Interval {
+        months: i32,
+        days: i32,
+        micros: i64,
+    },
+    /// IPv4 or IPv6 address of `inet` or `cidr` type. `family` is `2` for
+    /// IPv4 and `3` for IPv6, `bits` is netmask length and `addr` holds 4 or
+    /// 16 bytes of the address. Type of the column, rather than `is_cidr`,
+    /// determines how the value is encoded
+    Inet {
+        family: u8,
+        bits: u8,
+        is_cidr: bool,
+        addr: Vec<u8>,
+    },
+    /// Syntactically valid JSON document
+    Json(String),
+    /// Syntactically valid JSON document of `jsonb` type
+    Jsonb(String),
+    /// Elements of one dimensional array, multidimensional arrays are
+    /// represented as arrays of arrays
+    Array(Vec<Value>),
+}
+
+/// Day of proleptic Gregorian calendar or one of the infinite `date` values.
+/// Infinite dates are sent over wire as `i32::MIN` and `i32::MAX` number of days
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Date {
+    /// `-infinity`, that is earlier than any other date
+    NegativeInfinity,
+    /// Year is astronomical, i.e. `0` is 1 BC
+    #[allow(missing_docs)]
+    Day { year: i32, month: u32, day: u32 },
+    /// `infinity`, that is later than any other date
+    Infinity,
+}
+
+impl Date {
+    /// Converts number of days since `2000-01-01`
+    pub fn from_days(days: i32) -> Date {
+        match days {
+            i32::MIN => Date::NegativeInfinity,
+            i32::MAX => Date::Infinity,
+            days => {
+                let (year, month, day) = datetime::civil_from_days(days as i64);
+                Date::Day {
+                    year: year as i32,
+                    month,
+                    day,
+                }
+            }
+        }
+    }
+
+    /// Number of days since `2000-01-01`, `None` if the date is not valid or
+    /// can not be represented by PostgreSQL
+    pub fn days(&self) -> Option<i32> {
+        match self {
+            Date::NegativeInfinity => Some(i32::MIN),
+            Date::Day { year, month, day } => datetime::checked_days_from_civil(*year as i64, *month, *day)
+                .and_then(|days| i32::try_from(days).ok())
+                .filter(|days| *days != i32::MIN && *days != i32::MAX),
+            Date::Infinity => Some(i32::MAX),
+        }
+    }
+}
+
+impl Value {
+    /// Returns `true` if the value is `Null`
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    /// Returns `bool` if the value is `Bool`, otherwise `None`
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns `i16` if the value is `Int16`, otherwise `None`
+    pub fn as_i16(&self) -> Option<i16> {
+        match self {
+            Value::Int16(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns `i32` if the value is `Int32`, otherwise `None`
+    pub fn as_i32(&self) -> Option<i32> {
+        match self {
+            Value::Int32(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns `i64` if the value is `Int64`, otherwise `None`
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Int64(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns string slice if the value is `String`, otherwise `None`
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) => Some(value.as_str()),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -380,6 +1559,12 @@ mod tests {
             assert_eq!(PgType::from_oid(PgType::Char.type_oid()), Ok(Some(PgType::Char)));
         }
 
+        #[test]
+        fn blank_padded_character() {
+            assert_eq!(PgType::BpChar.type_oid(), 1042);
+            assert_eq!(PgType::from_oid(1042), Ok(Some(PgType::BpChar)));
+        }
+
         #[test]
         fn big_int() {
             assert_eq!(PgType::BigInt.type_oid(), 20);
@@ -401,11 +1586,125 @@ mod tests {
             assert_eq!(PgType::from_oid(PgType::Integer.type_oid()), Ok(Some(PgType::Integer)));
         }
 
+        #[test]
+        fn oid() {
+            assert_eq!(PgType::Oid.type_oid(), 26);
+            assert_eq!(PgType::from_oid(26), Ok(Some(PgType::Oid)));
+        }
+
+        #[test]
+        fn money() {
+            assert_eq!(PgType::Money.type_oid(), 790);
+            assert_eq!(PgType::from_oid(790), Ok(Some(PgType::Money)));
+        }
+
+        #[test]
+        fn numeric() {
+            assert_eq!(PgType::Numeric.type_oid(), 1700);
+            assert_eq!(PgType::from_oid(1700), Ok(Some(PgType::Numeric)));
+        }
+
         #[test]
         fn variable_characters() {
             assert_eq!(PgType::VarChar.type_oid(), 1043);
             assert_eq!(PgType::from_oid(PgType::VarChar.type_oid()), Ok(Some(PgType::VarChar)));
         }
+
+        #[test]
+        fn text() {
+            assert_eq!(PgType::Text.type_oid(), 25);
+            assert_eq!(PgType::from_oid(25), Ok(Some(PgType::Text)));
+        }
+
+        #[test]
+        fn name() {
+            assert_eq!(PgType::Name.type_oid(), 19);
+            assert_eq!(PgType::from_oid(19), Ok(Some(PgType::Name)));
+        }
+
+        #[test]
+        fn timestamp() {
+            assert_eq!(PgType::Timestamp.type_oid(), 1114);
+            assert_eq!(
+                PgType::from_oid(PgType::Timestamp.type_oid()),
+                Ok(Some(PgType::Timestamp))
+            );
+        }
+
+        #[test]
+        fn timestamp_with_time_zone() {
+            assert_eq!(PgType::TimestampTz.type_oid(), 1184);
+            assert_eq!(
+                PgType::from_oid(PgType::TimestampTz.type_oid()),
+                Ok(Some(PgType::TimestampTz))
+            );
+        }
+
+        #[test]
+        fn date() {
+            assert_eq!(PgType::Date.type_oid(), 1082);
+            assert_eq!(PgType::from_oid(PgType::Date.type_oid()), Ok(Some(PgType::Date)));
+        }
+
+        #[test]
+        fn time() {
+            assert_eq!(PgType::Time.type_oid(), 1083);
+            assert_eq!(PgType::from_oid(PgType::Time.type_oid()), Ok(Some(PgType::Time)));
+        }
+
+        #[test]
+        fn interval() {
+            assert_eq!(PgType::Interval.type_oid(), 1186);
+            assert_eq!(
+                PgType::from_oid(PgType::Interval.type_oid()),
+                Ok(Some(PgType::Interval))
+            );
+        }
+
+        #[test]
+        fn inet() {
+            assert_eq!(PgType::Inet.type_oid(), 869);
+            assert_eq!(PgType::from_oid(869), Ok(Some(PgType::Inet)));
+        }
+
+        #[test]
+        fn cidr() {
+            assert_eq!(PgType::Cidr.type_oid(), 650);
+            assert_eq!(PgType::from_oid(650), Ok(Some(PgType::Cidr)));
+        }
+
+        #[test]
+        fn json() {
+            assert_eq!(PgType::Json.type_oid(), 114);
+            assert_eq!(PgType::from_oid(PgType::Json.type_oid()), Ok(Some(PgType::Json)));
+        }
+
+        #[test]
+        fn jsonb() {
+            assert_eq!(PgType::Jsonb.type_oid(), 3802);
+            assert_eq!(PgType::from_oid(PgType::Jsonb.type_oid()), Ok(Some(PgType::Jsonb)));
+        }
+
+        #[test]
+        fn int4_array() {
+            assert_eq!(PgType::Int4Array.type_oid(), 1007);
+            assert_eq!(
+                PgType::from_oid(PgType::Int4Array.type_oid()),
+                Ok(Some(PgType::Int4Array))
+            );
+        }
+
+        #[test]
+        fn bool_array() {
+            assert_eq!(PgType::BoolArray.type_oid(), 1000);
+            assert_eq!(PgType::from_oid(1000), Ok(Some(PgType::BoolArray)));
+        }
+
+        #[test]
+        fn text_array() {
+            assert_eq!(PgType::TextArray.type_oid(), 1009);
+            assert_eq!(PgType::from_oid(1009), Ok(Some(PgType::TextArray)));
+        }
     }
 
     #[cfg(test)]
@@ -422,6 +1721,11 @@ mod tests {
             assert_eq!(PgType::Char.type_len(), 1);
         }
 
+        #[test]
+        fn blank_padded_character() {
+            assert_eq!(PgType::BpChar.type_len(), -1);
+        }
+
         #[test]
         fn big_int() {
             assert_eq!(PgType::BigInt.type_len(), 8);
@@ -438,185 +1742,346 @@ mod tests {
         }
 
         #[test]
-        fn variable_characters() {
-            assert_eq!(PgType::VarChar.type_len(), -1);
+        fn oid() {
+            assert_eq!(PgType::Oid.type_len(), 4);
         }
-    }
-
-    #[cfg(test)]
-    mod type_string_representation {
-        use super::*;
 
         #[test]
-        fn boolean() {
-            assert_eq!(PgType::Bool.to_string(), "boolean".to_string());
+        fn money() {
+            assert_eq!(PgType::Money.type_len(), 8);
         }
 
         #[test]
-        fn character() {
-            assert_eq!(PgType::Char.to_string(), "character".to_string());
+        fn numeric() {
+            assert_eq!(PgType::Numeric.type_len(), -1);
         }
 
         #[test]
-        fn big_int() {
-            assert_eq!(PgType::BigInt.to_string(), "bigint".to_string());
+        fn variable_characters() {
+            assert_eq!(PgType::VarChar.type_len(), -1);
         }
 
         #[test]
-        fn small_int() {
-            assert_eq!(PgType::SmallInt.to_string(), "smallint".to_string());
+        fn text() {
+            assert_eq!(PgType::Text.type_len(), -1);
         }
 
         #[test]
-        fn integer() {
-            assert_eq!(PgType::Integer.to_string(), "integer".to_string());
+        fn name() {
+            assert_eq!(PgType::Name.type_len(), 64);
         }
 
         #[test]
-        fn variable_characters() {
-            assert_eq!(PgType::VarChar.to_string(), "variable character".to_string());
+        fn timestamp() {
+            assert_eq!(PgType::Timestamp.type_len(), 8);
         }
-    }
-
-    #[cfg(test)]
-    mod binary_decoding {
-        use super::*;
 
         #[test]
-        fn decode_true() {
-            assert_eq!(PgType::Bool.decode(&PgFormat::Binary, &[1]), Ok(Value::Bool(true)));
+        fn timestamp_with_time_zone() {
+            assert_eq!(PgType::TimestampTz.type_len(), 8);
         }
 
         #[test]
-        fn decode_false() {
-            assert_eq!(PgType::Bool.decode(&PgFormat::Binary, &[0]), Ok(Value::Bool(false)));
+        fn date() {
+            assert_eq!(PgType::Date.type_len(), 4);
         }
 
         #[test]
-        fn error_decode_bool() {
-            assert_eq!(
-                PgType::Bool.decode(&PgFormat::Binary, &[]),
-                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::NotEnoughBytes {
-                    required_bytes: 1,
-                    source: &[],
-                    pg_type: PgType::Bool
-                }))
-            );
+        fn time() {
+            assert_eq!(PgType::Time.type_len(), 8);
         }
 
         #[test]
-        fn decode_char() {
-            assert_eq!(
-                PgType::Char.decode(&PgFormat::Binary, &[97, 98, 99]),
-                Ok(Value::String("abc".into()))
-            );
+        fn interval() {
+            assert_eq!(PgType::Interval.type_len(), 16);
         }
 
         #[test]
-        fn decode_varchar() {
-            assert_eq!(
-                PgType::VarChar.decode(&PgFormat::Binary, &[97, 98, 99]),
-                Ok(Value::String("abc".into()))
-            );
+        fn inet() {
+            assert_eq!(PgType::Inet.type_len(), -1);
+            assert_eq!(PgType::Cidr.type_len(), -1);
         }
 
         #[test]
-        fn error_decode_string() {
-            let non_utf_code = 0x96;
-            assert_eq!(
-                PgType::Char.decode(&PgFormat::Binary, &[non_utf_code]),
-                Err(TypeValueDecodeError::from(
-                    TypeValueDecodeErrorKind::CannotDecodeString {
-                        cause: str::from_utf8(&[non_utf_code]).unwrap_err(),
-                        source: &[non_utf_code]
-                    }
-                ))
-            );
+        fn json() {
+            assert_eq!(PgType::Json.type_len(), -1);
         }
 
         #[test]
-        fn decode_smallint() {
-            assert_eq!(
-                PgType::SmallInt.decode(&PgFormat::Binary, &[0, 0, 0, 1]),
-                Ok(Value::Int16(1))
-            );
+        fn jsonb() {
+            assert_eq!(PgType::Jsonb.type_len(), -1);
         }
 
         #[test]
-        fn error_decode_smallint() {
-            assert_eq!(
-                PgType::SmallInt.decode(&PgFormat::Binary, &[0, 0, 1]),
-                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::NotEnoughBytes {
-                    required_bytes: 4,
-                    source: &[0, 0, 1],
-                    pg_type: PgType::SmallInt
-                }))
-            );
+        fn int4_array() {
+            assert_eq!(PgType::Int4Array.type_len(), -1);
         }
 
         #[test]
-        fn decode_integer() {
-            assert_eq!(
-                PgType::Integer.decode(&PgFormat::Binary, &[0, 0, 0, 1]),
-                Ok(Value::Int32(1))
-            );
+        fn bool_array() {
+            assert_eq!(PgType::BoolArray.type_len(), -1);
         }
 
         #[test]
-        fn error_decode_integer() {
-            assert_eq!(
-                PgType::Integer.decode(&PgFormat::Binary, &[0, 0, 1]),
-                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::NotEnoughBytes {
-                    required_bytes: 4,
-                    source: &[0, 0, 1],
-                    pg_type: PgType::Integer
-                }))
-            );
+        fn text_array() {
+            assert_eq!(PgType::TextArray.type_len(), -1);
         }
+    }
+
+    #[cfg(test)]
+    mod type_string_representation {
+        use super::*;
 
         #[test]
-        fn decode_bigint() {
-            assert_eq!(
-                PgType::BigInt.decode(&PgFormat::Binary, &[0, 0, 0, 0, 0, 0, 0, 1]),
-                Ok(Value::Int64(1))
-            );
+        fn boolean() {
+            assert_eq!(PgType::Bool.to_string(), "boolean".to_string());
         }
 
         #[test]
-        fn error_decode_bigint() {
+        fn character() {
+            assert_eq!(PgType::Char.to_string(), "character".to_string());
+        }
+
+        #[test]
+        fn blank_padded_character() {
+            assert_eq!(PgType::BpChar.to_string(), "character".to_string());
+        }
+
+        #[test]
+        fn big_int() {
+            assert_eq!(PgType::BigInt.to_string(), "bigint".to_string());
+        }
+
+        #[test]
+        fn small_int() {
+            assert_eq!(PgType::SmallInt.to_string(), "smallint".to_string());
+        }
+
+        #[test]
+        fn integer() {
+            assert_eq!(PgType::Integer.to_string(), "integer".to_string());
+        }
+
+        #[test]
+        fn oid() {
+            assert_eq!(PgType::Oid.to_string(), "oid".to_string());
+        }
+
+        #[test]
+        fn money() {
+            assert_eq!(PgType::Money.to_string(), "money".to_string());
+        }
+
+        #[test]
+        fn numeric() {
+            assert_eq!(PgType::Numeric.to_string(), "numeric".to_string());
+        }
+
+        #[test]
+        fn variable_characters() {
+            assert_eq!(PgType::VarChar.to_string(), "variable character".to_string());
+        }
+
+        #[test]
+        fn text() {
+            assert_eq!(PgType::Text.to_string(), "text".to_string());
+        }
+
+        #[test]
+        fn name() {
+            assert_eq!(PgType::Name.to_string(), "name".to_string());
+        }
+
+        #[test]
+        fn timestamp() {
+            assert_eq!(PgType::Timestamp.to_string(), "timestamp without time zone".to_string());
+        }
+
+        #[test]
+        fn timestamp_with_time_zone() {
+            assert_eq!(PgType::TimestampTz.to_string(), "timestamp with time zone".to_string());
+        }
+
+        #[test]
+        fn date() {
+            assert_eq!(PgType::Date.to_string(), "date".to_string());
+        }
+
+        #[test]
+        fn time() {
+            assert_eq!(PgType::Time.to_string(), "time without time zone".to_string());
+        }
+
+        #[test]
+        fn interval() {
+            assert_eq!(PgType::Interval.to_string(), "interval".to_string());
+        }
+
+        #[test]
+        fn inet() {
+            assert_eq!(PgType::Inet.to_string(), "inet".to_string());
+            assert_eq!(PgType::Cidr.to_string(), "cidr".to_string());
+        }
+
+        #[test]
+        fn json() {
+            assert_eq!(PgType::Json.to_string(), "json".to_string());
+        }
+
+        #[test]
+        fn jsonb() {
+            assert_eq!(PgType::Jsonb.to_string(), "jsonb".to_string());
+        }
+
+        #[test]
+        fn int4_array() {
+            assert_eq!(PgType::Int4Array.to_string(), "integer[]".to_string());
+        }
+
+        #[test]
+        fn bool_array() {
+            assert_eq!(PgType::BoolArray.to_string(), "boolean[]".to_string());
+        }
+
+        #[test]
+        fn text_array() {
+            assert_eq!(PgType::TextArray.to_string(), "text[]".to_string());
+        }
+    }
+
+    #[cfg(test)]
+    mod type_name_parsing {
+        use super::*;
+
+        #[test]
+        fn boolean() {
+            assert_eq!(PgType::from_str("bool"), Ok(PgType::Bool));
+            assert_eq!(PgType::from_str("boolean"), Ok(PgType::Bool));
+        }
+
+        #[test]
+        fn character() {
+            assert_eq!(PgType::from_str("char"), Ok(PgType::Char));
+            assert_eq!(PgType::from_str("character"), Ok(PgType::Char));
+        }
+
+        #[test]
+        fn blank_padded_character() {
+            assert_eq!(PgType::from_str("bpchar"), Ok(PgType::BpChar));
+        }
+
+        #[test]
+        fn big_int() {
+            assert_eq!(PgType::from_str("int8"), Ok(PgType::BigInt));
+            assert_eq!(PgType::from_str("bigint"), Ok(PgType::BigInt));
+        }
+
+        #[test]
+        fn small_int() {
+            assert_eq!(PgType::from_str("int2"), Ok(PgType::SmallInt));
+            assert_eq!(PgType::from_str("smallint"), Ok(PgType::SmallInt));
+        }
+
+        #[test]
+        fn integer() {
+            assert_eq!(PgType::from_str("int4"), Ok(PgType::Integer));
+            assert_eq!(PgType::from_str("int"), Ok(PgType::Integer));
+            assert_eq!(PgType::from_str("integer"), Ok(PgType::Integer));
+        }
+
+        #[test]
+        fn oid() {
+            assert_eq!(PgType::from_str("oid"), Ok(PgType::Oid));
+        }
+
+        #[test]
+        fn money() {
+            assert_eq!(PgType::from_str("money"), Ok(PgType::Money));
+        }
+
+        #[test]
+        fn numeric() {
+            assert_eq!(PgType::from_str("numeric"), Ok(PgType::Numeric));
+            assert_eq!(PgType::from_str("decimal"), Ok(PgType::Numeric));
+        }
+
+        #[test]
+        fn variable_characters() {
+            assert_eq!(PgType::from_str("varchar"), Ok(PgType::VarChar));
+            assert_eq!(PgType::from_str("character varying"), Ok(PgType::VarChar));
+            assert_eq!(PgType::from_str("text"), Ok(PgType::Text));
+            assert_eq!(PgType::from_str("name"), Ok(PgType::Name));
+        }
+
+        #[test]
+        fn case_insensitive() {
+            assert_eq!(PgType::from_str(" INTEGER "), Ok(PgType::Integer));
+            assert_eq!(PgType::from_str("VarChar"), Ok(PgType::VarChar));
+        }
+
+        #[test]
+        fn display_representation() {
+            for pg_type in &[
+                PgType::Bool,
+                PgType::Char,
+                PgType::BigInt,
+                PgType::SmallInt,
+                PgType::Integer,
+                PgType::Oid,
+                PgType::Money,
+                PgType::Numeric,
+                PgType::VarChar,
+                PgType::Text,
+                PgType::Name,
+                PgType::Date,
+                PgType::Timestamp,
+                PgType::TimestampTz,
+                PgType::Time,
+                PgType::Interval,
+                PgType::Inet,
+                PgType::Cidr,
+                PgType::Json,
+                PgType::Jsonb,
+                PgType::Int4Array,
+                PgType::BoolArray,
+                PgType::TextArray,
+            ] {
+                assert_eq!(PgType::from_str(&pg_type.to_string()), Ok(*pg_type));
+            }
+        }
+
+        #[test]
+        fn unknown_type_name() {
+            assert_eq!(PgType::from_str("box"), Err(UnknownTypeName("box".to_owned())));
             assert_eq!(
-                PgType::BigInt.decode(&PgFormat::Binary, &[0, 0, 1]),
-                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::NotEnoughBytes {
-                    required_bytes: 8,
-                    source: &[0, 0, 1],
-                    pg_type: PgType::BigInt
-                }))
+                UnknownTypeName("box".to_owned()).to_string(),
+                "type \"box\" does not exist"
             );
         }
     }
 
     #[cfg(test)]
-    mod text_decoding {
-        use std::str::FromStr;
-
+    mod binary_decoding {
         use super::*;
 
         #[test]
         fn decode_true() {
-            assert_eq!(PgType::Bool.decode(&PgFormat::Text, b"true"), Ok(Value::Bool(true)));
+            assert_eq!(PgType::Bool.decode(&PgFormat::Binary, &[1]), Ok(Value::Bool(true)));
         }
 
         #[test]
         fn decode_false() {
-            assert_eq!(PgType::Bool.decode(&PgFormat::Text, b"0"), Ok(Value::Bool(false)));
+            assert_eq!(PgType::Bool.decode(&PgFormat::Binary, &[0]), Ok(Value::Bool(false)));
         }
 
         #[test]
         fn error_decode_bool() {
             assert_eq!(
-                PgType::Bool.decode(&PgFormat::Text, b"abc"),
-                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::CannotParseBool {
-                    source: "abc"
+                PgType::Bool.decode(&PgFormat::Binary, &[]),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::NotEnoughBytes {
+                    required_bytes: 1,
+                    source: &[],
+                    pg_type: PgType::Bool
                 }))
             );
         }
@@ -624,24 +2089,48 @@ mod tests {
         #[test]
         fn decode_char() {
             assert_eq!(
-                PgType::Char.decode(&PgFormat::Text, b"abc"),
+                PgType::Char.decode(&PgFormat::Binary, &[97, 98, 99]),
                 Ok(Value::String("abc".into()))
             );
         }
 
+        #[test]
+        fn decode_bpchar() {
+            assert_eq!(
+                PgType::BpChar.decode(&PgFormat::Binary, b"ab  "),
+                Ok(Value::String("ab  ".into()))
+            );
+        }
+
         #[test]
         fn decode_varchar() {
             assert_eq!(
-                PgType::VarChar.decode(&PgFormat::Text, b"abc"),
+                PgType::VarChar.decode(&PgFormat::Binary, &[97, 98, 99]),
                 Ok(Value::String("abc".into()))
             );
         }
 
+        #[test]
+        fn decode_text() {
+            assert_eq!(
+                PgType::Text.decode(&PgFormat::Binary, &[97, 98, 99]),
+                Ok(Value::String("abc".into()))
+            );
+        }
+
+        #[test]
+        fn decode_name() {
+            assert_eq!(
+                PgType::Name.decode(&PgFormat::Binary, b"pg_class"),
+                Ok(Value::String("pg_class".into()))
+            );
+        }
+
         #[test]
         fn error_decode_string() {
             let non_utf_code = 0x96;
             assert_eq!(
-                PgType::Char.decode(&PgFormat::Text, &[non_utf_code]),
+                PgType::Char.decode(&PgFormat::Binary, &[non_utf_code]),
                 Err(TypeValueDecodeError::from(
                     TypeValueDecodeErrorKind::CannotDecodeString {
                         cause: str::from_utf8(&[non_utf_code]).unwrap_err(),
@@ -653,16 +2142,16 @@ mod tests {
 
         #[test]
         fn decode_smallint() {
-            assert_eq!(PgType::SmallInt.decode(&PgFormat::Text, b"1"), Ok(Value::Int16(1)));
+            assert_eq!(PgType::SmallInt.decode(&PgFormat::Binary, &[0, 1]), Ok(Value::Int16(1)));
         }
 
         #[test]
         fn error_decode_smallint() {
             assert_eq!(
-                PgType::SmallInt.decode(&PgFormat::Text, b"1.0"),
-                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::CannotParseInt {
-                    cause: i16::from_str("1.0").unwrap_err(),
-                    source: &"1.0",
+                PgType::SmallInt.decode(&PgFormat::Binary, &[1]),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::NotEnoughBytes {
+                    required_bytes: 2,
+                    source: &[1],
                     pg_type: PgType::SmallInt
                 }))
             );
@@ -670,39 +2159,1916 @@ mod tests {
 
         #[test]
         fn decode_integer() {
-            assert_eq!(PgType::Integer.decode(&PgFormat::Text, b"123"), Ok(Value::Int32(123)));
+            assert_eq!(
+                PgType::Integer.decode(&PgFormat::Binary, &[0, 0, 0, 1]),
+                Ok(Value::Int32(1))
+            );
         }
 
         #[test]
-        fn error_decode_integer() {
+        fn decode_oid() {
             assert_eq!(
-                PgType::Integer.decode(&PgFormat::Text, b"1.0"),
-                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::CannotParseInt {
-                    cause: i32::from_str("1.0").unwrap_err(),
-                    source: &"1.0",
-                    pg_type: PgType::Integer
+                PgType::Oid.decode(&PgFormat::Binary, &[255, 255, 255, 254]),
+                Ok(Value::Oid(4_294_967_294))
+            );
+        }
+
+        #[test]
+        fn error_decode_oid() {
+            assert_eq!(
+                PgType::Oid.decode(&PgFormat::Binary, &[0, 1]),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::NotEnoughBytes {
+                    required_bytes: 4,
+                    source: &[0, 1],
+                    pg_type: PgType::Oid
                 }))
             );
         }
 
         #[test]
-        fn decode_bigint() {
+        fn decode_money() {
             assert_eq!(
-                PgType::BigInt.decode(&PgFormat::Text, b"123456"),
-                Ok(Value::Int64(123456))
+                PgType::Money.decode(&PgFormat::Binary, &123_456i64.to_be_bytes()),
+                Ok(Value::Money(123_456))
             );
         }
 
         #[test]
-        fn error_decode_bigint() {
+        fn decode_numeric() {
             assert_eq!(
-                PgType::BigInt.decode(&PgFormat::Text, b"1.0"),
-                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::CannotParseInt {
-                    cause: i64::from_str("1.0").unwrap_err(),
-                    source: &"1.0",
-                    pg_type: PgType::BigInt
+                PgType::Numeric.decode(&PgFormat::Binary, &[0, 2, 0, 0, 0, 0, 0, 2, 0, 123, 17, 148]),
+                Ok(Value::Numeric("123.45".to_owned()))
+            );
+            assert_eq!(
+                PgType::Numeric.decode(&PgFormat::Binary, &[0, 1, 0, 1, 0, 0, 0, 0, 0, 1]),
+                Ok(Value::Numeric("10000".to_owned()))
+            );
+            assert_eq!(
+                PgType::Numeric.decode(&PgFormat::Binary, &[0, 1, 255, 255, 64, 0, 0, 4, 0, 12]),
+                Ok(Value::Numeric("-0.0012".to_owned()))
+            );
+        }
+
+        #[test]
+        fn decode_numeric_with_display_scale() {
+            assert_eq!(
+                PgType::Numeric.decode(&PgFormat::Binary, &[0, 1, 0, 0, 0, 0, 0, 4, 0, 5]),
+                Ok(Value::Numeric("5.0000".to_owned()))
+            );
+            assert_eq!(
+                PgType::Numeric.decode(&PgFormat::Binary, &[0, 0, 0, 0, 0, 0, 0, 2]),
+                Ok(Value::Numeric("0.00".to_owned()))
+            );
+        }
+
+        #[test]
+        fn decode_numeric_nan() {
+            assert_eq!(
+                PgType::Numeric.decode(&PgFormat::Binary, &[0, 0, 0, 0, 192, 0, 0, 0]),
+                Ok(Value::Numeric("NaN".to_owned()))
+            );
+        }
+
+        #[test]
+        fn error_decode_numeric() {
+            for malformed in &[
+                &[0, 1, 0, 0][..],
+                &[0, 1, 0, 0, 0, 0, 0, 0],
+                &[0, 1, 0, 0, 18, 52, 0, 0, 0, 1],
+                &[0, 1, 0, 0, 0, 0, 0, 0, 39, 16],
+            ] {
+                assert_eq!(
+                    PgType::Numeric.decode(&PgFormat::Binary, malformed),
+                    Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::MalformedNumeric {
+                        source: malformed
+                    }))
+                );
+            }
+        }
+
+        #[test]
+        fn error_decode_money() {
+            assert_eq!(
+                PgType::Money.decode(&PgFormat::Binary, &[0, 0, 0, 1]),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::NotEnoughBytes {
+                    required_bytes: 8,
+                    source: &[0, 0, 0, 1],
+                    pg_type: PgType::Money
                 }))
             );
         }
+
+        #[test]
+        fn error_decode_integer() {
+            assert_eq!(
+                PgType::Integer.decode(&PgFormat::Binary, &[0, 0, 1]),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::NotEnoughBytes {
+                    required_bytes: 4,
+                    source: &[0, 0, 1],
+                    pg_type: PgType::Integer
+                }))
+            );
+        }
+
+        #[test]
+        fn decode_bigint() {
+            assert_eq!(
+                PgType::BigInt.decode(&PgFormat::Binary, &[0, 0, 0, 0, 0, 0, 0, 1]),
+                Ok(Value::Int64(1))
+            );
+        }
+
+        #[test]
+        fn error_decode_bigint() {
+            assert_eq!(
+                PgType::BigInt.decode(&PgFormat::Binary, &[0, 0, 1]),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::NotEnoughBytes {
+                    required_bytes: 8,
+                    source: &[0, 0, 1],
+                    pg_type: PgType::BigInt
+                }))
+            );
+        }
+
+        #[test]
+        fn decode_timestamp() {
+            assert_eq!(
+                PgType::Timestamp.decode(&PgFormat::Binary, &1_000_000i64.to_be_bytes()),
+                Ok(Value::Timestamp(1_000_000))
+            );
+        }
+
+        /// `2021-04-13 10:20:30.5` in both `integer_datetimes` forms
+        const INTEGER_TIMESTAMP: i64 = 671_624_430_500_000;
+        const FLOAT_TIMESTAMP: f64 = 671_624_430.5;
+
+        #[test]
+        fn decode_integer_timestamp() {
+            assert_eq!(
+                PgType::Timestamp.decode_with_datetimes(
+                    &PgFormat::Binary,
+                    &INTEGER_TIMESTAMP.to_be_bytes(),
+                    DatetimeEncoding::Integer
+                ),
+                Ok(Value::Timestamp(INTEGER_TIMESTAMP))
+            );
+            assert_eq!(
+                PgType::TimestampTz.decode_with_datetimes(
+                    &PgFormat::Binary,
+                    &INTEGER_TIMESTAMP.to_be_bytes(),
+                    DatetimeEncoding::Integer
+                ),
+                Ok(Value::TimestampTz(INTEGER_TIMESTAMP))
+            );
+        }
+
+        #[test]
+        fn decode_float_timestamp() {
+            assert_eq!(
+                PgType::Timestamp.decode_with_datetimes(
+                    &PgFormat::Binary,
+                    &FLOAT_TIMESTAMP.to_be_bytes(),
+                    DatetimeEncoding::Float
+                ),
+                Ok(Value::Timestamp(INTEGER_TIMESTAMP))
+            );
+            assert_eq!(
+                PgType::TimestampTz.decode_with_datetimes(
+                    &PgFormat::Binary,
+                    &(-FLOAT_TIMESTAMP).to_be_bytes(),
+                    DatetimeEncoding::Float
+                ),
+                Ok(Value::TimestampTz(-INTEGER_TIMESTAMP))
+            );
+        }
+
+        #[test]
+        fn float_datetimes_do_not_change_other_types() {
+            assert_eq!(
+                PgType::BigInt.decode_with_datetimes(&PgFormat::Binary, &1i64.to_be_bytes(), DatetimeEncoding::Float),
+                Ok(Value::Int64(1))
+            );
+            assert_eq!(
+                PgType::Timestamp.decode_with_datetimes(
+                    &PgFormat::Text,
+                    b"2021-04-13 10:20:30.5",
+                    DatetimeEncoding::Float
+                ),
+                Ok(Value::Timestamp(INTEGER_TIMESTAMP))
+            );
+        }
+
+        #[test]
+        fn error_decode_float_timestamp() {
+            assert_eq!(
+                PgType::Timestamp.decode_with_datetimes(&PgFormat::Binary, &[0, 0, 1], DatetimeEncoding::Float),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::NotEnoughBytes {
+                    required_bytes: 8,
+                    source: &[0, 0, 1],
+                    pg_type: PgType::Timestamp
+                }))
+            );
+            let infinity = f64::INFINITY.to_be_bytes();
+            assert_eq!(
+                PgType::TimestampTz.decode_with_datetimes(&PgFormat::Binary, &infinity, DatetimeEncoding::Float),
+                Err(TypeValueDecodeError::from(
+                    TypeValueDecodeErrorKind::TimestampOutOfRange {
+                        source: &infinity,
+                        pg_type: PgType::TimestampTz
+                    }
+                ))
+            );
+        }
+
+        #[test]
+        fn decode_timestamp_with_time_zone() {
+            assert_eq!(
+                PgType::TimestampTz.decode(&PgFormat::Binary, &(-1_000_000i64).to_be_bytes()),
+                Ok(Value::TimestampTz(-1_000_000))
+            );
+        }
+
+        #[test]
+        fn decode_pg_epoch_date() {
+            assert_eq!(
+                PgType::Date.decode(&PgFormat::Binary, &0i32.to_be_bytes()),
+                Ok(Value::Date(Date::Day {
+                    year: 2000,
+                    month: 1,
+                    day: 1
+                }))
+            );
+        }
+
+        #[test]
+        fn decode_date_before_pg_epoch() {
+            assert_eq!(
+                PgType::Date.decode(&PgFormat::Binary, &(-1i32).to_be_bytes()),
+                Ok(Value::Date(Date::Day {
+                    year: 1999,
+                    month: 12,
+                    day: 31
+                }))
+            );
+            assert_eq!(
+                PgType::Date.decode(&PgFormat::Binary, &(-306i32).to_be_bytes()),
+                Ok(Value::Date(Date::Day {
+                    year: 1999,
+                    month: 3,
+                    day: 1
+                }))
+            );
+        }
+
+        #[test]
+        fn decode_leap_day() {
+            assert_eq!(
+                PgType::Date.decode(&PgFormat::Binary, &59i32.to_be_bytes()),
+                Ok(Value::Date(Date::Day {
+                    year: 2000,
+                    month: 2,
+                    day: 29
+                }))
+            );
+        }
+
+        #[test]
+        fn decode_infinite_dates() {
+            assert_eq!(
+                PgType::Date.decode(&PgFormat::Binary, &i32::MAX.to_be_bytes()),
+                Ok(Value::Date(Date::Infinity))
+            );
+            assert_eq!(
+                PgType::Date.decode(&PgFormat::Binary, &i32::MIN.to_be_bytes()),
+                Ok(Value::Date(Date::NegativeInfinity))
+            );
+        }
+
+        #[test]
+        fn error_decode_date() {
+            assert_eq!(
+                PgType::Date.decode(&PgFormat::Binary, &[0, 1]),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::NotEnoughBytes {
+                    required_bytes: 4,
+                    source: &[0, 1],
+                    pg_type: PgType::Date
+                }))
+            );
+        }
+
+        #[test]
+        fn error_decode_date_with_huge_year() {
+            for date in ["9000000000000000000-01-01", "9000000000000000000-01-01 BC"].iter() {
+                assert_eq!(
+                    PgType::Date.decode(&PgFormat::Text, date.as_bytes()),
+                    Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::InvalidSyntax {
+                        source: date,
+                        pg_type: PgType::Date
+                    }))
+                );
+            }
+        }
+
+        #[test]
+        fn error_decode_timestamp_with_huge_year() {
+            for pg_type in [PgType::Timestamp, PgType::TimestampTz].iter() {
+                assert_eq!(
+                    pg_type.decode(&PgFormat::Text, b"9000000000000000000-01-01 00:00:00"),
+                    Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::InvalidSyntax {
+                        source: "9000000000000000000-01-01 00:00:00",
+                        pg_type: *pg_type
+                    }))
+                );
+            }
+        }
+
+        #[test]
+        fn decode_midnight_time() {
+            assert_eq!(
+                PgType::Time.decode(&PgFormat::Binary, &0i64.to_be_bytes()),
+                Ok(Value::Time(0))
+            );
+        }
+
+        #[test]
+        fn decode_fractional_time() {
+            assert_eq!(
+                PgType::Time.decode(&PgFormat::Binary, &3_723_500_000i64.to_be_bytes()),
+                Ok(Value::Time(3_723_500_000))
+            );
+        }
+
+        #[test]
+        fn error_decode_timestamp() {
+            assert_eq!(
+                PgType::TimestampTz.decode(&PgFormat::Binary, &[0, 0, 1]),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::NotEnoughBytes {
+                    required_bytes: 8,
+                    source: &[0, 0, 1],
+                    pg_type: PgType::TimestampTz
+                }))
+            );
+        }
+
+        fn interval(micros: i64, days: i32, months: i32) -> Vec<u8> {
+            let mut buff = vec![];
+            buff.extend_from_slice(&micros.to_be_bytes());
+            buff.extend_from_slice(&days.to_be_bytes());
+            buff.extend_from_slice(&months.to_be_bytes());
+            buff
+        }
+
+        #[test]
+        fn decode_time_interval() {
+            assert_eq!(
+                PgType::Interval.decode(&PgFormat::Binary, &interval(14_706_000_000, 0, 0)),
+                Ok(Value::Interval {
+                    months: 0,
+                    days: 0,
+                    micros: 14_706_000_000
+                })
+            );
+        }
+
+        #[test]
+        fn decode_months_and_days_interval() {
+            assert_eq!(
+                PgType::Interval.decode(&PgFormat::Binary, &interval(0, -3, 14)),
+                Ok(Value::Interval {
+                    months: 14,
+                    days: -3,
+                    micros: 0
+                })
+            );
+        }
+
+        #[test]
+        fn error_decode_interval() {
+            assert_eq!(
+                PgType::Interval.decode(&PgFormat::Binary, &[0, 0, 1]),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::NotEnoughBytes {
+                    required_bytes: 16,
+                    source: &[0, 0, 1],
+                    pg_type: PgType::Interval
+                }))
+            );
+        }
+
+        #[test]
+        fn decode_json_object() {
+            assert_eq!(
+                PgType::Json.decode(&PgFormat::Binary, b"{\"a\": 1}"),
+                Ok(Value::Json("{\"a\": 1}".into()))
+            );
+        }
+
+        #[test]
+        fn error_decode_json_string() {
+            let non_utf_code = 0x96;
+            assert_eq!(
+                PgType::Json.decode(&PgFormat::Binary, &[non_utf_code]),
+                Err(TypeValueDecodeError::from(
+                    TypeValueDecodeErrorKind::CannotDecodeString {
+                        cause: str::from_utf8(&[non_utf_code]).unwrap_err(),
+                        source: &[non_utf_code]
+                    }
+                ))
+            );
+        }
+
+        #[test]
+        fn decode_jsonb_with_version() {
+            assert_eq!(
+                PgType::Jsonb.decode(&PgFormat::Binary, b"\x01{\"a\": 1}"),
+                Ok(Value::Jsonb("{\"a\": 1}".into()))
+            );
+        }
+
+        #[test]
+        fn error_decode_jsonb_unsupported_version() {
+            assert_eq!(
+                PgType::Jsonb.decode(&PgFormat::Binary, b"\x02{\"a\": 1}"),
+                Err(TypeValueDecodeError::from(
+                    TypeValueDecodeErrorKind::UnsupportedJsonbVersion { version: 2 }
+                ))
+            );
+        }
+
+        #[test]
+        fn error_decode_jsonb_without_version() {
+            assert_eq!(
+                PgType::Jsonb.decode(&PgFormat::Binary, &[]),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::NotEnoughBytes {
+                    required_bytes: 1,
+                    source: &[],
+                    pg_type: PgType::Jsonb
+                }))
+            );
+        }
+
+        #[test]
+        fn error_decode_jsonb_invalid_document() {
+            assert_eq!(
+                PgType::Jsonb.decode(&PgFormat::Binary, b"\x01{a}"),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::InvalidJson {
+                    source: "{a}",
+                    pg_type: PgType::Jsonb
+                }))
+            );
+        }
+
+        #[test]
+        fn error_decode_too_deeply_nested_jsonb() {
+            let document = "[".repeat(1_000_000);
+            let mut raw = vec![1];
+            raw.extend_from_slice(document.as_bytes());
+            assert_eq!(
+                PgType::Jsonb.decode(&PgFormat::Binary, &raw),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::InvalidJson {
+                    source: &document,
+                    pg_type: PgType::Jsonb
+                }))
+            );
+        }
+
+        fn int4_array(header: &[i32], elements: &[Option<i32>]) -> Vec<u8> {
+            let mut buff = vec![];
+            for value in header {
+                buff.extend_from_slice(&value.to_be_bytes());
+            }
+            for element in elements {
+                match element {
+                    Some(value) => {
+                        buff.extend_from_slice(&4i32.to_be_bytes());
+                        buff.extend_from_slice(&value.to_be_bytes());
+                    }
+                    None => buff.extend_from_slice(&(-1i32).to_be_bytes()),
+                }
+            }
+            buff
+        }
+
+        #[test]
+        fn decode_int4_array() {
+            let raw = int4_array(&[1, 1, 23, 3, 1], &[Some(1), Some(2), None]);
+            assert_eq!(
+                PgType::Int4Array.decode(&PgFormat::Binary, &raw),
+                Ok(Value::Array(vec![Value::Int32(1), Value::Int32(2), Value::Null]))
+            );
+        }
+
+        #[test]
+        fn decode_empty_int4_array() {
+            let raw = int4_array(&[0, 0, 23], &[]);
+            assert_eq!(
+                PgType::Int4Array.decode(&PgFormat::Binary, &raw),
+                Ok(Value::Array(vec![]))
+            );
+        }
+
+        #[test]
+        fn decode_bool_array() {
+            let mut raw = vec![];
+            for value in &[1, 0, 16, 2, 1] {
+                raw.extend_from_slice(&i32::to_be_bytes(*value));
+            }
+            raw.extend_from_slice(&[0, 0, 0, 1, 1, 0, 0, 0, 1, 0]);
+            assert_eq!(
+                PgType::BoolArray.decode(&PgFormat::Binary, &raw),
+                Ok(Value::Array(vec![Value::Bool(true), Value::Bool(false)]))
+            );
+        }
+
+        #[test]
+        fn decode_text_array() {
+            let mut raw = vec![];
+            for value in &[1, 1, 25, 2, 1, 2] {
+                raw.extend_from_slice(&i32::to_be_bytes(*value));
+            }
+            raw.extend_from_slice(b"ab");
+            raw.extend_from_slice(&(-1i32).to_be_bytes());
+            assert_eq!(
+                PgType::TextArray.decode(&PgFormat::Binary, &raw),
+                Ok(Value::Array(vec![Value::String("ab".to_owned()), Value::Null]))
+            );
+        }
+
+        #[test]
+        fn decode_two_dimensional_int4_array() {
+            let raw = int4_array(&[2, 0, 23, 2, 1, 2, 1], &[Some(1), Some(2), Some(3), Some(4)]);
+            assert_eq!(
+                PgType::Int4Array.decode(&PgFormat::Binary, &raw),
+                Ok(Value::Array(vec![
+                    Value::Array(vec![Value::Int32(1), Value::Int32(2)]),
+                    Value::Array(vec![Value::Int32(3), Value::Int32(4)]),
+                ]))
+            );
+        }
+
+        #[test]
+        fn error_decode_int4_array_with_wrong_element_type() {
+            let raw = int4_array(&[1, 0, 20, 1, 1], &[Some(1)]);
+            assert_eq!(
+                PgType::Int4Array.decode(&PgFormat::Binary, &raw),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::MalformedArray {
+                    source: &raw,
+                    pg_type: PgType::Int4Array
+                }))
+            );
+        }
+
+        #[test]
+        fn error_decode_truncated_int4_array() {
+            let raw = int4_array(&[1, 0, 23, 2, 1], &[Some(1)]);
+            assert_eq!(
+                PgType::Int4Array.decode(&PgFormat::Binary, &raw),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::MalformedArray {
+                    source: &raw,
+                    pg_type: PgType::Int4Array
+                }))
+            );
+        }
+    }
+
+    mod hashing {
+        use super::*;
+        use std::collections::HashSet;
+
+        #[test]
+        fn types_as_set_elements() {
+            let types = vec![PgType::Integer, PgType::Text, PgType::Integer, PgType::Json]
+                .into_iter()
+                .collect::<HashSet<_>>();
+
+            assert_eq!(types.len(), 3);
+            assert!(types.contains(&PgType::Integer));
+            assert!(!types.contains(&PgType::VarChar));
+        }
+
+        #[test]
+        fn values_as_set_elements() {
+            let values = vec![
+                Value::Int32(1),
+                Value::String("1".to_owned()),
+                Value::Int32(1),
+                Value::Array(vec![Value::Null, Value::Int32(1)]),
+                Value::Array(vec![Value::Null, Value::Int32(1)]),
+            ]
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+            assert_eq!(values.len(), 3);
+            assert!(values.contains(&Value::String("1".to_owned())));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde_support {
+        use super::*;
+
+        #[test]
+        fn null() {
+            let json = serde_json::to_string(&Value::Null).unwrap();
+            assert_eq!(json, r#"{"type":"Null"}"#);
+            assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), Value::Null);
+        }
+
+        #[test]
+        fn bool() {
+            let json = serde_json::to_string(&Value::Bool(true)).unwrap();
+            assert_eq!(json, r#"{"type":"Bool","value":true}"#);
+            assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), Value::Bool(true));
+        }
+
+        #[test]
+        fn int32() {
+            let json = serde_json::to_string(&Value::Int32(-123)).unwrap();
+            assert_eq!(json, r#"{"type":"Int32","value":-123}"#);
+            assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), Value::Int32(-123));
+        }
+
+        #[test]
+        fn string() {
+            let json = serde_json::to_string(&Value::String("abc".into())).unwrap();
+            assert_eq!(json, r#"{"type":"String","value":"abc"}"#);
+            assert_eq!(
+                serde_json::from_str::<Value>(&json).unwrap(),
+                Value::String("abc".into())
+            );
+        }
+
+        #[test]
+        fn pg_type() {
+            let json = serde_json::to_string(&PgType::BigInt).unwrap();
+            assert_eq!(json, r#""BigInt""#);
+            assert_eq!(serde_json::from_str::<PgType>(&json).unwrap(), PgType::BigInt);
+        }
+    }
+
+    #[cfg(test)]
+    mod text_encoding {
+        use super::*;
+
+        #[test]
+        fn encode_bool() {
+            assert_eq!(PgType::Bool.encode_text(&Value::Bool(true)), Ok(b"t".to_vec()));
+            assert_eq!(PgType::Bool.encode_text(&Value::Bool(false)), Ok(b"f".to_vec()));
+        }
+
+        #[test]
+        fn encode_ints() {
+            assert_eq!(PgType::SmallInt.encode_text(&Value::Int16(-1)), Ok(b"-1".to_vec()));
+            assert_eq!(PgType::Integer.encode_text(&Value::Int32(123)), Ok(b"123".to_vec()));
+            assert_eq!(
+                PgType::BigInt.encode_text(&Value::Int64(i64::MAX)),
+                Ok(b"9223372036854775807".to_vec())
+            );
+        }
+
+        #[test]
+        fn encode_numeric() {
+            assert_eq!(
+                PgType::Numeric.encode_text(&Value::Numeric("-123.4500".to_owned())),
+                Ok(b"-123.4500".to_vec())
+            );
+        }
+
+        #[test]
+        fn encode_money() {
+            assert_eq!(
+                PgType::Money.encode_text(&Value::Money(123_456)),
+                Ok(b"$1,234.56".to_vec())
+            );
+            assert_eq!(PgType::Money.encode_text(&Value::Money(-5)), Ok(b"-$0.05".to_vec()));
+            assert_eq!(
+                PgType::Money.encode_text(&Value::Money(i64::MIN)),
+                Ok(b"-$92,233,720,368,547,758.08".to_vec())
+            );
+        }
+
+        #[test]
+        fn encode_strings() {
+            assert_eq!(PgType::Char.encode_text(&Value::String("a".into())), Ok(b"a".to_vec()));
+            assert_eq!(
+                PgType::VarChar.encode_text(&Value::String("abc\ndef".into())),
+                Ok(b"abc\ndef".to_vec())
+            );
+        }
+
+        #[test]
+        fn encode_timestamps() {
+            assert_eq!(
+                PgType::Timestamp.encode_text(&Value::Timestamp(1_500_000)),
+                Ok(b"2000-01-01 00:00:01.5".to_vec())
+            );
+            assert_eq!(
+                PgType::TimestampTz.encode_text(&Value::TimestampTz(0)),
+                Ok(b"2000-01-01 00:00:00+00".to_vec())
+            );
+        }
+
+        #[test]
+        fn encode_dates() {
+            assert_eq!(
+                PgType::Date.encode_text(&Value::Date(Date::Day {
+                    year: 2021,
+                    month: 4,
+                    day: 3
+                })),
+                Ok(b"2021-04-03".to_vec())
+            );
+            assert_eq!(
+                PgType::Date.encode_text(&Value::Date(Date::Infinity)),
+                Ok(b"infinity".to_vec())
+            );
+            assert_eq!(
+                PgType::Date.encode_text(&Value::Date(Date::NegativeInfinity)),
+                Ok(b"-infinity".to_vec())
+            );
+        }
+
+        #[test]
+        fn encode_time() {
+            assert_eq!(
+                PgType::Time.encode_text(&Value::Time(3_723_500_000)),
+                Ok(b"01:02:03.5".to_vec())
+            );
+        }
+
+        #[test]
+        fn encode_interval() {
+            assert_eq!(
+                PgType::Interval.encode_text(&Value::Interval {
+                    months: 14,
+                    days: 3,
+                    micros: 14_706_000_000
+                }),
+                Ok(b"1 year 2 mons 3 days 04:05:06".to_vec())
+            );
+        }
+
+        #[test]
+        fn encode_json() {
+            assert_eq!(
+                PgType::Json.encode_text(&Value::Json("{\"a\": 1}".into())),
+                Ok(b"{\"a\": 1}".to_vec())
+            );
+            assert_eq!(
+                PgType::Jsonb.encode_text(&Value::Jsonb("[]".into())),
+                Ok(b"[]".to_vec())
+            );
+        }
+
+        #[test]
+        fn encode_int4_array() {
+            assert_eq!(
+                PgType::Int4Array.encode_text(&Value::Array(vec![
+                    Value::Array(vec![Value::Int32(1), Value::Null]),
+                    Value::Array(vec![Value::Int32(3), Value::Int32(4)]),
+                ])),
+                Ok(b"{{1,NULL},{3,4}}".to_vec())
+            );
+        }
+
+        #[test]
+        fn encode_bool_array() {
+            assert_eq!(
+                PgType::BoolArray.encode_text(&Value::Array(vec![Value::Bool(true), Value::Null, Value::Bool(false)])),
+                Ok(b"{t,NULL,f}".to_vec())
+            );
+        }
+
+        #[test]
+        fn encode_text_array() {
+            assert_eq!(
+                PgType::TextArray.encode_text(&Value::Array(vec![
+                    Value::String("abc".into()),
+                    Value::String("a,b".into()),
+                    Value::String("NULL".into()),
+                    Value::Null,
+                    Value::String("say \"hi\" \\".into()),
+                    Value::String("".into()),
+                ])),
+                Ok(br#"{abc,"a,b","NULL",NULL,"say \"hi\" \\",""}"#.to_vec())
+            );
+        }
+
+        #[test]
+        fn encode_null() {
+            assert_eq!(
+                PgType::Integer.encode_text(&Value::Null),
+                Err(EncodeError::from(EncodeErrorKind::NullValue {
+                    pg_type: PgType::Integer
+                }))
+            );
+        }
+
+        #[test]
+        fn type_mismatch() {
+            assert_eq!(
+                PgType::Bool.encode_text(&Value::Int32(1)),
+                Err(EncodeError::from(EncodeErrorKind::TypeMismatch {
+                    value: &Value::Int32(1),
+                    pg_type: PgType::Bool
+                }))
+            );
+        }
+
+        #[test]
+        fn array_element_type_mismatch() {
+            assert_eq!(
+                PgType::Int4Array.encode_text(&Value::Array(vec![Value::Bool(true)])),
+                Err(EncodeError::from(EncodeErrorKind::TypeMismatch {
+                    value: &Value::Bool(true),
+                    pg_type: PgType::Integer
+                }))
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod binary_encoding {
+        use super::*;
+
+        fn round_trip(pg_type: PgType, value: Value) {
+            let encoded = pg_type.encode_binary(&value).expect("encoded");
+            assert_eq!(pg_type.decode(&PgFormat::Binary, &encoded), Ok(value));
+        }
+
+        #[test]
+        fn bool() {
+            round_trip(PgType::Bool, Value::Bool(true));
+            round_trip(PgType::Bool, Value::Bool(false));
+        }
+
+        #[test]
+        fn small_int() {
+            round_trip(PgType::SmallInt, Value::Int16(i16::MIN));
+            round_trip(PgType::SmallInt, Value::Int16(i16::MAX));
+        }
+
+        #[test]
+        fn integer() {
+            round_trip(PgType::Integer, Value::Int32(i32::MIN));
+            round_trip(PgType::Integer, Value::Int32(-1));
+        }
+
+        #[test]
+        fn oid() {
+            round_trip(PgType::Oid, Value::Oid(u32::MAX));
+        }
+
+        #[test]
+        fn money() {
+            round_trip(PgType::Money, Value::Money(i64::MIN));
+            round_trip(PgType::Money, Value::Money(-123_456));
+        }
+
+        #[test]
+        fn numeric() {
+            round_trip(PgType::Numeric, Value::Numeric("123.45".to_owned()));
+            round_trip(PgType::Numeric, Value::Numeric("-0.0012".to_owned()));
+            round_trip(PgType::Numeric, Value::Numeric("100000000".to_owned()));
+            round_trip(PgType::Numeric, Value::Numeric("0".to_owned()));
+            round_trip(PgType::Numeric, Value::Numeric("NaN".to_owned()));
+        }
+
+        #[test]
+        fn numeric_trailing_zeros_are_not_kept() {
+            let value = Value::Numeric("123.4500".to_owned());
+            let encoded = PgType::Numeric.encode_binary(&value).unwrap();
+
+            assert_eq!(encoded, vec![0, 2, 0, 0, 0, 0, 0, 2, 0, 123, 17, 148]);
+            assert_eq!(
+                PgType::Numeric.decode(&PgFormat::Binary, &encoded),
+                Ok(Value::Numeric("123.45".to_owned()))
+            );
+        }
+
+        #[test]
+        fn numeric_nbase_digits() {
+            assert_eq!(
+                PgType::Numeric.encode_binary(&Value::Numeric("10000".to_owned())),
+                Ok(vec![0, 1, 0, 1, 0, 0, 0, 0, 0, 1])
+            );
+            assert_eq!(
+                PgType::Numeric.encode_binary(&Value::Numeric("-0.0012".to_owned())),
+                Ok(vec![0, 1, 255, 255, 64, 0, 0, 4, 0, 12])
+            );
+            assert_eq!(
+                PgType::Numeric.encode_binary(&Value::Numeric("0.000".to_owned())),
+                Ok(vec![0, 0, 0, 0, 0, 0, 0, 0])
+            );
+            assert_eq!(
+                PgType::Numeric.encode_binary(&Value::Numeric("NaN".to_owned())),
+                Ok(vec![0, 0, 0, 0, 192, 0, 0, 0])
+            );
+        }
+
+        #[test]
+        fn malformed_numeric() {
+            let value = Value::Numeric("1.2.3".to_owned());
+            assert_eq!(
+                PgType::Numeric.encode_binary(&value),
+                Err(EncodeError::from(EncodeErrorKind::MalformedValue {
+                    value: &value,
+                    pg_type: PgType::Numeric
+                }))
+            );
+        }
+
+        #[test]
+        fn big_int() {
+            round_trip(PgType::BigInt, Value::Int64(i64::MAX));
+            round_trip(PgType::BigInt, Value::Int64(0));
+        }
+
+        #[test]
+        fn date() {
+            round_trip(
+                PgType::Date,
+                Value::Date(Date::Day {
+                    year: 1999,
+                    month: 12,
+                    day: 31,
+                }),
+            );
+            round_trip(
+                PgType::Date,
+                Value::Date(Date::Day {
+                    year: -4713,
+                    month: 11,
+                    day: 24,
+                }),
+            );
+            round_trip(PgType::Date, Value::Date(Date::Infinity));
+            round_trip(PgType::Date, Value::Date(Date::NegativeInfinity));
+        }
+
+        #[test]
+        fn invalid_date() {
+            let value = Value::Date(Date::Day {
+                year: 2021,
+                month: 2,
+                day: 29,
+            });
+
+            assert_eq!(
+                PgType::Date.encode_binary(&value),
+                Err(EncodeError::from(EncodeErrorKind::MalformedValue {
+                    value: &value,
+                    pg_type: PgType::Date
+                }))
+            );
+        }
+
+        #[test]
+        fn float_timestamp() {
+            for (pg_type, value) in [
+                (PgType::Timestamp, Value::Timestamp(671_624_430_500_000)),
+                (PgType::TimestampTz, Value::TimestampTz(-1_000_000)),
+            ] {
+                let encoded = pg_type
+                    .encode_binary_with_datetimes(&value, DatetimeEncoding::Float)
+                    .expect("encoded");
+                assert_eq!(
+                    pg_type.decode_with_datetimes(&PgFormat::Binary, &encoded, DatetimeEncoding::Float),
+                    Ok(value)
+                );
+            }
+            assert_eq!(
+                PgType::Timestamp
+                    .encode_binary_with_datetimes(&Value::Timestamp(671_624_430_500_000), DatetimeEncoding::Float),
+                Ok(671_624_430.5f64.to_be_bytes().to_vec())
+            );
+        }
+
+        #[test]
+        fn float_datetimes_do_not_change_other_types() {
+            assert_eq!(
+                PgType::BigInt.encode_binary_with_datetimes(&Value::Int64(1), DatetimeEncoding::Float),
+                Ok(1i64.to_be_bytes().to_vec())
+            );
+            assert_eq!(
+                PgType::Timestamp.encode_binary_with_datetimes(&Value::Timestamp(1), DatetimeEncoding::Integer),
+                Ok(1i64.to_be_bytes().to_vec())
+            );
+        }
+
+        #[test]
+        fn date_time() {
+            round_trip(PgType::Timestamp, Value::Timestamp(-1_000_000));
+            round_trip(PgType::TimestampTz, Value::TimestampTz(1_000_000));
+            round_trip(PgType::Time, Value::Time(3_723_500_000));
+            round_trip(
+                PgType::Interval,
+                Value::Interval {
+                    months: 14,
+                    days: -3,
+                    micros: 14_706_000_000,
+                },
+            );
+        }
+
+        #[test]
+        fn strings() {
+            round_trip(PgType::VarChar, Value::String("abc".into()));
+            round_trip(PgType::Text, Value::String("abc".into()));
+            round_trip(PgType::Name, Value::String("pg_class".into()));
+            round_trip(PgType::Json, Value::Json("{\"a\": 1}".into()));
+            round_trip(PgType::Jsonb, Value::Jsonb("[1, 2]".into()));
+        }
+
+        #[test]
+        fn int4_array() {
+            round_trip(PgType::Int4Array, Value::Array(vec![]));
+            round_trip(
+                PgType::Int4Array,
+                Value::Array(vec![Value::Int32(1), Value::Null, Value::Int32(3)]),
+            );
+            round_trip(
+                PgType::Int4Array,
+                Value::Array(vec![
+                    Value::Array(vec![Value::Int32(1), Value::Int32(2)]),
+                    Value::Array(vec![Value::Int32(3), Value::Int32(4)]),
+                ]),
+            );
+        }
+
+        #[test]
+        fn bool_array() {
+            round_trip(
+                PgType::BoolArray,
+                Value::Array(vec![Value::Bool(true), Value::Null, Value::Bool(false)]),
+            );
+        }
+
+        #[test]
+        fn text_array() {
+            round_trip(
+                PgType::TextArray,
+                Value::Array(vec![Value::String("a,b".into()), Value::Null, Value::String("".into())]),
+            );
+        }
+
+        #[test]
+        fn not_rectangular_int4_array() {
+            assert_eq!(
+                PgType::Int4Array.encode_binary(&Value::Array(vec![
+                    Value::Array(vec![Value::Int32(1), Value::Int32(2)]),
+                    Value::Array(vec![Value::Int32(3)]),
+                ])),
+                Err(EncodeError::from(EncodeErrorKind::NotRectangularArray {
+                    pg_type: PgType::Int4Array
+                }))
+            );
+        }
+
+        #[test]
+        fn type_mismatch() {
+            assert_eq!(
+                PgType::Integer.encode_binary(&Value::Int64(1)),
+                Err(EncodeError::from(EncodeErrorKind::TypeMismatch {
+                    value: &Value::Int64(1),
+                    pg_type: PgType::Integer
+                }))
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod text_decoding {
+        use std::str::FromStr;
+
+        use super::*;
+
+        #[test]
+        fn decode_true() {
+            assert_eq!(PgType::Bool.decode(&PgFormat::Text, b"true"), Ok(Value::Bool(true)));
+        }
+
+        #[test]
+        fn decode_false() {
+            assert_eq!(PgType::Bool.decode(&PgFormat::Text, b"0"), Ok(Value::Bool(false)));
+        }
+
+        #[test]
+        fn error_decode_bool() {
+            assert_eq!(
+                PgType::Bool.decode(&PgFormat::Text, b"abc"),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::CannotParseBool {
+                    source: "abc"
+                }))
+            );
+        }
+
+        #[test]
+        fn decode_char() {
+            assert_eq!(
+                PgType::Char.decode(&PgFormat::Text, b"abc"),
+                Ok(Value::String("abc".into()))
+            );
+        }
+
+        #[test]
+        fn decode_bpchar() {
+            assert_eq!(
+                PgType::BpChar.decode(&PgFormat::Text, "ab  ".as_bytes()),
+                Ok(Value::String("ab  ".into()))
+            );
+        }
+
+        #[test]
+        fn decode_varchar() {
+            assert_eq!(
+                PgType::VarChar.decode(&PgFormat::Text, b"abc"),
+                Ok(Value::String("abc".into()))
+            );
+        }
+
+        #[test]
+        fn decode_text() {
+            assert_eq!(
+                PgType::Text.decode(&PgFormat::Text, b"abc"),
+                Ok(Value::String("abc".into()))
+            );
+        }
+
+        #[test]
+        fn decode_name() {
+            assert_eq!(
+                PgType::Name.decode(&PgFormat::Text, b"pg_class"),
+                Ok(Value::String("pg_class".into()))
+            );
+        }
+
+        #[test]
+        fn error_decode_string() {
+            let non_utf_code = 0x96;
+            assert_eq!(
+                PgType::Char.decode(&PgFormat::Text, &[non_utf_code]),
+                Err(TypeValueDecodeError::from(
+                    TypeValueDecodeErrorKind::CannotDecodeString {
+                        cause: str::from_utf8(&[non_utf_code]).unwrap_err(),
+                        source: &[non_utf_code]
+                    }
+                ))
+            );
+        }
+
+        #[test]
+        fn decode_smallint() {
+            assert_eq!(PgType::SmallInt.decode(&PgFormat::Text, b"1"), Ok(Value::Int16(1)));
+        }
+
+        #[test]
+        fn error_decode_smallint() {
+            assert_eq!(
+                PgType::SmallInt.decode(&PgFormat::Text, b"1.0"),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::CannotParseInt {
+                    cause: i16::from_str("1.0").unwrap_err(),
+                    source: &"1.0",
+                    pg_type: PgType::SmallInt
+                }))
+            );
+        }
+
+        #[test]
+        fn decode_integer() {
+            assert_eq!(PgType::Integer.decode(&PgFormat::Text, b"123"), Ok(Value::Int32(123)));
+        }
+
+        #[test]
+        fn decode_oid() {
+            assert_eq!(
+                PgType::Oid.decode(&PgFormat::Text, b"3000000000"),
+                Ok(Value::Oid(3_000_000_000))
+            );
+        }
+
+        #[test]
+        fn error_decode_negative_oid() {
+            assert!(matches!(
+                PgType::Oid.decode(&PgFormat::Text, b"-1"),
+                Err(TypeValueDecodeError {
+                    kind: TypeValueDecodeErrorKind::CannotParseInt { .. }
+                })
+            ));
+        }
+
+        #[test]
+        fn decode_money() {
+            assert_eq!(
+                PgType::Money.decode(&PgFormat::Text, b"$1,234.56"),
+                Ok(Value::Money(123_456))
+            );
+            assert_eq!(
+                PgType::Money.decode(&PgFormat::Text, b"-$1,234,567.5"),
+                Ok(Value::Money(-123_456_750))
+            );
+            assert_eq!(PgType::Money.decode(&PgFormat::Text, b"42"), Ok(Value::Money(4_200)));
+        }
+
+        #[test]
+        fn error_decode_money() {
+            for malformed in &["$", "$1,234.567", "$,123.00", "$12,.00", "$1.2a", "1$", "-"] {
+                assert_eq!(
+                    PgType::Money.decode(&PgFormat::Text, malformed.as_bytes()),
+                    Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::InvalidSyntax {
+                        source: malformed,
+                        pg_type: PgType::Money
+                    }))
+                );
+            }
+        }
+
+        #[test]
+        fn decode_numeric() {
+            for (text, expected) in &[
+                ("123.4500", "123.4500"),
+                ("  -00.50 ", "-0.50"),
+                ("+7", "7"),
+                ("-0", "0"),
+                (".5", "0.5"),
+                ("1.5e2", "150"),
+                ("1.50E1", "15.0"),
+                ("1e-2", "0.01"),
+                ("nan", "NaN"),
+            ] {
+                assert_eq!(
+                    PgType::Numeric.decode(&PgFormat::Text, text.as_bytes()),
+                    Ok(Value::Numeric((*expected).to_owned()))
+                );
+            }
+        }
+
+        #[test]
+        fn error_decode_numeric() {
+            for malformed in &["", "-", ".", "--1", "1.2.3", "1e", "1e+", "abc", "1e1001", "1,5"] {
+                assert_eq!(
+                    PgType::Numeric.decode(&PgFormat::Text, malformed.as_bytes()),
+                    Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::InvalidSyntax {
+                        source: malformed,
+                        pg_type: PgType::Numeric
+                    }))
+                );
+            }
+        }
+
+        #[test]
+        fn error_decode_money_out_of_range() {
+            assert_eq!(
+                PgType::Money.decode(&PgFormat::Text, b"$92,233,720,368,547,758.08"),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::InvalidSyntax {
+                    source: "$92,233,720,368,547,758.08",
+                    pg_type: PgType::Money
+                }))
+            );
+        }
+
+        #[test]
+        fn error_decode_integer() {
+            assert_eq!(
+                PgType::Integer.decode(&PgFormat::Text, b"1.0"),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::CannotParseInt {
+                    cause: i32::from_str("1.0").unwrap_err(),
+                    source: &"1.0",
+                    pg_type: PgType::Integer
+                }))
+            );
+        }
+
+        #[test]
+        fn decode_bigint() {
+            assert_eq!(
+                PgType::BigInt.decode(&PgFormat::Text, b"123456"),
+                Ok(Value::Int64(123456))
+            );
+        }
+
+        #[test]
+        fn error_decode_bigint() {
+            assert_eq!(
+                PgType::BigInt.decode(&PgFormat::Text, b"1.0"),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::CannotParseInt {
+                    cause: i64::from_str("1.0").unwrap_err(),
+                    source: &"1.0",
+                    pg_type: PgType::BigInt
+                }))
+            );
+        }
+
+        // 2021-04-13 10:20:30.5 is 671_624_430.5 seconds since 2000-01-01
+        const MICROS: i64 = 671_624_430_500_000;
+
+        #[test]
+        fn decode_timestamp() {
+            assert_eq!(
+                PgType::Timestamp.decode(&PgFormat::Text, b"2021-04-13 10:20:30.5"),
+                Ok(Value::Timestamp(MICROS))
+            );
+        }
+
+        #[test]
+        fn decode_timestamp_with_time_zone_without_offset() {
+            assert_eq!(
+                PgType::TimestampTz.decode(&PgFormat::Text, b"2021-04-13 10:20:30.5"),
+                Ok(Value::TimestampTz(MICROS))
+            );
+        }
+
+        #[test]
+        fn decode_timestamp_with_time_zone_normalized_to_utc() {
+            assert_eq!(
+                PgType::TimestampTz.decode(&PgFormat::Text, b"2021-04-13 13:20:30.5+03"),
+                Ok(Value::TimestampTz(MICROS))
+            );
+            assert_eq!(
+                PgType::TimestampTz.decode(&PgFormat::Text, b"2021-04-13 04:50:30.5-05:30"),
+                Ok(Value::TimestampTz(MICROS))
+            );
+        }
+
+        #[test]
+        fn error_decode_timestamp() {
+            assert_eq!(
+                PgType::Timestamp.decode(&PgFormat::Text, b"2021-02-30 10:20:30"),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::InvalidSyntax {
+                    source: "2021-02-30 10:20:30",
+                    pg_type: PgType::Timestamp
+                }))
+            );
+        }
+
+        #[test]
+        fn decode_date() {
+            assert_eq!(
+                PgType::Date.decode(&PgFormat::Text, b"2021-04-13"),
+                Ok(Value::Date(Date::Day {
+                    year: 2021,
+                    month: 4,
+                    day: 13
+                }))
+            );
+            assert_eq!(
+                PgType::Date.decode(&PgFormat::Text, b"infinity"),
+                Ok(Value::Date(Date::Infinity))
+            );
+            assert_eq!(
+                PgType::Date.decode(&PgFormat::Text, b"-infinity"),
+                Ok(Value::Date(Date::NegativeInfinity))
+            );
+        }
+
+        #[test]
+        fn error_decode_date() {
+            assert_eq!(
+                PgType::Date.decode(&PgFormat::Text, b"2021-02-29"),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::InvalidSyntax {
+                    source: "2021-02-29",
+                    pg_type: PgType::Date
+                }))
+            );
+        }
+
+        #[test]
+        fn decode_midnight_time() {
+            assert_eq!(PgType::Time.decode(&PgFormat::Text, b"00:00:00"), Ok(Value::Time(0)));
+            assert_eq!(
+                PgType::Time.decode(&PgFormat::Text, b"24:00:00"),
+                Ok(Value::Time(24 * 3_600 * MICROS_PER_SECOND))
+            );
+        }
+
+        #[test]
+        fn decode_fractional_time() {
+            assert_eq!(
+                PgType::Time.decode(&PgFormat::Text, b"01:02:03.5"),
+                Ok(Value::Time(3_723_500_000))
+            );
+        }
+
+        #[test]
+        fn error_decode_out_of_range_time() {
+            assert_eq!(
+                PgType::Time.decode(&PgFormat::Text, b"24:00:00.000001"),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::InvalidSyntax {
+                    source: "24:00:00.000001",
+                    pg_type: PgType::Time
+                }))
+            );
+        }
+
+        #[test]
+        fn decode_interval() {
+            assert_eq!(
+                PgType::Interval.decode(&PgFormat::Text, b"1 year 2 mons 3 days 04:05:06"),
+                Ok(Value::Interval {
+                    months: 14,
+                    days: 3,
+                    micros: 14_706_000_000
+                })
+            );
+        }
+
+        #[test]
+        fn error_decode_interval() {
+            assert_eq!(
+                PgType::Interval.decode(&PgFormat::Text, b"1 fortnight"),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::InvalidSyntax {
+                    source: "1 fortnight",
+                    pg_type: PgType::Interval
+                }))
+            );
+        }
+
+        #[test]
+        fn decode_json_object() {
+            assert_eq!(
+                PgType::Json.decode(&PgFormat::Text, b"{\"a\": {\"b\": [true, null]}}"),
+                Ok(Value::Json("{\"a\": {\"b\": [true, null]}}".into()))
+            );
+        }
+
+        #[test]
+        fn decode_json_array() {
+            assert_eq!(
+                PgType::Json.decode(&PgFormat::Text, b"[1, -2.5e3, \"three\"]"),
+                Ok(Value::Json("[1, -2.5e3, \"three\"]".into()))
+            );
+        }
+
+        #[test]
+        fn error_decode_json() {
+            assert_eq!(
+                PgType::Json.decode(&PgFormat::Text, b"{\"a\": }"),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::InvalidJson {
+                    source: "{\"a\": }",
+                    pg_type: PgType::Json
+                }))
+            );
+        }
+
+        #[test]
+        fn decode_jsonb() {
+            assert_eq!(
+                PgType::Jsonb.decode(&PgFormat::Text, b"{\"a\": [1, 2]}"),
+                Ok(Value::Jsonb("{\"a\": [1, 2]}".into()))
+            );
+        }
+
+        #[test]
+        fn decode_int4_array() {
+            assert_eq!(
+                PgType::Int4Array.decode(&PgFormat::Text, b"{1, 2,NULL}"),
+                Ok(Value::Array(vec![Value::Int32(1), Value::Int32(2), Value::Null]))
+            );
+        }
+
+        #[test]
+        fn decode_empty_int4_array() {
+            assert_eq!(
+                PgType::Int4Array.decode(&PgFormat::Text, b"{}"),
+                Ok(Value::Array(vec![]))
+            );
+        }
+
+        #[test]
+        fn decode_bool_array() {
+            assert_eq!(
+                PgType::BoolArray.decode(&PgFormat::Text, b"{t,f,NULL}"),
+                Ok(Value::Array(vec![Value::Bool(true), Value::Bool(false), Value::Null]))
+            );
+        }
+
+        #[test]
+        fn decode_text_array() {
+            assert_eq!(
+                PgType::TextArray.decode(&PgFormat::Text, b"{abc, def ,NULL}"),
+                Ok(Value::Array(vec![
+                    Value::String("abc".to_owned()),
+                    Value::String("def".to_owned()),
+                    Value::Null
+                ]))
+            );
+        }
+
+        #[test]
+        fn decode_quoted_text_array() {
+            assert_eq!(
+                PgType::TextArray.decode(&PgFormat::Text, br#"{"a,b", "c", "NULL", "", "{x}"}"#),
+                Ok(Value::Array(vec![
+                    Value::String("a,b".to_owned()),
+                    Value::String("c".to_owned()),
+                    Value::String("NULL".to_owned()),
+                    Value::String("".to_owned()),
+                    Value::String("{x}".to_owned())
+                ]))
+            );
+        }
+
+        #[test]
+        fn decode_escaped_text_array() {
+            assert_eq!(
+                PgType::TextArray.decode(&PgFormat::Text, br#"{"say \"hi\"","back\\slash"}"#),
+                Ok(Value::Array(vec![
+                    Value::String("say \"hi\"".to_owned()),
+                    Value::String("back\\slash".to_owned())
+                ]))
+            );
+        }
+
+        #[test]
+        fn decode_quoted_int4_array() {
+            assert_eq!(
+                PgType::Int4Array.decode(&PgFormat::Text, br#"{"1",2}"#),
+                Ok(Value::Array(vec![Value::Int32(1), Value::Int32(2)]))
+            );
+        }
+
+        #[test]
+        fn error_decode_text_array() {
+            for malformed in &[r#"{"abc}"#, r#"{"a"b}"#, "{a,,b}", "{a,}", r#"{a"b}"#] {
+                assert_eq!(
+                    PgType::TextArray.decode(&PgFormat::Text, malformed.as_bytes()),
+                    Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::InvalidSyntax {
+                        source: malformed,
+                        pg_type: PgType::TextArray
+                    }))
+                );
+            }
+        }
+
+        #[test]
+        fn error_decode_int4_array() {
+            assert_eq!(
+                PgType::Int4Array.decode(&PgFormat::Text, b"1,2"),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::InvalidSyntax {
+                    source: "1,2",
+                    pg_type: PgType::Int4Array
+                }))
+            );
+        }
+    }
+
+    mod network_addresses {
+        use super::*;
+
+        fn ipv4_host() -> Value {
+            Value::Inet {
+                family: 2,
+                bits: 32,
+                is_cidr: false,
+                addr: vec![192, 168, 0, 1],
+            }
+        }
+
+        fn ipv6_network() -> Value {
+            Value::Inet {
+                family: 3,
+                bits: 32,
+                is_cidr: true,
+                addr: vec![0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            }
+        }
+
+        fn ipv6_network_binary() -> Vec<u8> {
+            [
+                &[3, 32, 1, 16][..],
+                &[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            ]
+            .concat()
+        }
+
+        #[test]
+        fn decode_binary_ipv4_host_address() {
+            assert_eq!(
+                PgType::Inet.decode(&PgFormat::Binary, &[2, 32, 0, 4, 192, 168, 0, 1]),
+                Ok(ipv4_host())
+            );
+        }
+
+        #[test]
+        fn decode_binary_ipv6_cidr() {
+            assert_eq!(
+                PgType::Cidr.decode(&PgFormat::Binary, &ipv6_network_binary()),
+                Ok(ipv6_network())
+            );
+        }
+
+        #[test]
+        fn error_decode_binary() {
+            for malformed in &[
+                &[2, 32, 0][..],
+                &[2, 32, 0, 4, 192, 168, 0],
+                &[2, 33, 0, 4, 192, 168, 0, 1],
+                &[4, 32, 0, 4, 192, 168, 0, 1],
+                &[3, 32, 0, 4, 192, 168, 0, 1],
+            ] {
+                assert_eq!(
+                    PgType::Inet.decode(&PgFormat::Binary, malformed),
+                    Err(TypeValueDecodeError::from(
+                        TypeValueDecodeErrorKind::MalformedNetworkAddress {
+                            source: malformed,
+                            pg_type: PgType::Inet
+                        }
+                    ))
+                );
+            }
+        }
+
+        #[test]
+        fn error_decode_binary_cidr_with_host_bits() {
+            let raw = [2, 24, 1, 4, 192, 168, 0, 1];
+
+            assert_eq!(
+                PgType::Cidr.decode(&PgFormat::Binary, &raw),
+                Err(TypeValueDecodeError::from(
+                    TypeValueDecodeErrorKind::MalformedNetworkAddress {
+                        source: &raw,
+                        pg_type: PgType::Cidr
+                    }
+                ))
+            );
+        }
+
+        #[test]
+        fn decode_text() {
+            assert_eq!(PgType::Inet.decode(&PgFormat::Text, b"192.168.0.1"), Ok(ipv4_host()));
+            assert_eq!(
+                PgType::Inet.decode(&PgFormat::Text, b"192.168.0.1/24"),
+                Ok(Value::Inet {
+                    family: 2,
+                    bits: 24,
+                    is_cidr: false,
+                    addr: vec![192, 168, 0, 1],
+                })
+            );
+            assert_eq!(
+                PgType::Cidr.decode(&PgFormat::Text, b"2001:db8::/32"),
+                Ok(ipv6_network())
+            );
+        }
+
+        #[test]
+        fn error_decode_text() {
+            for (pg_type, malformed) in &[
+                (PgType::Inet, "192.168.0"),
+                (PgType::Inet, "192.168.0.1/33"),
+                (PgType::Inet, "192.168.0.1/"),
+                (PgType::Inet, "::1/-1"),
+                (PgType::Cidr, "192.168.0.1/24"),
+            ] {
+                assert_eq!(
+                    pg_type.decode(&PgFormat::Text, malformed.as_bytes()),
+                    Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::InvalidSyntax {
+                        source: malformed,
+                        pg_type: *pg_type
+                    }))
+                );
+            }
+        }
+
+        #[test]
+        fn encode_text() {
+            assert_eq!(PgType::Inet.encode_text(&ipv4_host()), Ok(b"192.168.0.1".to_vec()));
+            assert_eq!(PgType::Cidr.encode_text(&ipv4_host()), Ok(b"192.168.0.1/32".to_vec()));
+            assert_eq!(PgType::Cidr.encode_text(&ipv6_network()), Ok(b"2001:db8::/32".to_vec()));
+        }
+
+        #[test]
+        fn encode_binary() {
+            assert_eq!(
+                PgType::Inet.encode_binary(&ipv4_host()),
+                Ok(vec![2, 32, 0, 4, 192, 168, 0, 1])
+            );
+            assert_eq!(PgType::Cidr.encode_binary(&ipv6_network()), Ok(ipv6_network_binary()));
+        }
+
+        #[test]
+        fn error_encode_malformed_address() {
+            let value = Value::Inet {
+                family: 2,
+                bits: 8,
+                is_cidr: false,
+                addr: vec![192, 168, 0, 1],
+            };
+
+            assert_eq!(
+                PgType::Cidr.encode_binary(&value),
+                Err(EncodeError::from(EncodeErrorKind::MalformedValue {
+                    value: &value,
+                    pg_type: PgType::Cidr
+                }))
+            );
+        }
+    }
+
+    mod typmod_decoding {
+        use super::*;
+
+        /// `varchar(5)`
+        const TYPMOD: Option<i32> = Some(5 + VARHDRSZ);
+
+        #[test]
+        fn exact_length() {
+            assert_eq!(
+                PgType::VarChar.decode_with_typmod(&PgFormat::Text, b"abcde", TYPMOD),
+                Ok(Value::String("abcde".into()))
+            );
+            assert_eq!(
+                PgType::BpChar.decode_with_typmod(&PgFormat::Binary, "ábcdé".as_bytes(), TYPMOD),
+                Ok(Value::String("ábcdé".into()))
+            );
+        }
+
+        #[test]
+        fn overlong_string() {
+            assert_eq!(
+                PgType::VarChar.decode_with_typmod(&PgFormat::Text, b"abcdef", TYPMOD),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::StringTooLong {
+                    length: 6,
+                    max_length: 5,
+                    pg_type: PgType::VarChar
+                }))
+            );
+            assert_eq!(
+                PgType::Char.decode_with_typmod(&PgFormat::Binary, b"abcdef", TYPMOD),
+                Err(TypeValueDecodeError::from(TypeValueDecodeErrorKind::StringTooLong {
+                    length: 6,
+                    max_length: 5,
+                    pg_type: PgType::Char
+                }))
+            );
+        }
+
+        #[test]
+        fn unconstrained() {
+            for typmod in &[None, Some(-1)] {
+                assert_eq!(
+                    PgType::VarChar.decode_with_typmod(&PgFormat::Text, b"abcdef", *typmod),
+                    Ok(Value::String("abcdef".into()))
+                );
+            }
+            assert_eq!(
+                PgType::Text.decode_with_typmod(&PgFormat::Text, b"abcdef", TYPMOD),
+                Ok(Value::String("abcdef".into()))
+            );
+        }
+    }
+
+    mod round_trip {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn binary(pg_type: PgType, value: &Value) -> Result<Value, String> {
+            let raw = pg_type.encode_binary(value).map_err(|error| error.to_string())?;
+            pg_type
+                .decode(&PgFormat::Binary, &raw)
+                .map_err(|error| error.to_string())
+        }
+
+        fn text(pg_type: PgType, value: &Value) -> Result<Value, String> {
+            let raw = pg_type.encode_text(value).map_err(|error| error.to_string())?;
+            pg_type.decode(&PgFormat::Text, &raw).map_err(|error| error.to_string())
+        }
+
+        proptest! {
+            #[test]
+            fn boolean(v in any::<bool>()) {
+                prop_assert_eq!(binary(PgType::Bool, &Value::Bool(v)), Ok(Value::Bool(v)));
+                prop_assert_eq!(text(PgType::Bool, &Value::Bool(v)), Ok(Value::Bool(v)));
+            }
+
+            #[test]
+            fn small_integer(v in any::<i16>()) {
+                prop_assert_eq!(binary(PgType::SmallInt, &Value::Int16(v)), Ok(Value::Int16(v)));
+                prop_assert_eq!(text(PgType::SmallInt, &Value::Int16(v)), Ok(Value::Int16(v)));
+            }
+
+            #[test]
+            fn integer(v in any::<i32>()) {
+                prop_assert_eq!(binary(PgType::Integer, &Value::Int32(v)), Ok(Value::Int32(v)));
+                prop_assert_eq!(text(PgType::Integer, &Value::Int32(v)), Ok(Value::Int32(v)));
+            }
+
+            #[test]
+            fn big_integer(v in any::<i64>()) {
+                prop_assert_eq!(binary(PgType::BigInt, &Value::Int64(v)), Ok(Value::Int64(v)));
+                prop_assert_eq!(text(PgType::BigInt, &Value::Int64(v)), Ok(Value::Int64(v)));
+            }
+
+            #[test]
+            fn string(v in "[^\\x00]*") {
+                for pg_type in &[PgType::Text, PgType::VarChar] {
+                    prop_assert_eq!(binary(*pg_type, &Value::String(v.clone())), Ok(Value::String(v.clone())));
+                    prop_assert_eq!(text(*pg_type, &Value::String(v.clone())), Ok(Value::String(v.clone())));
+                }
+            }
+
+            #[test]
+            fn money(v in any::<i64>()) {
+                prop_assert_eq!(binary(PgType::Money, &Value::Money(v)), Ok(Value::Money(v)));
+                prop_assert_eq!(text(PgType::Money, &Value::Money(v)), Ok(Value::Money(v)));
+            }
+
+            #[test]
+            fn numeric(v in "-?[0-9]{1,20}(\\.[0-9]{1,20})?") {
+                let normalized = match text(PgType::Numeric, &Value::Numeric(v)) {
+                    Ok(Value::Numeric(normalized)) => normalized,
+                    other => return Err(TestCaseError::fail(format!("{:?}", other))),
+                };
+                prop_assert_eq!(
+                    text(PgType::Numeric, &Value::Numeric(normalized.clone())),
+                    Ok(Value::Numeric(normalized.clone()))
+                );
+                let trimmed = match binary(PgType::Numeric, &Value::Numeric(normalized)) {
+                    Ok(Value::Numeric(trimmed)) => trimmed,
+                    other => return Err(TestCaseError::fail(format!("{:?}", other))),
+                };
+                prop_assert_eq!(
+                    binary(PgType::Numeric, &Value::Numeric(trimmed.clone())),
+                    Ok(Value::Numeric(trimmed))
+                );
+            }
+
+            #[test]
+            fn date(days in any::<i32>()) {
+                let date = || Value::Date(Date::from_days(days));
+                prop_assert_eq!(binary(PgType::Date, &date()), Ok(date()));
+                prop_assert_eq!(text(PgType::Date, &date()), Ok(date()));
+            }
+
+            #[test]
+            fn timestamp(v in any::<i64>()) {
+                prop_assert_eq!(binary(PgType::Timestamp, &Value::Timestamp(v)), Ok(Value::Timestamp(v)));
+                prop_assert_eq!(text(PgType::Timestamp, &Value::Timestamp(v)), Ok(Value::Timestamp(v)));
+                prop_assert_eq!(binary(PgType::TimestampTz, &Value::TimestampTz(v)), Ok(Value::TimestampTz(v)));
+                prop_assert_eq!(text(PgType::TimestampTz, &Value::TimestampTz(v)), Ok(Value::TimestampTz(v)));
+            }
+
+            #[test]
+            fn time(v in 0..=datetime::MICROS_PER_DAY) {
+                prop_assert_eq!(binary(PgType::Time, &Value::Time(v)), Ok(Value::Time(v)));
+                prop_assert_eq!(text(PgType::Time, &Value::Time(v)), Ok(Value::Time(v)));
+            }
+
+            #[test]
+            fn interval(months in any::<i32>(), days in any::<i32>(), micros in any::<i64>()) {
+                let interval = || Value::Interval { months, days, micros };
+                prop_assert_eq!(binary(PgType::Interval, &interval()), Ok(interval()));
+                prop_assert_eq!(text(PgType::Interval, &interval()), Ok(interval()));
+            }
+        }
+    }
+
+    mod accessors {
+        use super::*;
+
+        #[test]
+        fn null() {
+            assert!(Value::Null.is_null());
+            assert!(!Value::Int32(0).is_null());
+            assert!(!Value::String(String::new()).is_null());
+        }
+
+        #[test]
+        fn boolean() {
+            assert_eq!(Value::Bool(true).as_bool(), Some(true));
+            assert_eq!(Value::Bool(false).as_bool(), Some(false));
+            assert_eq!(Value::Int16(1).as_bool(), None);
+            assert_eq!(Value::Null.as_bool(), None);
+        }
+
+        #[test]
+        fn small_integer() {
+            assert_eq!(Value::Int16(-1).as_i16(), Some(-1));
+            assert_eq!(Value::Int32(1).as_i16(), None);
+            assert_eq!(Value::Null.as_i16(), None);
+        }
+
+        #[test]
+        fn integer() {
+            assert_eq!(Value::Int32(i32::MAX).as_i32(), Some(i32::MAX));
+            assert_eq!(Value::Int16(1).as_i32(), None);
+            assert_eq!(Value::Int64(1).as_i32(), None);
+            assert_eq!(Value::Null.as_i32(), None);
+        }
+
+        #[test]
+        fn big_integer() {
+            assert_eq!(Value::Int64(i64::MIN).as_i64(), Some(i64::MIN));
+            assert_eq!(Value::Int32(1).as_i64(), None);
+            assert_eq!(Value::Money(1).as_i64(), None);
+            assert_eq!(Value::Null.as_i64(), None);
+        }
+
+        #[test]
+        fn string() {
+            assert_eq!(Value::String("abc".to_owned()).as_str(), Some("abc"));
+            assert_eq!(Value::Json("{}".to_owned()).as_str(), None);
+            assert_eq!(Value::Int32(1).as_str(), None);
+            assert_eq!(Value::Null.as_str(), None);
+        }
     }
 }
@@ -0,0 +1,108 @@
+// Copyright 2020 - 2021 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// Connection key-value params
+pub type ClientParams = Vec<(String, String)>;
+
+/// Lookup of [ClientParams] by key
+///
+/// ```
+/// use pg_wire::{ClientParams, ClientParamsExt};
+///
+/// let params: ClientParams = vec![
+///     ("user".to_owned(), "postgres".to_owned()),
+///     ("application_name".to_owned(), "psql".to_owned()),
+/// ];
+///
+/// assert_eq!(params.user(), Some("postgres"));
+/// assert_eq!(params.param("application_name"), Some("psql"));
+/// assert_eq!(params.database(), None);
+/// ```
+pub trait ClientParamsExt {
+    /// Value of the parameter with exactly the same key, keys are case-sensitive
+    /// as they are in PostgreSQL. If a key is repeated the first value is returned
+    fn param(&self, key: &str) -> Option<&str>;
+
+    /// Value of the `user` parameter
+    fn user(&self) -> Option<&str> {
+        self.param("user")
+    }
+
+    /// Value of the `database` parameter
+    fn database(&self) -> Option<&str> {
+        self.param("database")
+    }
+}
+
+impl ClientParamsExt for ClientParams {
+    fn param(&self, key: &str) -> Option<&str> {
+        self.iter()
+            .find(|(param, _)| param == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> ClientParams {
+        vec![
+            ("user".to_owned(), "postgres".to_owned()),
+            ("database".to_owned(), "test_db".to_owned()),
+            ("DateStyle".to_owned(), "ISO".to_owned()),
+        ]
+    }
+
+    #[test]
+    fn present_keys() {
+        let params = params();
+
+        assert_eq!(params.user(), Some("postgres"));
+        assert_eq!(params.database(), Some("test_db"));
+        assert_eq!(params.param("DateStyle"), Some("ISO"));
+    }
+
+    #[test]
+    fn absent_keys() {
+        let params: ClientParams = vec![];
+
+        assert_eq!(params.user(), None);
+        assert_eq!(params.database(), None);
+        assert_eq!(params.param("application_name"), None);
+    }
+
+    #[test]
+    fn keys_are_case_sensitive() {
+        let params = params();
+
+        assert_eq!(params.param("datestyle"), None);
+        assert_eq!(params.param("USER"), None);
+    }
+
+    #[test]
+    fn slice_get_is_not_shadowed() {
+        let params = params();
+
+        assert_eq!(params.get(1), Some(&("database".to_owned(), "test_db".to_owned())));
+    }
+
+    #[test]
+    fn first_value_of_repeated_key() {
+        let mut params = params();
+        params.push(("user".to_owned(), "other".to_owned()));
+
+        assert_eq!(params.user(), Some("postgres"));
+    }
+}
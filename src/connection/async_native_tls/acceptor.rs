@@ -3,26 +3,12 @@ use std::marker::Unpin;
 
 use super::handshake::handshake;
 use super::TlsStream;
-use futures_lite::{AsyncRead, AsyncReadExt, AsyncWrite};
-use crate::connection::AcceptError;
+use futures_lite::{AsyncRead, AsyncWrite};
 
 #[derive(Clone)]
 pub struct TlsAcceptor(native_tls::TlsAcceptor);
 
 impl TlsAcceptor {
-    /// Create a new TlsAcceptor based on an identity file and matching password.
-    pub async fn new<R, S>(mut file: R, password: S) -> Result<Self, AcceptError>
-    where
-        R: AsyncRead + Unpin,
-        S: AsRef<str>,
-    {
-        let mut identity = vec![];
-        file.read_to_end(&mut identity).await?;
-
-        let identity = native_tls::Identity::from_pkcs12(&identity, password.as_ref())?;
-        Ok(TlsAcceptor(native_tls::TlsAcceptor::new(identity)?))
-    }
-
     /// Accepts a new client connection with the provided stream.
     ///
     /// This function will internally call `TlsAcceptor::accept` to connect
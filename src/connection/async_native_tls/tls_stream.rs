@@ -23,6 +23,14 @@ impl<S> TlsStream<S> {
         Self(stream)
     }
 
+    /// Returns the certificate that the peer presented during the handshake, if any.
+    pub(crate) fn peer_certificate(&self) -> native_tls::Result<Option<native_tls::Certificate>>
+    where
+        StdAdapter<S>: Read + Write,
+    {
+        self.0.peer_certificate()
+    }
+
     fn with_context<F, R>(&mut self, ctx: &mut Context<'_>, f: F) -> R
     where
         F: FnOnce(&mut native_tls::TlsStream<StdAdapter<S>>) -> R,
@@ -13,11 +13,16 @@
 // limitations under the License.
 
 use crate::{
-    connection::{network::*, AcceptError, ClientRequest, ConnSupervisor, Encryption, ProtocolConfiguration},
-    hand_shake::{HandShakeProcess, HandShakeStatus},
+    connection::{
+        network::*, AcceptError, AuthMethod, ClientRequest, ConnSupervisor, Encryption, ProtocolConfiguration,
+    },
+    hand_shake::{self, HandShakeProcess, HandShakeStatus},
+    proxy_protocol::{ProxyHeader, HEADER_LEN},
     Error,
 };
-use std::io;
+use pg_wire_payload::BackendMessage;
+use rand::Rng;
+use std::{io, net::SocketAddr};
 
 /// A PostgreSql connection server, listening for connections.
 pub struct PgWireListener {
@@ -27,12 +32,26 @@ pub struct PgWireListener {
 }
 
 impl PgWireListener {
+    /// Configuration the listener accepts connections with
+    pub fn protocol_config(&self) -> &ProtocolConfiguration {
+        &self.protocol_config
+    }
+
     /// Accept a new incoming connection from this listener.
     pub async fn accept(&self) -> io::Result<Result<ClientRequest, Error>> {
         match self.network.accept().await {
             Ok((stream, address)) => {
+                stream.set_tcp_options(self.protocol_config.tcp_nodelay(), self.protocol_config.tcp_keepalive())?;
                 let mut channel = Channel::Plain(stream);
-                let mut process = HandShakeProcess::start();
+                let address = if self.protocol_config.proxy_protocol() {
+                    match self.read_proxy_header(&mut channel).await? {
+                        Ok(source) => source.unwrap_or(address),
+                        Err(error) => return Ok(Err(error)),
+                    }
+                } else {
+                    address
+                };
+                let mut process = HandShakeProcess::with_max_message_len(self.protocol_config.max_startup_packet_len());
                 let mut current: Option<Vec<u8>> = None;
                 loop {
                     match process.next_stage(current.as_deref()) {
@@ -43,11 +62,22 @@ impl PgWireListener {
                         }
                         Ok(HandShakeStatus::UpdatingToSecure) => {
                             channel = match channel {
-                                Channel::Plain(mut channel) if self.protocol_config.ssl_support() => {
+                                Channel::Plain(mut channel)
+                                    if self.protocol_config.ssl_support() && self.network.ssl_allowed() =>
+                                {
                                     channel.write_all(Encryption::AcceptSsl.into()).await?;
-                                    match self.protocol_config.ssl_config() {
-                                        Some((path, password)) => {
-                                            match self.network.tls_accept(path, password, channel).await {
+                                    match self.protocol_config.tls_identity() {
+                                        Some(identity) => {
+                                            match self
+                                                .network
+                                                .tls_accept(
+                                                    identity,
+                                                    self.protocol_config.sni_identities(),
+                                                    self.protocol_config.client_ca(),
+                                                    channel,
+                                                )
+                                                .await
+                                            {
                                                 Ok(socket) => Channel::Secure(socket),
                                                 Err(err) => {
                                                     return match err {
@@ -76,13 +106,29 @@ impl PgWireListener {
                                 Ok(Err(Error::secret_keys_have_not_matched()))
                             }
                         }
+                        Ok(HandShakeStatus::Done(_)) if self.protocol_config.ssl_required() && !channel.is_secure() => {
+                            let error = Error::ssl_required();
+                            channel.write_all(&BackendMessage::from(&error).as_vec()).await?;
+                            return Ok(Err(error));
+                        }
                         Ok(HandShakeStatus::Done(props)) => {
+                            let unrecognized_options = hand_shake::unrecognized_protocol_options(&props);
+                            if process.requested_minor_version() > hand_shake::NEWEST_MINOR_VERSION
+                                || !unrecognized_options.is_empty()
+                            {
+                                let negotiate = BackendMessage::NegotiateProtocolVersion {
+                                    newest_minor_version: hand_shake::NEWEST_MINOR_VERSION,
+                                    unrecognized_options,
+                                };
+                                channel.write_all(&negotiate.as_vec()).await?;
+                            }
                             return Ok(Ok(ClientRequest::Connect((
                                 channel,
                                 props,
                                 self.conn_supervisor.clone(),
                                 address,
-                            ))))
+                                self.protocol_config.connection_settings(),
+                            ))));
                         }
                         Err(error) => {
                             return Ok(Err(Error::from(error)));
@@ -93,4 +139,62 @@ impl PgWireListener {
             Err(io_error) => Err(io_error),
         }
     }
+
+    async fn read_proxy_header(&self, channel: &mut Channel) -> io::Result<Result<Option<SocketAddr>, Error>> {
+        let mut header = [0; HEADER_LEN];
+        channel.read_exact(&mut header).await?;
+        let header = match ProxyHeader::parse(&header) {
+            Ok(header) => header,
+            Err(error) => return Ok(Err(Error::from(error))),
+        };
+        let mut addresses = vec![0; header.addresses_len()];
+        channel.read_exact(&mut addresses).await?;
+        Ok(header.source_address(&addresses).map_err(Error::from))
+    }
+
+    /// Sends the first authentication message of [AuthMethod] configured in
+    /// [ProtocolConfiguration]. [AuthMethod::Trust] completes authentication
+    /// right away as [PgWireListener::complete_authentication] does, other
+    /// methods require the server to verify the client response and then
+    /// complete authentication.
+    ///
+    /// [AuthMethod::Md5Password] requests the password hashed with a random
+    /// salt generated for the connection, the salt is returned to verify the
+    /// client response with [verify_md5_password](crate::md5::verify_md5_password).
+    /// Other methods return `None`
+    pub async fn request_authentication(&self, channel: &mut Channel) -> io::Result<Option<[u8; 4]>> {
+        let (request, salt) = match self.protocol_config.auth_method() {
+            AuthMethod::Trust => return self.complete_authentication(channel).await.map(|()| None),
+            AuthMethod::CleartextPassword => (BackendMessage::AuthenticationCleartextPassword, None),
+            AuthMethod::Md5Password => {
+                let salt = rand::thread_rng().gen();
+                (BackendMessage::AuthenticationMd5Password(salt), Some(salt))
+            }
+            AuthMethod::ScramSha256 => (
+                BackendMessage::AuthenticationSasl(vec!["SCRAM-SHA-256".to_owned()]),
+                None,
+            ),
+        };
+        channel.write_all(request.as_vec().as_slice()).await?;
+        channel.flush().await?;
+        Ok(salt)
+    }
+
+    /// Notifies the client that authentication is successfully completed and
+    /// reports backend parameters configured in [ProtocolConfiguration]
+    pub async fn complete_authentication(&self, channel: &mut Channel) -> io::Result<()> {
+        channel
+            .write_all(BackendMessage::AuthenticationOk.as_vec().as_slice())
+            .await?;
+        for (name, value) in self.protocol_config.parameter_statuses() {
+            channel
+                .write_all(
+                    BackendMessage::ParameterStatus(name.clone(), value.clone())
+                        .as_vec()
+                        .as_slice(),
+                )
+                .await?;
+        }
+        channel.flush().await
+    }
 }
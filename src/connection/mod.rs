@@ -14,22 +14,27 @@
 
 use crate::{
     connection::network::*,
-    message_decoder::{MessageDecoder, MessageDecoderStatus},
-    CommandMessage,
+    hand_shake::MAX_STARTUP_PACKET_LEN,
+    hand_shake::PROTOCOL_OPTION_PREFIX,
+    message_decoder::{MessageDecoder, MessageDecoderMode, MessageDecoderStatus, MAX_MESSAGE_LEN},
+    CommandMessage, Error,
 };
 use async_mutex::Mutex as AsyncMutex;
-use futures_lite::future::block_on;
-use pg_wire_payload::{BackendMessage, ConnId, ConnSecretKey};
+use futures_lite::future::{self, block_on};
+use pg_wire_payload::{BackendMessage, ConnId, ConnSecretKey, DatetimeEncoding, ErrorFields, TransactionStatus};
 use rand::Rng;
 use std::{
     collections::{HashMap, VecDeque},
-    io,
+    future::Future,
+    io::{self, IoSlice},
     net::SocketAddr,
-    path::PathBuf,
-    sync::{Arc, Mutex},
+    path::{Path, PathBuf},
+    sync::{atomic::AtomicBool, Arc, Mutex},
+    task::Poll,
+    time::Duration,
 };
 
-#[cfg(feature = "async_io")]
+#[cfg(all(feature = "async_io", not(feature = "async_rustls")))]
 mod async_native_tls;
 pub mod listener;
 pub mod network;
@@ -55,38 +60,125 @@ impl From<std::io::Error> for AcceptError {
     }
 }
 
+/// Builds `native-tls` identity out of the contents of [TlsIdentity] files,
+/// `key` is not used for PKCS#12 archives
+#[cfg(any(
+    test,
+    feature = "tokio_net",
+    all(feature = "async_io", not(feature = "async_rustls"))
+))]
+pub(crate) fn native_tls_identity(
+    identity: &TlsIdentity,
+    certificate: &[u8],
+    key: &[u8],
+) -> Result<native_tls::Identity, native_tls::Error> {
+    match identity {
+        TlsIdentity::Pkcs12 { password, .. } => native_tls::Identity::from_pkcs12(certificate, password),
+        TlsIdentity::Pem { .. } => native_tls::Identity::from_pkcs8(certificate, key),
+    }
+}
+
 type Props = Vec<(String, String)>;
 
+/// Startup packet keys that are not run-time parameters of the session
+const NOT_RUNTIME_PARAMETERS: &[&str] = &["user", "database", "options", "replication"];
+/// SQLSTATE of the error that server reports when it terminates a connection
+const ADMIN_SHUTDOWN: &str = "57P01";
+/// Capacity of the read buffer that [ConnectionReader] keeps between messages,
+/// bigger buffers are shrunk once a message is decoded
+const RETAINED_BUFFER_CAPACITY: usize = 64 * 1024;
+
 /// Represents connection between client and server
 pub struct Connection {
     id: ConnId,
+    secret_key: ConnSecretKey,
     #[allow(dead_code)]
     client_props: Props,
     #[allow(dead_code)]
     address: SocketAddr,
-    channel: Arc<AsyncMutex<Channel>>,
-    supervisor: ConnSupervisor,
-    sender: ResponseSender,
+    reader: ConnectionReader,
+    writer: ConnectionWriter,
 }
 
 impl Connection {
-    /// Create new Connection
+    /// Create new Connection with `settings` of [ProtocolConfiguration] that
+    /// the listener passes in [ClientRequest::Connect]
     pub fn new(
         id: ConnId,
+        secret_key: ConnSecretKey,
         client_props: Props,
         address: SocketAddr,
         channel: Arc<AsyncMutex<Channel>>,
         supervisor: ConnSupervisor,
+        settings: ConnectionSettings,
     ) -> Connection {
-        let sender = ResponseSender::new(channel.clone());
-        Connection {
+        let registration = Arc::new(Registration { id, supervisor });
+        let parameters = client_props
+            .iter()
+            .filter(|(key, _)| {
+                !NOT_RUNTIME_PARAMETERS.contains(&key.as_str()) && !key.starts_with(PROTOCOL_OPTION_PREFIX)
+            })
+            .cloned()
+            .collect();
+        let connection = Connection {
             id,
+            secret_key,
             client_props,
             address,
-            channel,
-            supervisor,
-            sender,
+            reader: ConnectionReader {
+                channel: channel.clone(),
+                decoder_mode: MessageDecoderMode::Command,
+                read_timeout: None,
+                idle_timeout: settings.idle_timeout,
+                max_message_len: settings.max_message_len,
+                buffer: Vec::new(),
+                _registration: registration.clone(),
+            },
+            writer: ConnectionWriter {
+                sender: ResponseSender::new(channel),
+                parameters,
+                datetime_encoding: settings.datetime_encoding,
+                _registration: registration,
+            },
+        };
+        connection.with_default_parameters(&settings.parameter_statuses)
+    }
+
+    /// Adds backend parameters that the server reported to the client, e.g.
+    /// [ProtocolConfiguration::parameter_statuses] that [Connection::new] adds
+    /// from [ConnectionSettings]. Parameters that the client set in the startup
+    /// packet are kept
+    pub fn with_default_parameters(mut self, parameters: &[(String, String)]) -> Connection {
+        for (name, value) in parameters {
+            self.writer
+                .parameters
+                .entry(name.clone())
+                .or_insert_with(|| value.clone());
         }
+        self
+    }
+
+    /// Current value of run-time parameter `name`, e.g. `client_encoding` or
+    /// `DateStyle`. Parameters are initialized from the startup packet and
+    /// [ConnectionSettings::parameter_statuses]
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.writer.parameter(name)
+    }
+
+    /// Stores the new value of run-time parameter `name` and reports it to
+    /// the client with `ParameterStatus`, see [ResponseSender::report_parameter]
+    pub fn set_parameter<N: ToString, V: ToString>(&mut self, name: N, value: V) -> io::Result<()> {
+        self.writer.set_parameter(name, value)
+    }
+
+    /// Connection ID
+    pub fn id(&self) -> ConnId {
+        self.id
+    }
+
+    /// Connection secret key that client has to send to cancel queries
+    pub fn secret_key(&self) -> ConnSecretKey {
+        self.secret_key
     }
 
     /// Client Address
@@ -96,32 +188,180 @@ impl Connection {
 
     /// Create [ResponseSender] to send queries result to the client
     pub fn sender(&self) -> ResponseSender {
-        self.sender.clone()
+        self.writer.sender()
+    }
+
+    /// Current transaction status that [ResponseSender::send_ready_for_query] reports
+    pub fn transaction_status(&self) -> TransactionStatus {
+        self.writer.transaction_status()
+    }
+
+    /// Binary representation of `timestamp` and `timestamptz` values that the
+    /// client expects as advertised by the `integer_datetimes` parameter status,
+    /// see [BackendMessage::typed_data_row_with_datetimes] and [PgType::decode_with_datetimes](pg_wire_payload::PgType::decode_with_datetimes)
+    pub fn datetime_encoding(&self) -> DatetimeEncoding {
+        self.writer.datetime_encoding()
+    }
+
+    /// Sets transaction status for all senders of the connection. It is up to
+    /// the server to track when transaction block starts, fails and ends
+    pub fn set_transaction_status(&self, status: TransactionStatus) {
+        self.writer.set_transaction_status(status);
+    }
+
+    /// Sets how [Connection::receive] decodes messages during SASL authentication.
+    /// Server has to switch it back to [MessageDecoderMode::Command] when authentication is finished
+    pub fn set_decoder_mode(&mut self, mode: MessageDecoderMode) {
+        self.reader.set_decoder_mode(mode);
+    }
+
+    /// Sets how long [Connection::receive] waits for each part of a client
+    /// message before failing with [io::ErrorKind::TimedOut]. `None` means waiting forever
+    pub fn set_read_timeout(&mut self, read_timeout: Option<Duration>) {
+        self.reader.set_read_timeout(read_timeout);
+    }
+
+    /// Sets how long [Connection::receive] waits for the next client message
+    /// to start. Exceeding it is considered as client `Terminate`, see
+    /// [ProtocolConfiguration::idle_timeout]. `None` means waiting forever
+    pub fn set_idle_timeout(&mut self, idle_timeout: Option<Duration>) {
+        self.reader.set_idle_timeout(idle_timeout);
+    }
+
+    /// Receive client messages. Messages that can not be decoded are reported
+    /// with [Error] that can be sent back to the client as `ErrorResponse`
+    pub async fn receive(&mut self) -> io::Result<Result<CommandMessage, Error>> {
+        self.reader.receive().await
+    }
+
+    /// Splits the connection into halves that receive client messages and
+    /// send responses independently, e.g. from different tasks. Connection ID
+    /// is freed when both halves are dropped
+    pub fn split(self) -> (ConnectionReader, ConnectionWriter) {
+        (self.reader, self.writer)
+    }
+}
+
+/// Frees connection ID when the last half of [Connection] is dropped
+struct Registration {
+    id: ConnId,
+    supervisor: ConnSupervisor,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        self.supervisor.free(self.id);
+    }
+}
+
+/// Receiving half of [Connection], see [Connection::split]
+pub struct ConnectionReader {
+    channel: Arc<AsyncMutex<Channel>>,
+    decoder_mode: MessageDecoderMode,
+    read_timeout: Option<Duration>,
+    idle_timeout: Option<Duration>,
+    max_message_len: usize,
+    buffer: Vec<u8>,
+    _registration: Arc<Registration>,
+}
+
+impl ConnectionReader {
+    /// Sets how [ConnectionReader::receive] decodes messages, see [Connection::set_decoder_mode]
+    pub fn set_decoder_mode(&mut self, mode: MessageDecoderMode) {
+        self.decoder_mode = mode;
+    }
+
+    /// Sets how long [ConnectionReader::receive] waits for each part of a
+    /// client message, see [Connection::set_read_timeout]
+    pub fn set_read_timeout(&mut self, read_timeout: Option<Duration>) {
+        self.read_timeout = read_timeout;
+    }
+
+    /// Sets how long [ConnectionReader::receive] waits for the next client
+    /// message to start, see [Connection::set_idle_timeout]
+    pub fn set_idle_timeout(&mut self, idle_timeout: Option<Duration>) {
+        self.idle_timeout = idle_timeout;
     }
 
-    async fn read_frontend_message(&mut self) -> io::Result<Result<CommandMessage, ()>> {
-        let mut current: Option<Vec<u8>> = None;
-        let mut message_decoder = MessageDecoder::default();
+    /// Reads exactly `buffer.len()` bytes. The channel is locked only while it
+    /// is polled, so senders are not blocked while the client has nothing to send
+    async fn read_exact(&self, buffer: &mut [u8]) -> io::Result<()> {
+        let mut filled = 0;
+        while filled < buffer.len() {
+            let polled = {
+                let mut channel = self.channel.lock().await;
+                let read = channel.read(&mut buffer[filled..]);
+                futures_lite::pin!(read);
+                future::poll_fn(|cx| Poll::Ready(read.as_mut().poll(cx))).await
+            };
+            match polled {
+                Poll::Ready(Ok(0)) => return Err(io::Error::from(io::ErrorKind::UnexpectedEof)),
+                Poll::Ready(Ok(read)) => filled += read,
+                Poll::Ready(Err(error)) if error.kind() == io::ErrorKind::Interrupted => {}
+                Poll::Ready(Err(error)) => return Err(error),
+                Poll::Pending => {
+                    // the channel wakes the task up once it has bytes to read
+                    let mut woken = false;
+                    future::poll_fn(|_| {
+                        if woken {
+                            Poll::Ready(())
+                        } else {
+                            woken = true;
+                            Poll::Pending
+                        }
+                    })
+                    .await
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads each stage of a message into the buffer kept between messages
+    async fn read_frontend_message(&mut self) -> io::Result<Result<CommandMessage, Error>> {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        let result = self.decode_frontend_message(&mut buffer).await;
+        buffer.clear();
+        buffer.shrink_to(RETAINED_BUFFER_CAPACITY);
+        self.buffer = buffer;
+        result
+    }
+
+    async fn decode_frontend_message(&self, buffer: &mut Vec<u8>) -> io::Result<Result<CommandMessage, Error>> {
+        let mut message_decoder = MessageDecoder::with_max_message_len(self.max_message_len);
+        message_decoder.set_mode(self.decoder_mode);
+        let mut idle = true;
+        let mut filled = false;
         loop {
-            match message_decoder.next_stage(current.take().as_deref()) {
+            let current = if filled { Some(buffer.as_slice()) } else { None };
+            match message_decoder.next_stage(current) {
                 Ok(MessageDecoderStatus::Requesting(len)) => {
-                    let mut buffer = vec![b'0'; len];
-                    self.channel.lock().await.read_exact(&mut buffer).await?;
-                    current = Some(buffer);
+                    buffer.clear();
+                    buffer.resize(len, 0);
+                    match (self.idle_timeout, self.read_timeout) {
+                        (Some(duration), _) if idle => match timeout(duration, self.read_exact(buffer)).await {
+                            Err(error) if error.kind() == io::ErrorKind::TimedOut => {
+                                return Ok(Ok(CommandMessage::Terminate))
+                            }
+                            read => read?,
+                        },
+                        (_, Some(duration)) => timeout(duration, self.read_exact(buffer)).await?,
+                        (_, None) => self.read_exact(buffer).await?,
+                    };
+                    idle = false;
+                    filled = true;
                 }
                 Ok(MessageDecoderStatus::Done(message)) => return Ok(Ok(message)),
-                Err(_error) => {
-                    return Ok(Err(()));
-                }
+                Err(error) => return Ok(Err(Error::from(error))),
             }
         }
     }
 
-    /// Receive client messages
-    pub async fn receive(&mut self) -> io::Result<Result<CommandMessage, ()>> {
+    /// Receive client messages, see [Connection::receive]
+    pub async fn receive(&mut self) -> io::Result<Result<CommandMessage, Error>> {
         let message = match self.read_frontend_message().await {
             Ok(Ok(message)) => message,
-            Ok(Err(_err)) => return Ok(Err(())),
+            Ok(Err(error)) => return Ok(Err(error)),
             Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
                 // Client disconnected the socket immediately without sending a
                 // Terminate message. Considers it as a client Terminate to save
@@ -134,51 +374,211 @@ impl Connection {
     }
 }
 
-impl Drop for Connection {
-    fn drop(&mut self) {
-        self.supervisor.free(self.id);
+/// Sending half of [Connection], see [Connection::split]
+pub struct ConnectionWriter {
+    sender: ResponseSender,
+    parameters: HashMap<String, String>,
+    datetime_encoding: DatetimeEncoding,
+    _registration: Arc<Registration>,
+}
+
+impl ConnectionWriter {
+    /// Create [ResponseSender] to send queries result to the client
+    pub fn sender(&self) -> ResponseSender {
+        self.sender.clone()
+    }
+
+    /// Current transaction status that [ResponseSender::send_ready_for_query] reports
+    pub fn transaction_status(&self) -> TransactionStatus {
+        self.sender.transaction_status()
+    }
+
+    /// Sets transaction status for all senders of the connection, see [Connection::set_transaction_status]
+    pub fn set_transaction_status(&self, status: TransactionStatus) {
+        self.sender.set_transaction_status(status);
+    }
+
+    /// Current value of run-time parameter `name`, see [Connection::parameter]
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters.get(name).map(String::as_str)
+    }
+
+    /// Binary representation of date and time values, see [Connection::datetime_encoding]
+    pub fn datetime_encoding(&self) -> DatetimeEncoding {
+        self.datetime_encoding
+    }
+
+    /// Stores the new value of run-time parameter `name` and reports it to
+    /// the client, see [Connection::set_parameter]
+    pub fn set_parameter<N: ToString, V: ToString>(&mut self, name: N, value: V) -> io::Result<()> {
+        let (name, value) = (name.to_string(), value.to_string());
+        self.sender.report_parameter(&name, &value)?;
+        self.parameters.insert(name, value);
+        Ok(())
+    }
+}
+
+impl Sender for ConnectionWriter {
+    fn flush(&self) -> io::Result<()> {
+        self.sender.flush()
+    }
+
+    fn send(&self, message: BackendMessage) -> io::Result<()> {
+        self.sender.send(message)
+    }
+
+    fn send_batch(&self, messages: &[BackendMessage]) -> io::Result<()> {
+        self.sender.send_batch(messages)
     }
 }
 
 /// Client request accepted from a client
 pub enum ClientRequest {
     /// Connection data and "infrastructure" to perform queries
-    Connect((network::Channel, Props, ConnSupervisor, SocketAddr)),
+    Connect((network::Channel, Props, ConnSupervisor, SocketAddr, ConnectionSettings)),
     /// Connection to cancel queries of another client
     QueryCancellation(ConnId),
 }
 
+/// Settings of [ProtocolConfiguration] that apply to each accepted
+/// [Connection], see [ProtocolConfiguration::connection_settings]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConnectionSettings {
+    max_message_len: usize,
+    idle_timeout: Option<Duration>,
+    datetime_encoding: DatetimeEncoding,
+    parameter_statuses: Vec<(String, String)>,
+}
+
+/// Settings of [ProtocolConfiguration::not_secure]
+impl Default for ConnectionSettings {
+    fn default() -> ConnectionSettings {
+        ProtocolConfiguration::not_secure().connection_settings()
+    }
+}
+
+impl ConnectionSettings {
+    /// maximum length of a client message that the connection accepts
+    pub fn max_message_len(&self) -> usize {
+        self.max_message_len
+    }
+
+    /// how long the connection waits for the next client message, see
+    /// [Connection::set_idle_timeout]
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout
+    }
+
+    /// binary representation of date and time values, see [Connection::datetime_encoding]
+    pub fn datetime_encoding(&self) -> DatetimeEncoding {
+        self.datetime_encoding
+    }
+
+    /// backend parameters that were reported to the client after
+    /// authentication, the connection starts with them unless the client set
+    /// them in the startup packet, see [Connection::parameter]
+    pub fn parameter_statuses(&self) -> &[(String, String)] {
+        &self.parameter_statuses
+    }
+}
+
 /// Responsible for sending messages back to client
 #[derive(Clone)]
 pub struct ResponseSender {
     channel: Arc<AsyncMutex<Channel>>,
+    transaction_status: Arc<Mutex<TransactionStatus>>,
 }
 
 impl ResponseSender {
     pub(crate) fn new(channel: Arc<AsyncMutex<Channel>>) -> ResponseSender {
-        ResponseSender { channel }
+        ResponseSender {
+            channel,
+            transaction_status: Arc::new(Mutex::new(TransactionStatus::Idle)),
+        }
+    }
+
+    fn transaction_status(&self) -> TransactionStatus {
+        *self.transaction_status.lock().unwrap()
+    }
+
+    fn set_transaction_status(&self, status: TransactionStatus) {
+        *self.transaction_status.lock().unwrap() = status;
+    }
+
+    /// Sends `ReadyForQuery` with transaction status of the [Connection]
+    pub fn send_ready_for_query(&self) -> io::Result<()> {
+        self.send(BackendMessage::ReadyForQueryWithStatus(self.transaction_status()))
+    }
+
+    /// Reports to the client that backend parameter `name` changed its value,
+    /// e.g. after `SET client_encoding` or `SET search_path` was executed.
+    /// The protocol allows `ParameterStatus` between any other messages, thus
+    /// it is safe to call in between commands
+    pub fn report_parameter<N: ToString, V: ToString>(&self, name: N, value: V) -> io::Result<()> {
+        self.send(BackendMessage::ParameterStatus(name.to_string(), value.to_string()))
+    }
+
+    /// Sends `FATAL` `ErrorResponse` with `admin_shutdown` code and flushes it,
+    /// e.g. when the server shuts down or an administrator terminates the
+    /// connection. The caller should then drop the [Connection] to close the socket
+    pub fn fatal_disconnect(&self, message: &str) -> io::Result<()> {
+        self.send_all_then_flush(&[BackendMessage::ErrorResponse(ErrorFields::new(
+            "FATAL",
+            ADMIN_SHUTDOWN,
+            message,
+        ))])
+    }
+
+    fn send_vectored(&self, buffers: &[Vec<u8>]) -> io::Result<()> {
+        block_on(async {
+            let mut channel = self.channel.lock().await;
+            if !channel.is_write_vectored() {
+                return channel.write_all(buffers.concat().as_slice()).await;
+            }
+            let mut buffers = buffers
+                .iter()
+                .filter(|buffer| !buffer.is_empty())
+                .collect::<VecDeque<_>>();
+            let mut offset = 0;
+            while let Some(first) = buffers.front() {
+                let slices = std::iter::once(IoSlice::new(&first[offset..]))
+                    .chain(buffers.iter().skip(1).map(|buffer| IoSlice::new(buffer)))
+                    .collect::<Vec<IoSlice>>();
+                let mut written = channel.write_vectored(&slices).await?;
+                if written == 0 {
+                    return Err(io::Error::from(io::ErrorKind::WriteZero));
+                }
+                while let Some(first) = buffers.front() {
+                    if written < first.len() - offset {
+                        offset += written;
+                        break;
+                    }
+                    written -= first.len() - offset;
+                    offset = 0;
+                    buffers.pop_front();
+                }
+            }
+            Ok(())
+        })
     }
 }
 
 impl Sender for ResponseSender {
     fn flush(&self) -> io::Result<()> {
-        block_on(async {
-            self.channel.lock().await.flush().await.expect("OK");
-        });
-
-        Ok(())
+        block_on(async { self.channel.lock().await.flush().await })
     }
 
     fn send(&self, message: BackendMessage) -> io::Result<()> {
-        block_on(async {
-            self.channel
-                .lock()
-                .await
-                .write_all(message.as_vec().as_slice())
-                .await
-                .expect("OK");
-        });
-        Ok(())
+        block_on(async { self.channel.lock().await.write_all(message.as_vec().as_slice()).await })
+    }
+
+    /// Writes all `messages` while holding the channel, so that messages sent
+    /// concurrently through clones of the sender are not interleaved with
+    /// them. Encoded messages are passed to the channel as separate buffers if
+    /// it supports vectored writes, otherwise they are copied into one buffer
+    fn send_batch(&self, messages: &[BackendMessage]) -> io::Result<()> {
+        let buffers = messages.iter().map(BackendMessage::as_vec).collect::<Vec<Vec<u8>>>();
+        self.send_vectored(&buffers)
     }
 }
 
@@ -191,6 +591,21 @@ pub trait Sender: Send + Sync {
     /// Sends response messages to client. Most of the time it is a single
     /// message, select result one of the exceptional situation
     fn send(&self, message: BackendMessage) -> io::Result<()>;
+
+    /// Sends response messages to client in the order they are in `messages`
+    fn send_batch(&self, messages: &[BackendMessage]) -> io::Result<()> {
+        for message in messages {
+            self.send(message.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Sends response messages to client as [Sender::send_batch] does and
+    /// then flushes the output stream
+    fn send_all_then_flush(&self, messages: &[BackendMessage]) -> io::Result<()> {
+        self.send_batch(messages)?;
+        self.flush()
+    }
 }
 
 /// Manages allocation of Connection IDs and secret keys.
@@ -223,23 +638,66 @@ impl ConnSupervisor {
     pub fn verify(&self, conn_id: ConnId, secret_key: ConnSecretKey) -> bool {
         self.inner.lock().unwrap().verify(conn_id, secret_key)
     }
+
+    /// Returns cancellation token of the specified Connection ID. The token
+    /// is created on [alloc](ConnSupervisor::alloc) and dropped on
+    /// [free](ConnSupervisor::free), a cancel request handler sets it after
+    /// [verify](ConnSupervisor::verify) and the connection polls it
+    pub fn token(&self, conn_id: ConnId) -> Option<Arc<AtomicBool>> {
+        self.inner.lock().unwrap().cancellation_tokens.get(&conn_id).cloned()
+    }
+
+    /// Returns number of currently allocated Connection IDs.
+    pub fn active_count(&self) -> usize {
+        self.inner.lock().unwrap().current_mapping.len()
+    }
+
+    /// Returns currently allocated Connection IDs in ascending order.
+    pub fn active_ids(&self) -> Vec<ConnId> {
+        let mut ids = self
+            .inner
+            .lock()
+            .unwrap()
+            .current_mapping
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Returns whether all Connection IDs are allocated and
+    /// [alloc](ConnSupervisor::alloc) would fail. A server can check it to
+    /// reject a client with `too many clients` error before the handshake
+    pub fn is_full(&self) -> bool {
+        self.inner.lock().unwrap().is_full()
+    }
+
+    /// Returns number of Connection IDs that the supervisor can allocate at once.
+    pub fn capacity(&self) -> usize {
+        self.inner.lock().unwrap().capacity()
+    }
 }
 
 struct ConnSupervisorInner {
+    min_id: ConnId,
     next_id: ConnId,
     max_id: ConnId,
     free_ids: VecDeque<ConnId>,
     current_mapping: HashMap<ConnId, ConnSecretKey>,
+    cancellation_tokens: HashMap<ConnId, Arc<AtomicBool>>,
 }
 
 impl ConnSupervisorInner {
     /// Creates a new Connection Supervisor.
     pub fn new(min_id: ConnId, max_id: ConnId) -> ConnSupervisorInner {
         ConnSupervisorInner {
+            min_id,
             next_id: min_id,
             max_id,
             free_ids: VecDeque::new(),
             current_mapping: HashMap::new(),
+            cancellation_tokens: HashMap::new(),
         }
     }
 
@@ -248,12 +706,15 @@ impl ConnSupervisorInner {
         let conn_id = self.generate_conn_id()?;
         let secret_key = rand::thread_rng().gen();
         self.current_mapping.insert(conn_id, secret_key);
+        self.cancellation_tokens
+            .insert(conn_id, Arc::new(AtomicBool::new(false)));
         Ok((conn_id, secret_key))
     }
 
     /// Releases a Connection ID back to the pool.
     fn free(&mut self, conn_id: ConnId) {
         if self.current_mapping.remove(&conn_id).is_some() {
+            self.cancellation_tokens.remove(&conn_id);
             self.free_ids.push_back(conn_id);
         }
     }
@@ -266,6 +727,14 @@ impl ConnSupervisorInner {
         }
     }
 
+    fn is_full(&self) -> bool {
+        self.free_ids.is_empty() && self.next_id > self.max_id
+    }
+
+    fn capacity(&self) -> usize {
+        (self.max_id as i64 - self.min_id as i64 + 1).max(0) as usize
+    }
+
     fn generate_conn_id(&mut self) -> Result<ConnId, ()> {
         match self.free_ids.pop_front() {
             Some(id) => Ok(id),
@@ -303,32 +772,316 @@ impl From<Encryption> for &'static [u8] {
 /// PostgreSQL Wire Protocol supports `ssl`/`tls` and `gss` encryption
 pub struct ProtocolConfiguration {
     ssl_conf: Option<(PathBuf, String)>,
+    tls_identity: Option<TlsIdentity>,
+    sni_identities: Vec<(String, PathBuf)>,
+    client_ca: Option<PathBuf>,
+    parameter_statuses: Vec<(String, String)>,
+    auth_method: AuthMethod,
+    proxy_protocol: bool,
+    idle_timeout: Option<Duration>,
+    ssl_required: bool,
+    tcp_nodelay: bool,
+    tcp_keepalive: Option<Duration>,
+    max_message_len: usize,
+}
+
+/// Authentication method that the server requests from the client after the
+/// handshake, see [PgWireListener::request_authentication](listener::PgWireListener::request_authentication)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AuthMethod {
+    /// Client is allowed to connect without a password
+    Trust,
+    /// Client has to send password in clear text
+    CleartextPassword,
+    /// Client has to send password hashed with `md5`
+    Md5Password,
+    /// Client has to authenticate with `SCRAM-SHA-256` SASL mechanism
+    ScramSha256,
+}
+
+/// Certificate and private key that the server presents to clients during
+/// TLS handshake
+#[derive(Clone, Debug, PartialEq)]
+pub enum TlsIdentity {
+    /// PKCS#12 archive (`.pfx`) protected with password
+    Pkcs12 {
+        /// archive file
+        path: PathBuf,
+        /// password of the archive
+        password: String,
+    },
+    /// PEM certificate chain with PKCS#8 private key that is not encrypted
+    Pem {
+        /// file with the certificate chain
+        certificate: PathBuf,
+        /// file with the private key
+        key: PathBuf,
+    },
 }
 
 #[allow(dead_code)]
 impl ProtocolConfiguration {
     /// Creates configuration that support neither `ssl` nor `gss` encryption
     pub fn not_secure() -> Self {
-        Self { ssl_conf: None }
+        Self {
+            ssl_conf: None,
+            tls_identity: None,
+            sni_identities: vec![],
+            client_ca: None,
+            parameter_statuses: Self::default_parameter_statuses(),
+            auth_method: AuthMethod::CleartextPassword,
+            proxy_protocol: false,
+            idle_timeout: None,
+            ssl_required: false,
+            tcp_nodelay: true,
+            tcp_keepalive: None,
+            max_message_len: MAX_MESSAGE_LEN,
+        }
     }
 
-    /// Creates configuration that support only `ssl`
+    /// Creates configuration that support only `ssl` with PKCS#12 archive
     pub fn with_ssl(cert: PathBuf, password: String) -> Self {
+        Self::with_tls_identity(TlsIdentity::Pkcs12 { path: cert, password })
+    }
+
+    /// Creates configuration that support only `ssl` with PEM certificate
+    /// chain and private key files
+    pub fn with_pem_ssl(certificate: PathBuf, key: PathBuf) -> Self {
+        Self::with_tls_identity(TlsIdentity::Pem { certificate, key })
+    }
+
+    /// Creates configuration that support only `ssl` with either form of identity
+    pub fn with_tls_identity(identity: TlsIdentity) -> Self {
+        let ssl_conf = match &identity {
+            TlsIdentity::Pkcs12 { path, password } => Some((path.clone(), password.clone())),
+            TlsIdentity::Pem { .. } => None,
+        };
         Self {
-            ssl_conf: Some((cert, password)),
+            ssl_conf,
+            tls_identity: Some(identity),
+            sni_identities: vec![],
+            client_ca: None,
+            parameter_statuses: Self::default_parameter_statuses(),
+            auth_method: AuthMethod::CleartextPassword,
+            proxy_protocol: false,
+            idle_timeout: None,
+            ssl_required: false,
+            tcp_nodelay: true,
+            tcp_keepalive: None,
+            max_message_len: MAX_MESSAGE_LEN,
         }
     }
 
+    /// Replaces backend parameters that are reported to the client after
+    /// successful authentication
+    pub fn with_parameter_statuses(mut self, parameter_statuses: Vec<(String, String)>) -> Self {
+        self.parameter_statuses = parameter_statuses;
+        self
+    }
+
+    /// backend parameters that are reported to the client after successful authentication
+    pub fn parameter_statuses(&self) -> &[(String, String)] {
+        &self.parameter_statuses
+    }
+
+    /// Replaces authentication method that is requested from the client,
+    /// [AuthMethod::CleartextPassword] by default
+    pub fn with_auth_method(mut self, auth_method: AuthMethod) -> Self {
+        self.auth_method = auth_method;
+        self
+    }
+
+    /// authentication method that is requested from the client
+    pub fn auth_method(&self) -> AuthMethod {
+        self.auth_method
+    }
+
+    /// Makes the listener expect PROXY protocol v2 header before any client
+    /// bytes and use the source address from it as the client address,
+    /// disabled by default
+    pub fn with_proxy_protocol(mut self, proxy_protocol: bool) -> Self {
+        self.proxy_protocol = proxy_protocol;
+        self
+    }
+
+    /// returns `true` if connections are expected to start with PROXY protocol v2 header
+    pub fn proxy_protocol(&self) -> bool {
+        self.proxy_protocol
+    }
+
+    /// Makes the listener refuse clients that start up without negotiating
+    /// `ssl` first, disabled by default
+    pub fn with_ssl_required(mut self, ssl_required: bool) -> Self {
+        self.ssl_required = ssl_required;
+        self
+    }
+
+    /// returns `true` if connections without `ssl` are refused
+    pub fn ssl_required(&self) -> bool {
+        self.ssl_required
+    }
+
+    /// Sets how long a connection may stay idle between client messages
+    /// before it is considered terminated, see [Connection::set_idle_timeout].
+    /// It is applied to connections built with [ConnectionSettings] of
+    /// [ClientRequest::Connect]. Connections wait forever by default
+    pub fn with_idle_timeout(mut self, idle_timeout: Option<Duration>) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// how long a connection may stay idle between client messages
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout
+    }
+
+    /// Sets `TCP_NODELAY` on accepted TCP connections so that small responses
+    /// of extended query flow are not delayed by Nagle's algorithm, enabled by default
+    pub fn with_tcp_nodelay(mut self, tcp_nodelay: bool) -> Self {
+        self.tcp_nodelay = tcp_nodelay;
+        self
+    }
+
+    /// returns `true` if `TCP_NODELAY` is set on accepted TCP connections
+    pub fn tcp_nodelay(&self) -> bool {
+        self.tcp_nodelay
+    }
+
+    /// Enables `SO_KEEPALIVE` on accepted TCP connections, the OS starts to
+    /// probe a connection after it stays idle for `tcp_keepalive`.
+    /// Keepalive is left to the OS defaults (usually disabled) by default
+    pub fn with_tcp_keepalive(mut self, tcp_keepalive: Option<Duration>) -> Self {
+        self.tcp_keepalive = tcp_keepalive;
+        self
+    }
+
+    /// idle time of TCP connections after which keepalive probes are sent
+    pub fn tcp_keepalive(&self) -> Option<Duration> {
+        self.tcp_keepalive
+    }
+
+    /// Sets maximum length of a client message, longer messages are rejected
+    /// with an error before they are read. Startup packets are additionally
+    /// limited to 10 000 bytes as PostgreSQL does. 1 GB by default
+    pub fn with_max_message_len(mut self, max_message_len: usize) -> Self {
+        self.max_message_len = max_message_len;
+        self
+    }
+
+    /// maximum length of a client message
+    pub fn max_message_len(&self) -> usize {
+        self.max_message_len
+    }
+
+    /// maximum length of a startup packet, that is [ProtocolConfiguration::max_message_len]
+    /// but no more than PostgreSQL accepts
+    pub(crate) fn max_startup_packet_len(&self) -> usize {
+        self.max_message_len.min(MAX_STARTUP_PACKET_LEN)
+    }
+
+    /// Settings that the listener passes to accepted connections in
+    /// [ClientRequest::Connect]
+    pub fn connection_settings(&self) -> ConnectionSettings {
+        ConnectionSettings {
+            max_message_len: self.max_message_len,
+            idle_timeout: self.idle_timeout,
+            datetime_encoding: self.datetime_encoding(),
+            parameter_statuses: self.parameter_statuses.clone(),
+        }
+    }
+
+    /// Sets binary representation of date and time values by replacing the
+    /// `integer_datetimes` parameter status that is reported to clients,
+    /// [DatetimeEncoding::Integer] by default
+    pub fn with_datetime_encoding(mut self, datetime_encoding: DatetimeEncoding) -> Self {
+        let value = match datetime_encoding {
+            DatetimeEncoding::Integer => "on",
+            DatetimeEncoding::Float => "off",
+        };
+        match self
+            .parameter_statuses
+            .iter_mut()
+            .find(|(name, _)| name == "integer_datetimes")
+        {
+            Some((_, current)) => *current = value.to_owned(),
+            None => self
+                .parameter_statuses
+                .push(("integer_datetimes".to_owned(), value.to_owned())),
+        }
+        self
+    }
+
+    /// binary representation of date and time values advertised to clients
+    /// by the `integer_datetimes` parameter status
+    pub fn datetime_encoding(&self) -> DatetimeEncoding {
+        match self
+            .parameter_statuses
+            .iter()
+            .find(|(name, _)| name == "integer_datetimes")
+        {
+            Some((_, value)) if value == "off" => DatetimeEncoding::Float,
+            _ => DatetimeEncoding::Integer,
+        }
+    }
+
+    fn default_parameter_statuses() -> Vec<(String, String)> {
+        vec![
+            ("client_encoding".to_owned(), "UTF8".to_owned()),
+            ("DateStyle".to_owned(), "ISO".to_owned()),
+            ("integer_datetimes".to_owned(), "on".to_owned()),
+            ("server_version".to_owned(), "12.4".to_owned()),
+        ]
+    }
+
     /// returns `true` if support `ssl` connection
     pub fn ssl_support(&self) -> bool {
-        self.ssl_conf.is_some()
+        self.tls_identity.is_some()
     }
 
-    /// cert file and its password
+    /// cert file and its password, `None` if the server is configured with
+    /// PEM files, see [ProtocolConfiguration::tls_identity]
     pub fn ssl_config(&self) -> Option<&(PathBuf, String)> {
         self.ssl_conf.as_ref()
     }
 
+    /// certificate and private key that are presented to clients
+    pub fn tls_identity(&self) -> Option<&TlsIdentity> {
+        self.tls_identity.as_ref()
+    }
+
+    /// Replaces certificates that are presented to clients which request one
+    /// of the server names with SNI during TLS handshake, other clients get the
+    /// certificate of [ProtocolConfiguration::with_ssl]. Files have the same
+    /// format as the default one.
+    ///
+    /// Only `async_rustls` backend selects certificates by SNI, `native-tls`
+    /// does not support it and always presents the default certificate
+    pub fn with_sni_identities(mut self, sni_identities: Vec<(String, PathBuf)>) -> Self {
+        self.sni_identities = sni_identities;
+        self
+    }
+
+    /// server names and cert files that are selected by SNI
+    pub fn sni_identities(&self) -> &[(String, PathBuf)] {
+        &self.sni_identities
+    }
+
+    /// Requires clients to present a certificate signed by one of the CA
+    /// certificates in the PEM file during TLS handshake, the certificate is
+    /// available with `channel.peer_certificate()`.
+    ///
+    /// Only `async_rustls` backend requests client certificates, `native-tls`
+    /// does not support it and ignores the setting
+    pub fn with_client_ca(mut self, ca: PathBuf) -> Self {
+        self.client_ca = Some(ca);
+        self
+    }
+
+    /// file with CA certificates that client certificates are verified against
+    pub fn client_ca(&self) -> Option<&Path> {
+        self.client_ca.as_deref()
+    }
+
     /// returns `true` if support `gss` encrypted connection
     pub fn gssenc_support(&self) -> bool {
         false
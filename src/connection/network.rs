@@ -22,6 +22,22 @@ cfg_if::cfg_if! {
     }
 }
 
+#[cfg(any(feature = "async_io", feature = "tokio_net"))]
+use socket2::{SockRef, TcpKeepalive};
+#[cfg(any(feature = "async_io", feature = "tokio_net"))]
+use std::{io, time::Duration};
+
+/// Sets `TCP_NODELAY` and, if `keepalive` idle time is specified, enables
+/// `SO_KEEPALIVE` on accepted TCP socket
+#[cfg(any(feature = "async_io", feature = "tokio_net"))]
+fn set_tcp_options(socket: SockRef<'_>, nodelay: bool, keepalive: Option<Duration>) -> io::Result<()> {
+    socket.set_nodelay(nodelay)?;
+    match keepalive {
+        Some(idle) => socket.set_tcp_keepalive(&TcpKeepalive::new().with_time(idle)),
+        None => Ok(()),
+    }
+}
+
 #[cfg(feature = "async_io")]
 mod async_io_net;
 #[cfg(feature = "mock_net")]
@@ -12,30 +12,55 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-#[cfg(not(feature = "tokio_net"))]
+#[cfg(not(any(feature = "tokio_net", feature = "async_rustls")))]
 use crate::connection::async_native_tls::{self, TlsStream};
-use crate::{connection::AcceptError, ConnSupervisor, PgWireListener, ProtocolConfiguration};
-use async_io::Async;
+#[cfg(not(any(feature = "tokio_net", feature = "async_rustls")))]
+use crate::connection::native_tls_identity;
+use crate::{
+    connection::{network::set_tcp_options, AcceptError},
+    ConnSupervisor, PgWireListener, ProtocolConfiguration, TlsIdentity,
+};
+use async_io::{Async, Timer};
 use blocking::Unblock;
+use futures_lite::future;
 pub use futures_lite::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+#[cfg(feature = "async_rustls")]
+use futures_rustls::{
+    rustls::{
+        internal::pemfile,
+        sign::{self, CertifiedKey},
+        AllowAnyAuthenticatedClient, ClientHello, NoClientAuth, ResolvesServerCert, RootCertStore, ServerConfig,
+        Session,
+    },
+    server::TlsStream,
+    TlsAcceptor,
+};
+use socket2::SockRef;
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(feature = "async_rustls")]
+use std::sync::Arc;
 use std::{
     fs::File,
-    io,
-    net::{SocketAddr, TcpListener, TcpStream},
-    path::Path,
+    future::Future,
+    io::{self, IoSlice},
+    net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    path::{Path, PathBuf},
     pin::Pin,
     task::{Context, Poll},
+    time::Duration,
 };
 
 impl PgWireListener {
-    /// creates new PostgreSql connection server
-    pub fn new(
-        listener: Async<TcpListener>,
+    /// creates new PostgreSql connection server over `Async<TcpListener>` or
+    /// `Async<UnixListener>`
+    pub fn new<L: Into<Network>>(
+        listener: L,
         protocol_config: ProtocolConfiguration,
         conn_supervisor: ConnSupervisor,
     ) -> PgWireListener {
         PgWireListener {
-            network: Network::from(listener),
+            network: listener.into(),
             protocol_config,
             conn_supervisor,
         }
@@ -44,51 +69,260 @@ impl PgWireListener {
 
 impl From<Async<TcpListener>> for Network {
     fn from(tcp: Async<TcpListener>) -> Network {
-        Network { inner: tcp }
+        Network {
+            inner: Listener::Tcp(tcp),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl From<Async<UnixListener>> for Network {
+    fn from(unix: Async<UnixListener>) -> Network {
+        Network {
+            inner: Listener::Unix(unix),
+        }
     }
 }
 
 impl From<Async<TcpStream>> for Stream {
     fn from(tcp: Async<TcpStream>) -> Stream {
-        Stream { inner: tcp }
+        Stream {
+            inner: Socket::Tcp(tcp),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl From<Async<UnixStream>> for Stream {
+    fn from(unix: Async<UnixStream>) -> Stream {
+        Stream {
+            inner: Socket::Unix(unix),
+        }
     }
 }
 
 impl From<TlsStream<Stream>> for SecureStream {
     fn from(stream: TlsStream<Stream>) -> SecureStream {
-        SecureStream { inner: stream }
+        SecureStream {
+            inner: Box::new(stream),
+        }
     }
 }
 
+/// Fails with [io::ErrorKind::TimedOut] if `future` is not completed within `duration`
+pub(crate) async fn timeout<T, F: Future<Output = io::Result<T>>>(duration: Duration, future: F) -> io::Result<T> {
+    future::or(future, async {
+        Timer::after(duration).await;
+        Err(io::Error::from(io::ErrorKind::TimedOut))
+    })
+    .await
+}
+
 /// Abstracts underling mechanics of establishing connection between client and server
 pub struct Network {
-    inner: Async<TcpListener>,
+    inner: Listener,
+}
+
+enum Listener {
+    Tcp(Async<TcpListener>),
+    #[cfg(unix)]
+    Unix(Async<UnixListener>),
 }
 
 impl Network {
     /// Accept a new incoming stream from this network.
+    /// Unix socket peers do not have an IP address, so `localhost:0` is returned for them
     pub async fn accept(&self) -> io::Result<(Stream, SocketAddr)> {
-        self.inner
-            .accept()
-            .await
-            .map(|(stream, addr)| (Stream::from(stream), addr))
+        match &self.inner {
+            Listener::Tcp(tcp) => tcp.accept().await.map(|(stream, addr)| (Stream::from(stream), addr)),
+            #[cfg(unix)]
+            Listener::Unix(unix) => unix.accept().await.map(|(stream, _addr)| {
+                (
+                    Stream::from(stream),
+                    SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0),
+                )
+            }),
+        }
+    }
+
+    /// PostgreSQL does not offer SSL on Unix domain sockets
+    pub(crate) fn ssl_allowed(&self) -> bool {
+        matches!(self.inner, Listener::Tcp(_))
+    }
+
+    /// Accept a new incoming tls stream from this network.
+    /// `native-tls` can neither select certificate by SNI nor request client
+    /// certificates, thus `sni_identities` and `client_ca` are not used
+    #[cfg(not(feature = "async_rustls"))]
+    pub async fn tls_accept(
+        &self,
+        identity: &TlsIdentity,
+        _sni_identities: &[(String, PathBuf)],
+        _client_ca: Option<&Path>,
+        stream: Stream,
+    ) -> Result<SecureStream, AcceptError> {
+        let (certificate, key) = match identity {
+            TlsIdentity::Pkcs12 { path, .. } => (read_file(path).await?, vec![]),
+            TlsIdentity::Pem { certificate, key } => (read_file(certificate).await?, read_file(key).await?),
+        };
+        let acceptor = native_tls::TlsAcceptor::new(native_tls_identity(identity, &certificate, &key)?)?;
+        Ok(SecureStream::from(
+            async_native_tls::TlsAcceptor::from(acceptor).accept(stream).await?,
+        ))
     }
 
     /// Accept a new incoming tls stream from this network.
+    /// `TlsIdentity::Pkcs12` path and files of `sni_identities` have to point
+    /// to a PEM file with the certificate chain followed by its PKCS#8 or RSA
+    /// private key, the password is not used. Clients have to present a
+    /// certificate signed by one of `client_ca` certificates if it is set
+    #[cfg(feature = "async_rustls")]
     pub async fn tls_accept(
         &self,
-        certificate_path: &Path,
-        password: &str,
+        identity: &TlsIdentity,
+        sni_identities: &[(String, PathBuf)],
+        client_ca: Option<&Path>,
         stream: Stream,
     ) -> Result<SecureStream, AcceptError> {
+        let mut by_server_name = vec![];
+        for (server_name, path) in sni_identities {
+            by_server_name.push((server_name.to_lowercase(), load_identity(path, path).await?));
+        }
+        let default = match identity {
+            TlsIdentity::Pkcs12 { path, .. } => load_identity(path, path).await?,
+            TlsIdentity::Pem { certificate, key } => load_identity(certificate, key).await?,
+        };
+        let resolver = SniResolver {
+            default,
+            by_server_name,
+        };
+        let client_verifier = match client_ca {
+            Some(path) => AllowAnyAuthenticatedClient::new(load_roots(path).await?),
+            None => NoClientAuth::new(),
+        };
+        let mut config = ServerConfig::new(client_verifier);
+        config.cert_resolver = Arc::new(resolver);
         Ok(SecureStream::from(
-            async_native_tls::accept(Unblock::new(File::open(certificate_path)?), password, stream).await?,
+            TlsAcceptor::from(Arc::new(config)).accept(stream).await?,
         ))
     }
 }
 
+async fn read_file(path: &Path) -> io::Result<Vec<u8>> {
+    let mut content = vec![];
+    Unblock::new(File::open(path)?).read_to_end(&mut content).await?;
+    Ok(content)
+}
+
+/// Reads certificate chain and its private key from PEM files, both can be
+/// kept in the same file
+#[cfg(feature = "async_rustls")]
+async fn load_identity(certificate_path: &Path, key_path: &Path) -> io::Result<CertifiedKey> {
+    fn invalid_identity(reason: &str) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, reason)
+    }
+
+    let pem = read_file(certificate_path).await?;
+    let certificates = match pemfile::certs(&mut pem.as_slice()) {
+        Ok(certificates) if !certificates.is_empty() => certificates,
+        _ => return Err(invalid_identity("no certificate found")),
+    };
+    let pem = if key_path == certificate_path {
+        pem
+    } else {
+        read_file(key_path).await?
+    };
+    let key = pemfile::pkcs8_private_keys(&mut pem.as_slice())
+        .ok()
+        .and_then(|keys| keys.into_iter().next())
+        .or_else(|| {
+            pemfile::rsa_private_keys(&mut pem.as_slice())
+                .ok()
+                .and_then(|keys| keys.into_iter().next())
+        })
+        .ok_or_else(|| invalid_identity("no private key found"))?;
+    let key = sign::any_supported_type(&key).map_err(|()| invalid_identity("unsupported private key type"))?;
+    Ok(CertifiedKey::new(certificates, Arc::new(key)))
+}
+
+/// Reads CA certificates that client certificates are verified against
+#[cfg(feature = "async_rustls")]
+async fn load_roots(path: &Path) -> io::Result<RootCertStore> {
+    let pem = read_file(path).await?;
+    let mut roots = RootCertStore::empty();
+    match roots.add_pem_file(&mut pem.as_slice()) {
+        Ok((valid, _)) if valid > 0 => Ok(roots),
+        _ => Err(io::Error::new(io::ErrorKind::InvalidData, "no CA certificate found")),
+    }
+}
+
+/// Selects certificate by server name that the client requested with SNI,
+/// clients that did not request any or requested unknown one get the default
+#[cfg(feature = "async_rustls")]
+struct SniResolver {
+    default: CertifiedKey,
+    by_server_name: Vec<(String, CertifiedKey)>,
+}
+
+#[cfg(feature = "async_rustls")]
+impl ResolvesServerCert for SniResolver {
+    fn resolve(&self, client_hello: ClientHello) -> Option<CertifiedKey> {
+        let requested = client_hello
+            .server_name()
+            .map(|server_name| <&str>::from(server_name).to_lowercase());
+        let identity = requested
+            .and_then(|requested| {
+                self.by_server_name
+                    .iter()
+                    .find(|(server_name, _)| *server_name == requested)
+            })
+            .map_or(&self.default, |(_, identity)| identity);
+        Some(identity.clone())
+    }
+}
+
 pub struct SecureStream {
-    inner: TlsStream<Stream>,
+    // TLS session state is large, boxed to keep `Channel` variants close in size
+    inner: Box<TlsStream<Stream>>,
+}
+
+impl SecureStream {
+    /// DER encoded certificate that the client presented during TLS handshake.
+    /// `native-tls` can not request client certificates, thus it is always `None`
+    #[cfg(not(feature = "async_rustls"))]
+    pub fn peer_certificate(&self) -> Option<Vec<u8>> {
+        self.inner
+            .peer_certificate()
+            .ok()
+            .flatten()
+            .and_then(|certificate| certificate.to_der().ok())
+    }
+
+    /// DER encoded certificate that the client presented during TLS handshake.
+    /// Client certificates are requested only with [ProtocolConfiguration::with_client_ca],
+    /// otherwise it is always `None`
+    #[cfg(feature = "async_rustls")]
+    pub fn peer_certificate(&self) -> Option<Vec<u8>> {
+        self.inner
+            .get_ref()
+            .1
+            .get_peer_certificates()
+            .and_then(|certificates| certificates.into_iter().next())
+            .map(|certificate| certificate.0)
+    }
+
+    /// Server name that the client requested with SNI during TLS handshake,
+    /// `native-tls` does not expose it, thus it is always `None`
+    #[cfg(not(feature = "async_rustls"))]
+    pub fn server_name(&self) -> Option<String> {
+        None
+    }
+
+    /// Server name that the client requested with SNI during TLS handshake
+    #[cfg(feature = "async_rustls")]
+    pub fn server_name(&self) -> Option<String> {
+        self.inner.get_ref().1.get_sni_hostname().map(str::to_owned)
+    }
 }
 
 impl AsyncRead for SecureStream {
@@ -112,26 +346,72 @@ impl AsyncWrite for SecureStream {
 }
 
 pub struct Stream {
-    inner: Async<TcpStream>,
+    inner: Socket,
+}
+
+enum Socket {
+    Tcp(Async<TcpStream>),
+    #[cfg(unix)]
+    Unix(Async<UnixStream>),
+}
+
+impl Stream {
+    /// Applies `TCP_NODELAY` and keepalive options to TCP sockets, Unix
+    /// domain sockets are left as they are
+    pub(crate) fn set_tcp_options(&self, nodelay: bool, keepalive: Option<Duration>) -> io::Result<()> {
+        match &self.inner {
+            Socket::Tcp(tcp) => set_tcp_options(SockRef::from(tcp.get_ref()), nodelay, keepalive),
+            #[cfg(unix)]
+            Socket::Unix(_) => Ok(()),
+        }
+    }
 }
 
 impl AsyncRead for Stream {
     fn poll_read(self: Pin<&mut Stream>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
-        Pin::new(&mut self.get_mut().inner).poll_read(cx, buf)
+        match &mut self.get_mut().inner {
+            Socket::Tcp(tcp) => Pin::new(tcp).poll_read(cx, buf),
+            #[cfg(unix)]
+            Socket::Unix(unix) => Pin::new(unix).poll_read(cx, buf),
+        }
     }
 }
 
 impl AsyncWrite for Stream {
     fn poll_write(self: Pin<&mut Stream>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
-        Pin::new(&mut self.get_mut().inner).poll_write(cx, buf)
+        match &mut self.get_mut().inner {
+            Socket::Tcp(tcp) => Pin::new(tcp).poll_write(cx, buf),
+            #[cfg(unix)]
+            Socket::Unix(unix) => Pin::new(unix).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Stream>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        match &mut self.get_mut().inner {
+            Socket::Tcp(tcp) => Pin::new(tcp).poll_write_vectored(cx, bufs),
+            #[cfg(unix)]
+            Socket::Unix(unix) => Pin::new(unix).poll_write_vectored(cx, bufs),
+        }
     }
 
     fn poll_flush(self: Pin<&mut Stream>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+        match &mut self.get_mut().inner {
+            Socket::Tcp(tcp) => Pin::new(tcp).poll_flush(cx),
+            #[cfg(unix)]
+            Socket::Unix(unix) => Pin::new(unix).poll_flush(cx),
+        }
     }
 
     fn poll_close(self: Pin<&mut Stream>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-        Pin::new(&mut self.get_mut().inner).poll_close(cx)
+        match &mut self.get_mut().inner {
+            Socket::Tcp(tcp) => Pin::new(tcp).poll_close(cx),
+            #[cfg(unix)]
+            Socket::Unix(unix) => Pin::new(unix).poll_close(cx),
+        }
     }
 }
 
@@ -140,6 +420,35 @@ pub enum Channel {
     Secure(SecureStream),
 }
 
+impl Channel {
+    /// DER encoded client certificate of the secure channel, see [SecureStream::peer_certificate]
+    pub fn peer_certificate(&self) -> Option<Vec<u8>> {
+        match self {
+            Channel::Plain(_) => None,
+            Channel::Secure(tls) => tls.peer_certificate(),
+        }
+    }
+
+    /// Server name that the client requested with SNI, see [SecureStream::server_name]
+    pub fn server_name(&self) -> Option<String> {
+        match self {
+            Channel::Plain(_) => None,
+            Channel::Secure(tls) => tls.server_name(),
+        }
+    }
+
+    /// Whether the channel was upgraded to TLS
+    pub fn is_secure(&self) -> bool {
+        matches!(self, Channel::Secure(_))
+    }
+
+    /// Whether the channel writes several buffers at once, TLS streams
+    /// write them one by one
+    pub fn is_write_vectored(&self) -> bool {
+        matches!(self, Channel::Plain(_))
+    }
+}
+
 impl AsyncRead for Channel {
     fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
         match self.get_mut() {
@@ -157,6 +466,17 @@ impl AsyncWrite for Channel {
         }
     }
 
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Channel::Plain(tcp) => Pin::new(tcp).poll_write_vectored(cx, bufs),
+            Channel::Secure(tls) => Pin::new(tls).poll_write_vectored(cx, bufs),
+        }
+    }
+
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
         match self.get_mut() {
             Channel::Plain(tcp) => Pin::new(tcp).poll_flush(cx),
@@ -12,15 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{connection::AcceptError, ConnSupervisor, PgWireListener, ProtocolConfiguration};
+use crate::{connection::AcceptError, ConnSupervisor, PgWireListener, ProtocolConfiguration, TlsIdentity};
+use futures_lite::future;
 pub use futures_lite::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use std::{
-    io,
+    collections::VecDeque,
+    future::Future,
+    io::{self, IoSlice},
     net::SocketAddr,
-    path::Path,
+    path::{Path, PathBuf},
     pin::Pin,
     sync::{Arc, Mutex},
-    task::{Context, Poll},
+    task::{Context, Poll, Waker},
+    thread,
+    time::Duration,
 };
 
 impl PgWireListener {
@@ -62,6 +67,27 @@ struct TestCaseInner {
     read_index: usize,
     write_content: Vec<u8>,
     write_index: usize,
+    never_ready: bool,
+    write_fails: bool,
+    write_limit: Option<usize>,
+    read_limit: Option<usize>,
+    write_calls: usize,
+    flush_calls: usize,
+    tcp_options: Option<(bool, Option<Duration>)>,
+    read_errors: VecDeque<(usize, io::ErrorKind)>,
+    read_delays: VecDeque<(usize, Duration)>,
+    pending_delay: Option<Delay>,
+    write_script: VecDeque<Result<(), io::ErrorKind>>,
+}
+
+impl TestCaseInner {
+    /// Takes the next scripted read error if a read of `len` bytes reaches it
+    fn read_error(&mut self, len: usize) -> Option<io::ErrorKind> {
+        match self.read_errors.front() {
+            Some((offset, _)) if *offset < self.read_index + len => self.read_errors.pop_front().map(|(_, kind)| kind),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -77,38 +103,174 @@ impl TestCase {
                 read_index: 0,
                 write_content: vec![],
                 write_index: 0,
+                never_ready: false,
+                write_fails: false,
+                write_limit: None,
+                read_limit: None,
+                write_calls: 0,
+                flush_calls: 0,
+                tcp_options: None,
+                read_errors: VecDeque::new(),
+                read_delays: VecDeque::new(),
+                pending_delay: None,
+                write_script: VecDeque::new(),
             })),
         }
     }
 
+    /// Creates test case that never has bytes to read, like a silent client
+    pub fn never_ready() -> TestCase {
+        let test_case = TestCase::new(vec![]);
+        test_case.inner.lock().unwrap().never_ready = true;
+        test_case
+    }
+
+    /// Creates test case that fails to write any bytes, like a disconnected client
+    pub fn broken_pipe() -> TestCase {
+        let test_case = TestCase::new(vec![]);
+        test_case.inner.lock().unwrap().write_fails = true;
+        test_case
+    }
+
+    /// Creates test case that accepts at most `limit` bytes per write, like a
+    /// socket with a full send buffer
+    pub fn with_write_limit(limit: usize) -> TestCase {
+        let test_case = TestCase::new(vec![]);
+        test_case.inner.lock().unwrap().write_limit = Some(limit);
+        test_case
+    }
+
+    /// Creates test case that hands out at most `limit` bytes of `content`
+    /// per read, like a kernel delivering bytes in small chunks
+    pub fn with_read_limit(content: Vec<&[u8]>, limit: usize) -> TestCase {
+        let test_case = TestCase::new(content);
+        test_case.inner.lock().unwrap().read_limit = Some(limit);
+        test_case
+    }
+
+    /// Creates test case that follows scripts of client reads and writes.
+    ///
+    /// `reads` are bytes the client sends interleaved with errors that the
+    /// server gets once it tries to read past them. Each step of `writes` is
+    /// consumed by a single write attempt; writes succeed once it is exhausted.
+    pub fn with_errors(reads: Vec<Result<&[u8], io::ErrorKind>>, writes: Vec<Result<(), io::ErrorKind>>) -> TestCase {
+        let test_case = TestCase::new(vec![]);
+        {
+            let mut case = test_case.inner.lock().unwrap();
+            for step in reads {
+                match step {
+                    Ok(bytes) => case.read_content.extend_from_slice(bytes),
+                    Err(kind) => {
+                        let offset = case.read_content.len();
+                        case.read_errors.push_back((offset, kind));
+                    }
+                }
+            }
+            case.write_script = writes.into_iter().collect();
+        }
+        test_case
+    }
+
+    /// Creates test case that hands out each part of `reads` only after its
+    /// delay elapses, the delay starts when the server tries to read the part,
+    /// like a client that sends messages with pauses between them
+    pub fn with_read_delays(reads: Vec<(Duration, &[u8])>) -> TestCase {
+        let test_case = TestCase::new(vec![]);
+        {
+            let mut case = test_case.inner.lock().unwrap();
+            for (delay, bytes) in reads {
+                let offset = case.read_content.len();
+                case.read_delays.push_back((offset, delay));
+                case.read_content.extend_from_slice(bytes);
+            }
+        }
+        test_case
+    }
+
     pub async fn read_result(&self) -> Vec<u8> {
         self.inner.lock().unwrap().write_content.clone()
     }
+
+    /// Number of successful write attempts, vectored or not
+    pub fn write_calls(&self) -> usize {
+        self.inner.lock().unwrap().write_calls
+    }
+
+    /// Number of flush calls
+    pub fn flush_calls(&self) -> usize {
+        self.inner.lock().unwrap().flush_calls
+    }
+
+    /// `TCP_NODELAY` and keepalive idle time that were set on accepted stream
+    pub fn tcp_options(&self) -> Option<(bool, Option<Duration>)> {
+        self.inner.lock().unwrap().tcp_options
+    }
 }
 
 impl AsyncRead for TestCase {
-    fn poll_read(self: Pin<&mut TestCase>, _cx: &mut Context, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+    fn poll_read(self: Pin<&mut TestCase>, cx: &mut Context, buf: &mut [u8]) -> Poll<io::Result<usize>> {
         let mut case = self.get_mut().inner.lock().unwrap();
-        if buf.len() > case.read_content.len() - case.read_index {
+        while let Some(&(offset, delay)) = case.read_delays.front() {
+            if offset != case.read_index {
+                break;
+            }
+            let pending = case.pending_delay.get_or_insert_with(|| Delay::new(delay));
+            if Pin::new(pending).poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+            case.pending_delay = None;
+            case.read_delays.pop_front();
+        }
+        // bytes after the next delay are not sent yet
+        let delayed = case.read_delays.front().map(|(offset, _)| *offset);
+        let available = delayed.unwrap_or_else(|| case.read_content.len()) - case.read_index;
+        let len = match (case.read_limit, delayed) {
+            (Some(limit), _) => buf.len().min(limit).min(available),
+            (None, Some(_)) => buf.len().min(available),
+            (None, None) => buf.len(),
+        };
+        if case.never_ready {
+            Poll::Pending
+        } else if let Some(kind) = case.read_error(len) {
+            Poll::Ready(Err(io::Error::from(kind)))
+        } else if len > available || (len == 0 && !buf.is_empty()) {
             Poll::Ready(Err(io::Error::from(io::ErrorKind::UnexpectedEof)))
         } else {
-            for (i, item) in buf.iter_mut().enumerate() {
-                *item = case.read_content[case.read_index + i];
-            }
-            case.read_index += buf.len();
-            Poll::Ready(Ok(buf.len()))
+            let start = case.read_index;
+            buf[..len].copy_from_slice(&case.read_content[start..start + len]);
+            case.read_index += len;
+            Poll::Ready(Ok(len))
         }
     }
 }
 
 impl AsyncWrite for TestCase {
-    fn poll_write(self: Pin<&mut TestCase>, _cx: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
+    fn poll_write(self: Pin<&mut TestCase>, cx: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.poll_write_vectored(cx, &[IoSlice::new(buf)])
+    }
+
+    fn poll_write_vectored(self: Pin<&mut TestCase>, _cx: &mut Context, bufs: &[IoSlice]) -> Poll<io::Result<usize>> {
         let mut case = self.get_mut().inner.lock().unwrap();
-        case.write_content.extend_from_slice(buf);
-        Poll::Ready(Ok(buf.len()))
+        if case.write_fails {
+            return Poll::Ready(Err(io::Error::from(io::ErrorKind::BrokenPipe)));
+        }
+        if let Some(Err(kind)) = case.write_script.pop_front() {
+            return Poll::Ready(Err(io::Error::from(kind)));
+        }
+        let mut left = case.write_limit.unwrap_or(usize::MAX);
+        let mut written = 0;
+        for buf in bufs {
+            let len = buf.len().min(left);
+            case.write_content.extend_from_slice(&buf[..len]);
+            written += len;
+            left -= len;
+        }
+        case.write_calls += 1;
+        Poll::Ready(Ok(written))
     }
 
     fn poll_flush(self: Pin<&mut TestCase>, _cx: &mut Context) -> Poll<io::Result<()>> {
+        self.get_mut().inner.lock().unwrap().flush_calls += 1;
         Poll::Ready(Ok(()))
     }
 
@@ -117,6 +279,51 @@ impl AsyncWrite for TestCase {
     }
 }
 
+/// Fails with [io::ErrorKind::TimedOut] if `future` is not completed within `duration`
+pub(crate) async fn timeout<T, F: Future<Output = io::Result<T>>>(duration: Duration, future: F) -> io::Result<T> {
+    future::or(future, async {
+        Delay::new(duration).await;
+        Err(io::Error::from(io::ErrorKind::TimedOut))
+    })
+    .await
+}
+
+/// Wakes up once after the specified duration from a separate thread
+#[derive(Debug)]
+struct Delay {
+    elapsed: Arc<Mutex<(bool, Option<Waker>)>>,
+}
+
+impl Delay {
+    fn new(duration: Duration) -> Delay {
+        let elapsed = Arc::new(Mutex::new((false, None::<Waker>)));
+        let shared = elapsed.clone();
+        thread::spawn(move || {
+            thread::sleep(duration);
+            let mut state = shared.lock().unwrap();
+            state.0 = true;
+            if let Some(waker) = state.1.take() {
+                waker.wake();
+            }
+        });
+        Delay { elapsed }
+    }
+}
+
+impl Future for Delay {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.elapsed.lock().unwrap();
+        if state.0 {
+            Poll::Ready(())
+        } else {
+            state.1 = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
 pub(crate) struct Network {
     data: TestCase,
 }
@@ -130,10 +337,15 @@ impl Network {
         ))
     }
 
+    pub(crate) fn ssl_allowed(&self) -> bool {
+        true
+    }
+
     pub(crate) async fn tls_accept(
         &self,
-        _certificate_path: &Path,
-        _password: &str,
+        _identity: &TlsIdentity,
+        _sni_identities: &[(String, PathBuf)],
+        _client_ca: Option<&Path>,
         _stream: Stream,
     ) -> Result<SecureStream, AcceptError> {
         Ok(SecureStream::from(self.data.clone()))
@@ -144,6 +356,16 @@ pub struct SecureStream {
     inner: TestCase,
 }
 
+impl SecureStream {
+    pub fn peer_certificate(&self) -> Option<Vec<u8>> {
+        None
+    }
+
+    pub fn server_name(&self) -> Option<String> {
+        None
+    }
+}
+
 impl AsyncRead for SecureStream {
     fn poll_read(self: Pin<&mut SecureStream>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
         Pin::new(&mut self.get_mut().inner).poll_read(cx, buf)
@@ -168,6 +390,13 @@ pub struct Stream {
     inner: TestCase,
 }
 
+impl Stream {
+    pub(crate) fn set_tcp_options(&self, nodelay: bool, keepalive: Option<Duration>) -> io::Result<()> {
+        self.inner.inner.lock().unwrap().tcp_options = Some((nodelay, keepalive));
+        Ok(())
+    }
+}
+
 impl AsyncRead for Stream {
     fn poll_read(self: Pin<&mut Stream>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
         Pin::new(&mut self.get_mut().inner).poll_read(cx, buf)
@@ -179,6 +408,14 @@ impl AsyncWrite for Stream {
         Pin::new(&mut self.get_mut().inner).poll_write(cx, buf)
     }
 
+    fn poll_write_vectored(
+        self: Pin<&mut Stream>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_write_vectored(cx, bufs)
+    }
+
     fn poll_flush(self: Pin<&mut Stream>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
         Pin::new(&mut self.get_mut().inner).poll_flush(cx)
     }
@@ -193,6 +430,33 @@ pub enum Channel {
     Secure(SecureStream),
 }
 
+impl Channel {
+    pub fn peer_certificate(&self) -> Option<Vec<u8>> {
+        match self {
+            Channel::Plain(_) => None,
+            Channel::Secure(tls) => tls.peer_certificate(),
+        }
+    }
+
+    pub fn server_name(&self) -> Option<String> {
+        match self {
+            Channel::Plain(_) => None,
+            Channel::Secure(tls) => tls.server_name(),
+        }
+    }
+
+    /// Whether the channel was upgraded to TLS
+    pub fn is_secure(&self) -> bool {
+        matches!(self, Channel::Secure(_))
+    }
+
+    /// Whether the channel writes several buffers at once, TLS streams
+    /// write them one by one
+    pub fn is_write_vectored(&self) -> bool {
+        matches!(self, Channel::Plain(_))
+    }
+}
+
 impl AsyncRead for Channel {
     fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
         match self.get_mut() {
@@ -210,6 +474,17 @@ impl AsyncWrite for Channel {
         }
     }
 
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Channel::Plain(tcp) => Pin::new(tcp).poll_write_vectored(cx, bufs),
+            Channel::Secure(tls) => Pin::new(tls).poll_write_vectored(cx, bufs),
+        }
+    }
+
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
         match self.get_mut() {
             Channel::Plain(tcp) => Pin::new(tcp).poll_flush(cx),
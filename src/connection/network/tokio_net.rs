@@ -12,15 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{connection::AcceptError, ConnSupervisor, PgWireListener, ProtocolConfiguration};
+use crate::{
+    connection::{native_tls_identity, network::set_tcp_options, AcceptError},
+    ConnSupervisor, PgWireListener, ProtocolConfiguration, TlsIdentity,
+};
+use socket2::SockRef;
 use std::{
-    io,
-    net::SocketAddr,
-    path::Path,
+    future::Future,
+    io::{self, IoSlice},
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::{Path, PathBuf},
     pin::Pin,
     task::{Context, Poll},
+    time::Duration,
 };
 pub use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+#[cfg(unix)]
+use tokio::net::{UnixListener, UnixStream};
 use tokio::{
     fs::File,
     io::ReadBuf,
@@ -29,14 +37,14 @@ use tokio::{
 use tokio_native_tls::TlsStream;
 
 impl PgWireListener {
-    /// creates new PostgreSql connection server
-    pub fn new(
-        listener: TcpListener,
+    /// creates new PostgreSql connection server over `TcpListener` or `UnixListener`
+    pub fn new<L: Into<Network>>(
+        listener: L,
         protocol_config: ProtocolConfiguration,
         conn_supervisor: ConnSupervisor,
     ) -> PgWireListener {
         PgWireListener {
-            network: Network::from(listener),
+            network: listener.into(),
             protocol_config,
             conn_supervisor,
         }
@@ -45,13 +53,35 @@ impl PgWireListener {
 
 impl From<TcpListener> for Network {
     fn from(tcp: TcpListener) -> Network {
-        Network { inner: tcp }
+        Network {
+            inner: Listener::Tcp(tcp),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl From<UnixListener> for Network {
+    fn from(unix: UnixListener) -> Network {
+        Network {
+            inner: Listener::Unix(unix),
+        }
     }
 }
 
 impl From<TcpStream> for Stream {
     fn from(tcp: TcpStream) -> Stream {
-        Stream { inner: tcp }
+        Stream {
+            inner: Socket::Tcp(tcp),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl From<UnixStream> for Stream {
+    fn from(unix: UnixStream) -> Stream {
+        Stream {
+            inner: Socket::Unix(unix),
+        }
     }
 }
 
@@ -61,42 +91,96 @@ impl From<TlsStream<Stream>> for SecureStream {
     }
 }
 
+/// Fails with [io::ErrorKind::TimedOut] if `future` is not completed within `duration`
+pub(crate) async fn timeout<T, F: Future<Output = io::Result<T>>>(duration: Duration, future: F) -> io::Result<T> {
+    tokio::time::timeout(duration, future)
+        .await
+        .unwrap_or_else(|_elapsed| Err(io::Error::from(io::ErrorKind::TimedOut)))
+}
+
 /// Abstracts underling mechanics of establishing connection between client and server
 pub struct Network {
-    inner: TcpListener,
+    inner: Listener,
+}
+
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
 }
 
 impl Network {
     /// Accept a new incoming stream from this network.
+    /// Unix socket peers do not have an IP address, so `localhost:0` is returned for them
     pub async fn accept(&self) -> io::Result<(Stream, SocketAddr)> {
-        self.inner
-            .accept()
-            .await
-            .map(|(stream, addr)| (Stream::from(stream), addr))
+        match &self.inner {
+            Listener::Tcp(tcp) => tcp.accept().await.map(|(stream, addr)| (Stream::from(stream), addr)),
+            #[cfg(unix)]
+            Listener::Unix(unix) => unix.accept().await.map(|(stream, _addr)| {
+                (
+                    Stream::from(stream),
+                    SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0),
+                )
+            }),
+        }
+    }
+
+    /// PostgreSQL does not offer SSL on Unix domain sockets
+    pub(crate) fn ssl_allowed(&self) -> bool {
+        matches!(self.inner, Listener::Tcp(_))
     }
 
     /// Accept a new incoming tls stream from this network.
+    /// `native-tls` can neither select certificate by SNI nor request client
+    /// certificates, thus `sni_identities` and `client_ca` are not used
     pub async fn tls_accept(
         &self,
-        certificate_path: &Path,
-        password: &str,
+        identity: &TlsIdentity,
+        _sni_identities: &[(String, PathBuf)],
+        _client_ca: Option<&Path>,
         stream: Stream,
     ) -> Result<SecureStream, AcceptError> {
-        let mut identity = vec![];
-        let mut file = File::open(certificate_path).await.map_err(AcceptError::Io)?;
-        file.read_to_end(&mut identity).await?;
+        let (certificate, key) = match identity {
+            TlsIdentity::Pkcs12 { path, .. } => (read_file(path).await?, vec![]),
+            TlsIdentity::Pem { certificate, key } => (read_file(certificate).await?, read_file(key).await?),
+        };
 
-        let identity = native_tls::Identity::from_pkcs12(&identity, password)?;
+        let identity = native_tls_identity(identity, &certificate, &key)?;
         let acceptor = tokio_native_tls::TlsAcceptor::from(native_tls::TlsAcceptor::new(identity)?);
         let tls_stream = acceptor.accept(stream).await?;
         Ok(SecureStream::from(tls_stream))
     }
 }
 
+async fn read_file(path: &Path) -> io::Result<Vec<u8>> {
+    let mut content = vec![];
+    File::open(path).await?.read_to_end(&mut content).await?;
+    Ok(content)
+}
+
 pub struct SecureStream {
     inner: TlsStream<Stream>,
 }
 
+impl SecureStream {
+    /// DER encoded certificate that the client presented during TLS handshake.
+    /// `native-tls` can not request client certificates, thus it is always `None`
+    pub fn peer_certificate(&self) -> Option<Vec<u8>> {
+        self.inner
+            .get_ref()
+            .peer_certificate()
+            .ok()
+            .flatten()
+            .and_then(|certificate| certificate.to_der().ok())
+    }
+
+    /// Server name that the client requested with SNI during TLS handshake,
+    /// `native-tls` does not expose it, thus it is always `None`
+    pub fn server_name(&self) -> Option<String> {
+        None
+    }
+}
+
 impl AsyncRead for SecureStream {
     fn poll_read(self: Pin<&mut SecureStream>, cx: &mut Context<'_>, buf: &mut ReadBuf) -> Poll<io::Result<()>> {
         Pin::new(&mut self.get_mut().inner).poll_read(cx, buf)
@@ -118,26 +202,80 @@ impl AsyncWrite for SecureStream {
 }
 
 pub struct Stream {
-    inner: TcpStream,
+    inner: Socket,
+}
+
+enum Socket {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Stream {
+    /// Applies `TCP_NODELAY` and keepalive options to TCP sockets, Unix
+    /// domain sockets are left as they are
+    pub(crate) fn set_tcp_options(&self, nodelay: bool, keepalive: Option<Duration>) -> io::Result<()> {
+        match &self.inner {
+            Socket::Tcp(tcp) => set_tcp_options(SockRef::from(tcp), nodelay, keepalive),
+            #[cfg(unix)]
+            Socket::Unix(_) => Ok(()),
+        }
+    }
 }
 
 impl AsyncRead for Stream {
     fn poll_read(self: Pin<&mut Stream>, cx: &mut Context<'_>, buf: &mut ReadBuf) -> Poll<io::Result<()>> {
-        Pin::new(&mut self.get_mut().inner).poll_read(cx, buf)
+        match &mut self.get_mut().inner {
+            Socket::Tcp(tcp) => Pin::new(tcp).poll_read(cx, buf),
+            #[cfg(unix)]
+            Socket::Unix(unix) => Pin::new(unix).poll_read(cx, buf),
+        }
     }
 }
 
 impl AsyncWrite for Stream {
     fn poll_write(self: Pin<&mut Stream>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
-        Pin::new(&mut self.get_mut().inner).poll_write(cx, buf)
+        match &mut self.get_mut().inner {
+            Socket::Tcp(tcp) => Pin::new(tcp).poll_write(cx, buf),
+            #[cfg(unix)]
+            Socket::Unix(unix) => Pin::new(unix).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Stream>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        match &mut self.get_mut().inner {
+            Socket::Tcp(tcp) => Pin::new(tcp).poll_write_vectored(cx, bufs),
+            #[cfg(unix)]
+            Socket::Unix(unix) => Pin::new(unix).poll_write_vectored(cx, bufs),
+        }
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        match &self.inner {
+            Socket::Tcp(tcp) => tcp.is_write_vectored(),
+            #[cfg(unix)]
+            Socket::Unix(unix) => unix.is_write_vectored(),
+        }
     }
 
     fn poll_flush(self: Pin<&mut Stream>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+        match &mut self.get_mut().inner {
+            Socket::Tcp(tcp) => Pin::new(tcp).poll_flush(cx),
+            #[cfg(unix)]
+            Socket::Unix(unix) => Pin::new(unix).poll_flush(cx),
+        }
     }
 
     fn poll_shutdown(self: Pin<&mut Stream>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
-        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+        match &mut self.get_mut().inner {
+            Socket::Tcp(tcp) => Pin::new(tcp).poll_shutdown(cx),
+            #[cfg(unix)]
+            Socket::Unix(unix) => Pin::new(unix).poll_shutdown(cx),
+        }
     }
 }
 
@@ -146,6 +284,29 @@ pub enum Channel {
     Secure(SecureStream),
 }
 
+impl Channel {
+    /// DER encoded client certificate of the secure channel, see [SecureStream::peer_certificate]
+    pub fn peer_certificate(&self) -> Option<Vec<u8>> {
+        match self {
+            Channel::Plain(_) => None,
+            Channel::Secure(tls) => tls.peer_certificate(),
+        }
+    }
+
+    /// Server name that the client requested with SNI, see [SecureStream::server_name]
+    pub fn server_name(&self) -> Option<String> {
+        match self {
+            Channel::Plain(_) => None,
+            Channel::Secure(tls) => tls.server_name(),
+        }
+    }
+
+    /// Whether the channel was upgraded to TLS
+    pub fn is_secure(&self) -> bool {
+        matches!(self, Channel::Secure(_))
+    }
+}
+
 impl AsyncRead for Channel {
     fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf) -> Poll<io::Result<()>> {
         match self.get_mut() {
@@ -163,6 +324,24 @@ impl AsyncWrite for Channel {
         }
     }
 
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Channel::Plain(tcp) => Pin::new(tcp).poll_write_vectored(cx, bufs),
+            Channel::Secure(tls) => Pin::new(tls).poll_write_vectored(cx, bufs),
+        }
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        match self {
+            Channel::Plain(tcp) => tcp.is_write_vectored(),
+            Channel::Secure(tls) => tls.is_write_vectored(),
+        }
+    }
+
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
         match self.get_mut() {
             Channel::Plain(tcp) => Pin::new(tcp).poll_flush(cx),
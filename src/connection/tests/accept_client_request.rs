@@ -13,12 +13,16 @@
 // limitations under the License.
 
 use super::pg_frontend;
-use crate::connection::{
-    listener::PgWireListener, network::mock_net::TestCase, ClientRequest, ConnSupervisor, Encryption,
-    ProtocolConfiguration,
+use crate::{
+    connection::{
+        listener::PgWireListener, network::mock_net::TestCase, AuthMethod, ClientRequest, ConnSupervisor, Encryption,
+        ProtocolConfiguration,
+    },
+    Error,
 };
 use futures_lite::future::block_on;
-use std::path::PathBuf;
+use pg_wire_payload::{BackendMessage, ErrorFields};
+use std::{collections::HashSet, io, net::SocketAddr, path::PathBuf};
 
 #[test]
 fn trying_read_from_empty_stream() {
@@ -163,6 +167,83 @@ fn successful_connection_handshake_for_none_secure() {
     });
 }
 
+#[test]
+fn negotiate_protocol_version_for_unrecognized_protocol_options() {
+    block_on(async {
+        let test_case = TestCase::new(vec![
+            pg_frontend::Message::Setup(vec![("user", "username"), ("_pq_.foo", "on"), ("DateStyle", "ISO")])
+                .as_vec()
+                .as_slice(),
+            &[],
+        ]);
+
+        let pg_wire_listener = PgWireListener::new(
+            test_case.clone(),
+            ProtocolConfiguration::not_secure(),
+            ConnSupervisor::new(1, 2),
+        );
+
+        let result = pg_wire_listener.accept().await;
+
+        match result {
+            Ok(Ok(ClientRequest::Connect((_, props, _, _, _)))) => assert_eq!(
+                props,
+                vec![
+                    ("user".to_owned(), "username".to_owned()),
+                    ("_pq_.foo".to_owned(), "on".to_owned()),
+                    ("DateStyle".to_owned(), "ISO".to_owned()),
+                ]
+            ),
+            _ => panic!("client should be connected"),
+        }
+
+        assert_eq!(
+            test_case.read_result().await,
+            BackendMessage::NegotiateProtocolVersion {
+                newest_minor_version: 0,
+                unrecognized_options: vec!["_pq_.foo".to_owned()],
+            }
+            .as_vec()
+        );
+    });
+}
+
+#[test]
+fn negotiate_protocol_version_for_newer_minor_version() {
+    block_on(async {
+        let test_case = TestCase::new(vec![
+            pg_frontend::Message::SetupWithMinorVersion(1, vec![("user", "username")])
+                .as_vec()
+                .as_slice(),
+            &[],
+        ]);
+
+        let pg_wire_listener = PgWireListener::new(
+            test_case.clone(),
+            ProtocolConfiguration::not_secure(),
+            ConnSupervisor::new(1, 2),
+        );
+
+        let result = pg_wire_listener.accept().await;
+
+        match result {
+            Ok(Ok(ClientRequest::Connect((_, props, _, _, _)))) => {
+                assert_eq!(props, vec![("user".to_owned(), "username".to_owned())])
+            }
+            _ => panic!("client should be connected"),
+        }
+
+        assert_eq!(
+            test_case.read_result().await,
+            BackendMessage::NegotiateProtocolVersion {
+                newest_minor_version: 0,
+                unrecognized_options: vec![],
+            }
+            .as_vec()
+        );
+    });
+}
+
 #[test]
 fn successful_connection_handshake_for_ssl_only_secure() {
     block_on(async {
@@ -230,6 +311,278 @@ fn successful_connection_handshake_for_ssl_only_secure() {
     });
 }
 
+#[test]
+fn configured_parameters_are_sent_after_authentication() {
+    block_on(async {
+        let test_case = TestCase::new(vec![pg_frontend::Message::Setup(vec![
+            ("user", "username"),
+            ("database", "database_name"),
+        ])
+        .as_vec()
+        .as_slice()]);
+
+        let pg_wire_listener = PgWireListener::new(
+            test_case.clone(),
+            ProtocolConfiguration::not_secure().with_parameter_statuses(vec![
+                ("server_version".to_owned(), "13.2".to_owned()),
+                ("integer_datetimes".to_owned(), "on".to_owned()),
+            ]),
+            ConnSupervisor::new(1, 2),
+        );
+
+        let mut channel = match pg_wire_listener.accept().await {
+            Ok(Ok(ClientRequest::Connect((channel, _, _, _, _)))) => channel,
+            _ => panic!("client has to be connected"),
+        };
+        pg_wire_listener
+            .complete_authentication(&mut channel)
+            .await
+            .expect("no io errors");
+
+        let actual_content = test_case.read_result().await;
+        let mut expected_content = Vec::new();
+        expected_content.extend_from_slice(BackendMessage::AuthenticationOk.as_vec().as_slice());
+        expected_content.extend_from_slice(
+            BackendMessage::ParameterStatus("server_version".to_owned(), "13.2".to_owned())
+                .as_vec()
+                .as_slice(),
+        );
+        expected_content.extend_from_slice(
+            BackendMessage::ParameterStatus("integer_datetimes".to_owned(), "on".to_owned())
+                .as_vec()
+                .as_slice(),
+        );
+        assert_eq!(actual_content, expected_content);
+    });
+}
+
+#[test]
+fn authentication_completion_is_flushed() {
+    block_on(async {
+        let test_case = TestCase::new(vec![pg_frontend::Message::Setup(vec![("user", "username")])
+            .as_vec()
+            .as_slice()]);
+
+        let pg_wire_listener = PgWireListener::new(
+            test_case.clone(),
+            ProtocolConfiguration::not_secure().with_auth_method(AuthMethod::Trust),
+            ConnSupervisor::new(1, 2),
+        );
+
+        let mut channel = match pg_wire_listener.accept().await {
+            Ok(Ok(ClientRequest::Connect((channel, _, _, _, _)))) => channel,
+            _ => panic!("client has to be connected"),
+        };
+        let flushes = test_case.flush_calls();
+        pg_wire_listener
+            .request_authentication(&mut channel)
+            .await
+            .expect("no io errors");
+
+        assert_eq!(test_case.flush_calls(), flushes + 1);
+    });
+}
+
+#[test]
+fn no_peer_certificate_for_none_secure() {
+    block_on(async {
+        let test_case = TestCase::new(vec![pg_frontend::Message::Setup(vec![("user", "username")])
+            .as_vec()
+            .as_slice()]);
+
+        let pg_wire_listener = PgWireListener::new(
+            test_case.clone(),
+            ProtocolConfiguration::not_secure(),
+            ConnSupervisor::new(1, 2),
+        );
+
+        match pg_wire_listener.accept().await {
+            Ok(Ok(ClientRequest::Connect((channel, _, _, _, _)))) => assert_eq!(channel.peer_certificate(), None),
+            _ => panic!("client has to be connected"),
+        }
+    });
+}
+
+#[test]
+fn write_error_during_ssl_negotiation() {
+    block_on(async {
+        let test_case = TestCase::with_errors(
+            vec![Ok(pg_frontend::Message::SslRequired.as_vec().as_slice())],
+            vec![Err(io::ErrorKind::ConnectionReset)],
+        );
+
+        let pg_wire_listener = PgWireListener::new(
+            test_case.clone(),
+            ProtocolConfiguration::not_secure(),
+            ConnSupervisor::new(1, 2),
+        );
+
+        let result = pg_wire_listener.accept().await;
+
+        assert!(matches!(result, Err(error) if error.kind() == io::ErrorKind::ConnectionReset));
+        assert_eq!(test_case.read_result().await, Vec::<u8>::new());
+    });
+}
+
+#[test]
+fn read_error_after_ssl_negotiation() {
+    block_on(async {
+        let test_case = TestCase::with_errors(
+            vec![
+                Ok(pg_frontend::Message::SslRequired.as_vec().as_slice()),
+                Err(io::ErrorKind::ConnectionReset),
+                Ok(pg_frontend::Message::Setup(vec![("user", "username")])
+                    .as_vec()
+                    .as_slice()),
+            ],
+            vec![],
+        );
+
+        let pg_wire_listener = PgWireListener::new(
+            test_case.clone(),
+            ProtocolConfiguration::not_secure(),
+            ConnSupervisor::new(1, 2),
+        );
+
+        let result = pg_wire_listener.accept().await;
+
+        assert!(matches!(result, Err(error) if error.kind() == io::ErrorKind::ConnectionReset));
+        let mut expected_content = Vec::new();
+        expected_content.extend_from_slice(Encryption::RejectSsl.into());
+        assert_eq!(test_case.read_result().await, expected_content);
+    });
+}
+
+#[test]
+fn write_error_during_authentication_completion() {
+    block_on(async {
+        let test_case = TestCase::with_errors(
+            vec![Ok(pg_frontend::Message::Setup(vec![("user", "username")])
+                .as_vec()
+                .as_slice())],
+            vec![Ok(()), Err(io::ErrorKind::BrokenPipe)],
+        );
+
+        let pg_wire_listener = PgWireListener::new(
+            test_case.clone(),
+            ProtocolConfiguration::not_secure(),
+            ConnSupervisor::new(1, 2),
+        );
+
+        match pg_wire_listener.accept().await {
+            Ok(Ok(ClientRequest::Connect((mut channel, _, _, _, _)))) => {
+                let result = pg_wire_listener.complete_authentication(&mut channel).await;
+                assert!(matches!(result, Err(error) if error.kind() == io::ErrorKind::BrokenPipe));
+            }
+            _ => panic!("client has to be connected"),
+        }
+        assert_eq!(test_case.read_result().await, BackendMessage::AuthenticationOk.as_vec());
+    });
+}
+
+fn first_authentication_message(auth_method: AuthMethod) -> Vec<u8> {
+    authentication_request(auth_method).0
+}
+
+/// Returns what the server sent to request authentication and the salt of
+/// `md5` authentication
+fn authentication_request(auth_method: AuthMethod) -> (Vec<u8>, Option<[u8; 4]>) {
+    block_on(async {
+        let test_case = TestCase::new(vec![pg_frontend::Message::Setup(vec![("user", "username")])
+            .as_vec()
+            .as_slice()]);
+
+        let pg_wire_listener = PgWireListener::new(
+            test_case.clone(),
+            ProtocolConfiguration::not_secure().with_auth_method(auth_method),
+            ConnSupervisor::new(1, 2),
+        );
+
+        let salt = match pg_wire_listener.accept().await {
+            Ok(Ok(ClientRequest::Connect((mut channel, _, _, _, _)))) => pg_wire_listener
+                .request_authentication(&mut channel)
+                .await
+                .expect("authentication requested"),
+            _ => panic!("client has to be connected"),
+        };
+        (test_case.read_result().await, salt)
+    })
+}
+
+#[test]
+fn default_auth_method() {
+    assert_eq!(
+        ProtocolConfiguration::not_secure().auth_method(),
+        AuthMethod::CleartextPassword
+    );
+}
+
+#[test]
+fn trust_authentication() {
+    let mut expected_content = BackendMessage::AuthenticationOk.as_vec();
+    for (name, value) in ProtocolConfiguration::not_secure().parameter_statuses() {
+        expected_content.extend_from_slice(&BackendMessage::ParameterStatus(name.clone(), value.clone()).as_vec());
+    }
+    assert_eq!(first_authentication_message(AuthMethod::Trust), expected_content);
+}
+
+#[test]
+fn cleartext_password_authentication() {
+    assert_eq!(
+        first_authentication_message(AuthMethod::CleartextPassword),
+        BackendMessage::AuthenticationCleartextPassword.as_vec()
+    );
+}
+
+#[test]
+fn md5_password_authentication() {
+    let (request, salt) = authentication_request(AuthMethod::Md5Password);
+    let salt = salt.expect("md5 salt");
+
+    assert_eq!(request, BackendMessage::AuthenticationMd5Password(salt).as_vec());
+}
+
+#[test]
+fn md5_salt_is_generated_per_connection() {
+    let salts = (0..4)
+        .map(|_| authentication_request(AuthMethod::Md5Password).1)
+        .collect::<HashSet<_>>();
+
+    assert!(salts.len() > 1);
+}
+
+#[test]
+fn only_md5_authentication_has_salt() {
+    for auth_method in &[
+        AuthMethod::Trust,
+        AuthMethod::CleartextPassword,
+        AuthMethod::ScramSha256,
+    ] {
+        assert_eq!(authentication_request(*auth_method).1, None);
+    }
+}
+
+#[test]
+fn scram_sha_256_authentication() {
+    assert_eq!(
+        first_authentication_message(AuthMethod::ScramSha256),
+        BackendMessage::AuthenticationSasl(vec!["SCRAM-SHA-256".to_owned()]).as_vec()
+    );
+}
+
+#[test]
+fn default_parameters() {
+    assert_eq!(
+        ProtocolConfiguration::not_secure().parameter_statuses(),
+        &[
+            ("client_encoding".to_owned(), "UTF8".to_owned()),
+            ("DateStyle".to_owned(), "ISO".to_owned()),
+            ("integer_datetimes".to_owned(), "on".to_owned()),
+            ("server_version".to_owned(), "12.4".to_owned()),
+        ]
+    );
+}
+
 #[test]
 fn successful_cancel_request_connection() {
     block_on(async {
@@ -267,3 +620,323 @@ fn verification_failed_cancel_request_connection() {
         assert!(matches!(result, Ok(Err(_))));
     });
 }
+
+const PROXY_SIGNATURE: &[u8] = b"\r\n\r\n\0\r\nQUIT\n";
+
+fn accepted_address(proxy_header: &[u8]) -> Option<SocketAddr> {
+    block_on(async {
+        let test_case = TestCase::new(vec![
+            proxy_header,
+            pg_frontend::Message::Setup(vec![("user", "username")])
+                .as_vec()
+                .as_slice(),
+        ]);
+
+        let pg_wire_listener = PgWireListener::new(
+            test_case.clone(),
+            ProtocolConfiguration::not_secure().with_proxy_protocol(true),
+            ConnSupervisor::new(1, 2),
+        );
+
+        match pg_wire_listener.accept().await {
+            Ok(Ok(ClientRequest::Connect((_, _, _, address, _)))) => Some(address),
+            _ => None,
+        }
+    })
+}
+
+#[test]
+fn proxy_protocol_tcp4_source_address() {
+    let mut header = PROXY_SIGNATURE.to_vec();
+    header.extend_from_slice(&[0x21, 0x11, 0, 12]);
+    header.extend_from_slice(&[192, 168, 0, 1, 10, 0, 0, 1, 0xdc, 0x04, 0x15, 0x38]);
+
+    assert_eq!(accepted_address(&header), Some("192.168.0.1:56324".parse().unwrap()));
+}
+
+#[test]
+fn proxy_protocol_tcp6_source_address() {
+    let mut header = PROXY_SIGNATURE.to_vec();
+    header.extend_from_slice(&[0x21, 0x21, 0, 36]);
+    header.extend_from_slice(&[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    header.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    header.extend_from_slice(&[0xdc, 0x04, 0x15, 0x38]);
+
+    assert_eq!(accepted_address(&header), Some("[2001:db8::1]:56324".parse().unwrap()));
+}
+
+#[test]
+fn proxy_protocol_local_command() {
+    let mut header = PROXY_SIGNATURE.to_vec();
+    header.extend_from_slice(&[0x20, 0x00, 0, 0]);
+
+    assert_eq!(accepted_address(&header), Some("127.0.0.1:1000".parse().unwrap()));
+}
+
+#[test]
+fn proxy_protocol_invalid_signature() {
+    block_on(async {
+        let test_case = TestCase::new(vec![pg_frontend::Message::Setup(vec![("user", "username")])
+            .as_vec()
+            .as_slice()]);
+
+        let pg_wire_listener = PgWireListener::new(
+            test_case.clone(),
+            ProtocolConfiguration::not_secure().with_proxy_protocol(true),
+            ConnSupervisor::new(1, 2),
+        );
+
+        let result = pg_wire_listener.accept().await;
+
+        assert!(matches!(result, Ok(Err(_))));
+    });
+}
+
+#[test]
+fn proxy_protocol_disabled_by_default() {
+    assert!(!ProtocolConfiguration::not_secure().proxy_protocol());
+}
+
+#[test]
+fn datetime_encoding_follows_integer_datetimes() {
+    use pg_wire_payload::DatetimeEncoding;
+
+    assert_eq!(
+        ProtocolConfiguration::not_secure().datetime_encoding(),
+        DatetimeEncoding::Integer
+    );
+    assert_eq!(
+        ProtocolConfiguration::not_secure()
+            .with_parameter_statuses(vec![("integer_datetimes".to_owned(), "off".to_owned())])
+            .datetime_encoding(),
+        DatetimeEncoding::Float
+    );
+}
+
+#[test]
+fn datetime_encoding_configuration() {
+    use pg_wire_payload::DatetimeEncoding;
+
+    let config = ProtocolConfiguration::not_secure().with_datetime_encoding(DatetimeEncoding::Float);
+
+    assert_eq!(config.datetime_encoding(), DatetimeEncoding::Float);
+    assert_eq!(
+        config
+            .parameter_statuses()
+            .iter()
+            .filter(|(name, _)| name == "integer_datetimes")
+            .collect::<Vec<_>>(),
+        vec![&("integer_datetimes".to_owned(), "off".to_owned())]
+    );
+    assert_eq!(
+        config.connection_settings().datetime_encoding(),
+        DatetimeEncoding::Float
+    );
+    assert_eq!(
+        ProtocolConfiguration::not_secure()
+            .with_parameter_statuses(vec![])
+            .with_datetime_encoding(DatetimeEncoding::Integer)
+            .parameter_statuses(),
+        &[("integer_datetimes".to_owned(), "on".to_owned())]
+    );
+}
+
+#[test]
+fn ssl_is_not_required_by_default() {
+    assert!(!ProtocolConfiguration::not_secure().ssl_required());
+}
+
+#[test]
+fn plaintext_startup_is_refused_when_ssl_required() {
+    block_on(async {
+        let test_case = TestCase::new(vec![pg_frontend::Message::Setup(vec![("user", "username")])
+            .as_vec()
+            .as_slice()]);
+
+        let pg_wire_listener = PgWireListener::new(
+            test_case.clone(),
+            ProtocolConfiguration::with_ssl(PathBuf::new(), "password".to_owned()).with_ssl_required(true),
+            ConnSupervisor::new(1, 2),
+        );
+
+        let result = pg_wire_listener.accept().await.expect("no io errors");
+        assert!(matches!(result, Err(error) if error == Error::ssl_required()));
+
+        assert_eq!(
+            test_case.read_result().await,
+            BackendMessage::ErrorResponse(ErrorFields::new(
+                "FATAL",
+                "28000",
+                "server requires SSL connection, connection without SSL is refused"
+            ))
+            .as_vec()
+        );
+    });
+}
+
+#[test]
+fn tls_startup_is_accepted_when_ssl_required() {
+    block_on(async {
+        let test_case = TestCase::new(vec![
+            pg_frontend::Message::SslRequired.as_vec().as_slice(),
+            pg_frontend::Message::Setup(vec![("user", "username")])
+                .as_vec()
+                .as_slice(),
+        ]);
+
+        let pg_wire_listener = PgWireListener::new(
+            test_case.clone(),
+            ProtocolConfiguration::with_ssl(PathBuf::new(), "password".to_owned()).with_ssl_required(true),
+            ConnSupervisor::new(1, 2),
+        );
+
+        let result = pg_wire_listener.accept().await.expect("no io errors");
+        assert!(matches!(result, Ok(ClientRequest::Connect((channel, _, _, _, _))) if channel.is_secure()));
+
+        let mut expected_content = Vec::new();
+        expected_content.extend_from_slice(Encryption::AcceptSsl.into());
+        assert_eq!(test_case.read_result().await, expected_content);
+    });
+}
+
+#[test]
+fn rejected_ssl_request_is_refused_when_ssl_required() {
+    block_on(async {
+        let test_case = TestCase::new(vec![
+            pg_frontend::Message::SslRequired.as_vec().as_slice(),
+            pg_frontend::Message::Setup(vec![("user", "username")])
+                .as_vec()
+                .as_slice(),
+        ]);
+
+        let pg_wire_listener = PgWireListener::new(
+            test_case.clone(),
+            ProtocolConfiguration::not_secure().with_ssl_required(true),
+            ConnSupervisor::new(1, 2),
+        );
+
+        let result = pg_wire_listener.accept().await.expect("no io errors");
+        assert!(matches!(result, Err(error) if error == Error::ssl_required()));
+
+        let mut expected_content = Vec::new();
+        expected_content.extend_from_slice(Encryption::RejectSsl.into());
+        expected_content.extend_from_slice(&BackendMessage::from(&Error::ssl_required()).as_vec());
+        assert_eq!(test_case.read_result().await, expected_content);
+    });
+}
+
+#[test]
+fn idle_timeout_configuration() {
+    use std::time::Duration;
+
+    assert_eq!(ProtocolConfiguration::not_secure().idle_timeout(), None);
+    assert_eq!(
+        ProtocolConfiguration::not_secure()
+            .with_idle_timeout(Some(Duration::from_secs(60)))
+            .idle_timeout(),
+        Some(Duration::from_secs(60))
+    );
+}
+
+#[test]
+fn tcp_options_configuration() {
+    use std::time::Duration;
+
+    let config = ProtocolConfiguration::not_secure();
+    assert!(config.tcp_nodelay());
+    assert_eq!(config.tcp_keepalive(), None);
+
+    let config = config
+        .with_tcp_nodelay(false)
+        .with_tcp_keepalive(Some(Duration::from_secs(60)));
+    assert!(!config.tcp_nodelay());
+    assert_eq!(config.tcp_keepalive(), Some(Duration::from_secs(60)));
+}
+
+#[test]
+fn tcp_options_are_set_after_accept() {
+    use std::time::Duration;
+
+    block_on(async {
+        let test_case = TestCase::new(vec![pg_frontend::Message::Setup(vec![("user", "username")])
+            .as_vec()
+            .as_slice()]);
+
+        let pg_wire_listener = PgWireListener::new(
+            test_case.clone(),
+            ProtocolConfiguration::not_secure().with_tcp_keepalive(Some(Duration::from_secs(30))),
+            ConnSupervisor::new(1, 2),
+        );
+
+        let result = pg_wire_listener.accept().await.expect("no io errors");
+        assert!(matches!(result, Ok(ClientRequest::Connect(_))));
+        assert_eq!(test_case.tcp_options(), Some((true, Some(Duration::from_secs(30)))));
+    });
+}
+
+#[test]
+fn max_message_len_configuration() {
+    assert_eq!(ProtocolConfiguration::not_secure().max_message_len(), 0x3fff_ffff);
+
+    let config = ProtocolConfiguration::not_secure().with_max_message_len(1024);
+    assert_eq!(config.max_message_len(), 1024);
+    assert_eq!(config.connection_settings().max_message_len(), 1024);
+}
+
+#[test]
+fn startup_packet_longer_than_max_message_len() {
+    use crate::errors::{HandShakeError, HandShakeErrorKind};
+
+    block_on(async {
+        let setup = pg_frontend::Message::Setup(vec![("user", "username")]).as_vec();
+        let test_case = TestCase::new(vec![setup.as_slice()]);
+
+        let pg_wire_listener = PgWireListener::new(
+            test_case.clone(),
+            ProtocolConfiguration::not_secure().with_max_message_len(16),
+            ConnSupervisor::new(1, 2),
+        );
+
+        let result = pg_wire_listener.accept().await.expect("no io errors");
+        assert_eq!(
+            result.err(),
+            Some(Error::from(HandShakeError::from(
+                HandShakeErrorKind::InvalidMessageLength {
+                    len: setup.len() as i32,
+                    max: 16
+                }
+            )))
+        );
+    });
+}
+
+#[test]
+fn accepted_connection_gets_configured_settings() {
+    use std::time::Duration;
+
+    block_on(async {
+        let test_case = TestCase::new(vec![pg_frontend::Message::Setup(vec![("user", "username")])
+            .as_vec()
+            .as_slice()]);
+
+        let pg_wire_listener = PgWireListener::new(
+            test_case.clone(),
+            ProtocolConfiguration::not_secure()
+                .with_max_message_len(1024)
+                .with_idle_timeout(Some(Duration::from_secs(60))),
+            ConnSupervisor::new(1, 2),
+        );
+
+        match pg_wire_listener.accept().await.expect("no io errors") {
+            Ok(ClientRequest::Connect((_, _, _, _, settings))) => {
+                assert_eq!(settings.max_message_len(), 1024);
+                assert_eq!(settings.idle_timeout(), Some(Duration::from_secs(60)));
+                assert_eq!(
+                    settings.parameter_statuses(),
+                    ProtocolConfiguration::not_secure().parameter_statuses()
+                );
+            }
+            _ => panic!("connection is expected"),
+        }
+    });
+}
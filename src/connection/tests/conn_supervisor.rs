@@ -0,0 +1,134 @@
+// Copyright 2020 - 2021 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{connection::ConnSupervisor, ConnId};
+use std::sync::atomic::Ordering;
+
+#[test]
+fn no_active_connections() {
+    let conn_supervisor = ConnSupervisor::new(1, 10);
+
+    assert_eq!(conn_supervisor.active_count(), 0);
+    assert_eq!(conn_supervisor.active_ids(), Vec::<ConnId>::new());
+}
+
+#[test]
+fn count_and_list_active_connections() {
+    let conn_supervisor = ConnSupervisor::new(1, 10);
+    for _ in 0..4 {
+        conn_supervisor.alloc().unwrap();
+    }
+    conn_supervisor.free(2);
+
+    assert_eq!(conn_supervisor.active_count(), 3);
+    assert_eq!(conn_supervisor.active_ids(), vec![1, 3, 4]);
+}
+
+#[test]
+fn reused_id_is_listed_in_order() {
+    let conn_supervisor = ConnSupervisor::new(1, 10);
+    for _ in 0..3 {
+        conn_supervisor.alloc().unwrap();
+    }
+    conn_supervisor.free(1);
+    conn_supervisor.alloc().unwrap();
+
+    assert_eq!(conn_supervisor.active_count(), 3);
+    assert_eq!(conn_supervisor.active_ids(), vec![1, 2, 3]);
+}
+
+#[test]
+fn no_token_for_not_allocated_connection() {
+    let conn_supervisor = ConnSupervisor::new(1, 10);
+
+    assert!(conn_supervisor.token(1).is_none());
+}
+
+#[test]
+fn cancel_verified_connection() {
+    let conn_supervisor = ConnSupervisor::new(1, 10);
+    let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
+    let token = conn_supervisor.token(conn_id).expect("token of allocated connection");
+    assert!(!token.load(Ordering::SeqCst));
+
+    assert!(conn_supervisor.verify(conn_id, secret_key));
+    conn_supervisor
+        .token(conn_id)
+        .expect("token of allocated connection")
+        .store(true, Ordering::SeqCst);
+
+    assert!(token.load(Ordering::SeqCst));
+}
+
+#[test]
+fn no_token_after_free() {
+    let conn_supervisor = ConnSupervisor::new(1, 10);
+    let (conn_id, _) = conn_supervisor.alloc().unwrap();
+    conn_supervisor.free(conn_id);
+
+    assert!(conn_supervisor.token(conn_id).is_none());
+}
+
+#[test]
+fn reused_id_gets_fresh_token() {
+    let conn_supervisor = ConnSupervisor::new(1, 10);
+    let (conn_id, _) = conn_supervisor.alloc().unwrap();
+    conn_supervisor.token(conn_id).unwrap().store(true, Ordering::SeqCst);
+    conn_supervisor.free(conn_id);
+
+    let (reused_id, _) = conn_supervisor.alloc().unwrap();
+
+    assert_eq!(reused_id, conn_id);
+    assert!(!conn_supervisor.token(reused_id).unwrap().load(Ordering::SeqCst));
+}
+
+#[test]
+fn capacity() {
+    assert_eq!(ConnSupervisor::new(1, 10).capacity(), 10);
+    assert_eq!(ConnSupervisor::new(0, 0).capacity(), 1);
+    assert_eq!(ConnSupervisor::new(ConnId::MIN, ConnId::MAX).capacity(), 1 << 32);
+    assert_eq!(ConnSupervisor::new(10, 1).capacity(), 0);
+}
+
+#[test]
+fn full_at_exhaustion() {
+    let conn_supervisor = ConnSupervisor::new(1, 3);
+    conn_supervisor.alloc().unwrap();
+    conn_supervisor.alloc().unwrap();
+    assert!(!conn_supervisor.is_full());
+
+    conn_supervisor.alloc().unwrap();
+
+    assert!(conn_supervisor.is_full());
+    assert!(conn_supervisor.alloc().is_err());
+}
+
+#[test]
+fn not_full_after_free() {
+    let conn_supervisor = ConnSupervisor::new(1, 2);
+    let (conn_id, _) = conn_supervisor.alloc().unwrap();
+    conn_supervisor.alloc().unwrap();
+    assert!(conn_supervisor.is_full());
+
+    conn_supervisor.free(conn_id);
+
+    assert!(!conn_supervisor.is_full());
+    conn_supervisor.alloc().unwrap();
+    assert!(conn_supervisor.is_full());
+}
+
+#[test]
+fn empty_range_is_full() {
+    assert!(ConnSupervisor::new(10, 1).is_full());
+}
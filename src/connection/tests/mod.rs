@@ -15,6 +15,16 @@
 #[cfg(test)]
 mod accept_client_request;
 #[cfg(test)]
+mod conn_supervisor;
+#[cfg(test)]
+mod parameters;
+#[cfg(test)]
 mod pg_frontend;
 #[cfg(test)]
 mod read_query;
+#[cfg(test)]
+mod response_sender;
+#[cfg(test)]
+mod split_connection;
+#[cfg(test)]
+mod tls_identity;
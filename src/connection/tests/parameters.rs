@@ -0,0 +1,139 @@
+// Copyright 2020 - 2021 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::connection::{
+    network::{mock_net::TestCase, Channel, Stream},
+    ConnSupervisor, Connection, ConnectionSettings, ProtocolConfiguration,
+};
+use async_mutex::Mutex as AsyncMutex;
+use futures_lite::future::block_on;
+use pg_wire_payload::{BackendMessage, DatetimeEncoding};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+};
+
+fn connection(test_case: TestCase, client_props: &[(&str, &str)]) -> Connection {
+    let conn_supervisor = ConnSupervisor::new(1, 2);
+    let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
+    Connection::new(
+        conn_id,
+        secret_key,
+        client_props
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect(),
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
+        Arc::new(AsyncMutex::new(Channel::Plain(Stream::from(test_case)))),
+        conn_supervisor,
+        ConnectionSettings::default(),
+    )
+}
+
+#[test]
+fn parameters_from_startup_packet() {
+    let connection = connection(
+        TestCase::new(vec![]),
+        &[
+            ("user", "postgres"),
+            ("database", "test_db"),
+            ("application_name", "psql"),
+            ("_pq_.foo", "on"),
+        ],
+    );
+
+    assert_eq!(connection.parameter("application_name"), Some("psql"));
+    assert_eq!(connection.parameter("user"), None);
+    assert_eq!(connection.parameter("database"), None);
+    assert_eq!(connection.parameter("_pq_.foo"), None);
+}
+
+#[test]
+fn client_parameters_override_defaults() {
+    let connection = connection(TestCase::new(vec![]), &[("DateStyle", "ISO, DMY")])
+        .with_default_parameters(ProtocolConfiguration::not_secure().parameter_statuses());
+
+    assert_eq!(connection.parameter("DateStyle"), Some("ISO, DMY"));
+    assert_eq!(connection.parameter("client_encoding"), Some("UTF8"));
+}
+
+#[test]
+fn configured_parameter_statuses() {
+    let conn_supervisor = ConnSupervisor::new(1, 2);
+    let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
+    let connection = Connection::new(
+        conn_id,
+        secret_key,
+        vec![("DateStyle".to_owned(), "ISO, DMY".to_owned())],
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
+        Arc::new(AsyncMutex::new(Channel::Plain(Stream::from(TestCase::new(vec![]))))),
+        conn_supervisor,
+        ProtocolConfiguration::not_secure()
+            .with_parameter_statuses(vec![
+                ("server_version".to_owned(), "13.2".to_owned()),
+                ("DateStyle".to_owned(), "ISO".to_owned()),
+            ])
+            .connection_settings(),
+    );
+
+    assert_eq!(connection.parameter("server_version"), Some("13.2"));
+    assert_eq!(connection.parameter("DateStyle"), Some("ISO, DMY"));
+    assert_eq!(connection.parameter("client_encoding"), None);
+}
+
+#[test]
+fn set_parameter_stores_and_reports_it() {
+    block_on(async {
+        let test_case = TestCase::new(vec![]);
+        let mut connection = connection(test_case.clone(), &[("client_encoding", "UTF8")]);
+
+        assert!(matches!(connection.set_parameter("client_encoding", "LATIN1"), Ok(())));
+
+        assert_eq!(connection.parameter("client_encoding"), Some("LATIN1"));
+        assert_eq!(
+            test_case.read_result().await,
+            BackendMessage::ParameterStatus("client_encoding".to_owned(), "LATIN1".to_owned()).as_vec()
+        );
+    });
+}
+
+#[test]
+fn parameters_are_kept_by_writer_half() {
+    let (_reader, mut writer) = connection(TestCase::new(vec![]), &[("application_name", "psql")]).split();
+
+    assert_eq!(writer.parameter("application_name"), Some("psql"));
+    assert!(matches!(writer.set_parameter("application_name", "app"), Ok(())));
+    assert_eq!(writer.parameter("application_name"), Some("app"));
+}
+
+#[test]
+fn datetime_encoding_is_configured() {
+    let conn_supervisor = ConnSupervisor::new(1, 2);
+    let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
+    let connection = Connection::new(
+        conn_id,
+        secret_key,
+        vec![],
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
+        Arc::new(AsyncMutex::new(Channel::Plain(Stream::from(TestCase::new(vec![]))))),
+        conn_supervisor,
+        ProtocolConfiguration::not_secure()
+            .with_datetime_encoding(DatetimeEncoding::Float)
+            .connection_settings(),
+    );
+
+    assert_eq!(connection.datetime_encoding(), DatetimeEncoding::Float);
+    let (_reader, writer) = connection.split();
+    assert_eq!(writer.datetime_encoding(), DatetimeEncoding::Float);
+}
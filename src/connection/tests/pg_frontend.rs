@@ -19,6 +19,7 @@ pub enum Message {
     Query(&'static str),
     Terminate,
     Setup(Vec<(&'static str, &'static str)>),
+    SetupWithMinorVersion(u16, Vec<(&'static str, &'static str)>),
     SslDisabled,
     SslRequired,
     Password(&'static str),
@@ -38,10 +39,11 @@ impl Message {
                 buff
             }
             Message::Terminate => vec![TERMINATE, 0, 0, 0, 4],
-            Message::Setup(params) => {
+            Message::Setup(params) => Message::SetupWithMinorVersion(0, params.clone()).as_vec(),
+            Message::SetupWithMinorVersion(minor_version, params) => {
                 let mut buff = Vec::new();
                 buff.extend_from_slice(&3u16.to_be_bytes());
-                buff.extend_from_slice(&0u16.to_be_bytes());
+                buff.extend_from_slice(&minor_version.to_be_bytes());
                 for (key, value) in params {
                     buff.extend_from_slice(key.as_bytes());
                     buff.extend_from_slice(&[0]);
@@ -114,7 +116,7 @@ mod tests {
 
     #[test]
     fn ssl_disabled() {
-        assert_eq!(Message::SslDisabled.as_vec(), vec![])
+        assert_eq!(Message::SslDisabled.as_vec(), Vec::<u8>::new())
     }
 
     #[test]
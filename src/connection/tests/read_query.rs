@@ -15,15 +15,17 @@
 use crate::{
     connection::{
         network::{mock_net::TestCase, Channel, Stream},
-        ConnSupervisor, Connection,
+        ConnSupervisor, Connection, ConnectionSettings, ProtocolConfiguration,
     },
-    CommandMessage,
+    BackendMessage, CommandMessage, ErrorFields, MessageDecoderMode, MessageFormatError, MessageFormatErrorType,
 };
 use async_mutex::Mutex as AsyncMutex;
 use futures_lite::future::block_on;
 use std::{
+    io,
     net::{IpAddr, Ipv4Addr, SocketAddr},
     sync::Arc,
+    time::Duration,
 };
 
 #[test]
@@ -32,13 +34,15 @@ fn read_termination_command() {
         let stream = Stream::from(TestCase::new(vec![&[88], &[0, 0, 0, 4]]));
         let channel = Arc::new(AsyncMutex::new(Channel::Plain(stream)));
         let conn_supervisor = ConnSupervisor::new(1, 2);
-        let (conn_id, _) = conn_supervisor.alloc().unwrap();
+        let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
         let mut connection = Connection::new(
             conn_id,
+            secret_key,
             vec![],
             SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
             channel,
             conn_supervisor,
+            ConnectionSettings::default(),
         );
 
         let query = connection.receive().await.expect("no io errors");
@@ -52,13 +56,15 @@ fn read_query_successfully() {
         let stream = Stream::from(TestCase::new(vec![&[81], &[0, 0, 0, 14], b"select 1;\0"]));
         let channel = Arc::new(AsyncMutex::new(Channel::Plain(stream)));
         let conn_supervisor = ConnSupervisor::new(1, 2);
-        let (conn_id, _) = conn_supervisor.alloc().unwrap();
+        let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
         let mut connection = Connection::new(
             conn_id,
+            secret_key,
             vec![],
             SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
             channel,
             conn_supervisor,
+            ConnectionSettings::default(),
         );
 
         let query = connection.receive().await.expect("no io errors");
@@ -71,22 +77,422 @@ fn read_query_successfully() {
     });
 }
 
+#[test]
+fn read_query_delivered_byte_by_byte() {
+    block_on(async {
+        let stream = Stream::from(TestCase::with_read_limit(
+            vec![&[81], &[0, 0, 0, 14], b"select 1;\0", &[88], &[0, 0, 0, 4]],
+            1,
+        ));
+        let channel = Arc::new(AsyncMutex::new(Channel::Plain(stream)));
+        let conn_supervisor = ConnSupervisor::new(1, 2);
+        let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
+        let mut connection = Connection::new(
+            conn_id,
+            secret_key,
+            vec![],
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
+            channel,
+            conn_supervisor,
+            ConnectionSettings::default(),
+        );
+
+        let query = connection.receive().await.expect("no io errors");
+        assert_eq!(
+            query,
+            Ok(CommandMessage::Query {
+                sql: "select 1;".to_owned()
+            })
+        );
+        let terminate = connection.receive().await.expect("no io errors");
+        assert_eq!(terminate, Ok(CommandMessage::Terminate));
+    });
+}
+
+#[test]
+fn client_disconnected_in_the_middle_of_message() {
+    block_on(async {
+        let stream = Stream::from(TestCase::with_read_limit(vec![&[81], &[0, 0, 0, 14], b"sel"], 2));
+        let channel = Arc::new(AsyncMutex::new(Channel::Plain(stream)));
+        let conn_supervisor = ConnSupervisor::new(1, 2);
+        let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
+        let mut connection = Connection::new(
+            conn_id,
+            secret_key,
+            vec![],
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
+            channel,
+            conn_supervisor,
+            ConnectionSettings::default(),
+        );
+
+        let query = connection.receive().await.expect("no io errors");
+        assert_eq!(query, Ok(CommandMessage::Terminate));
+    });
+}
+
+#[test]
+fn unsupported_message_tag() {
+    block_on(async {
+        let stream = Stream::from(TestCase::new(vec![b"!", &[0, 0, 0, 4], &[88], &[0, 0, 0, 4]]));
+        let channel = Arc::new(AsyncMutex::new(Channel::Plain(stream)));
+        let conn_supervisor = ConnSupervisor::new(1, 2);
+        let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
+        let mut connection = Connection::new(
+            conn_id,
+            secret_key,
+            vec![],
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
+            channel,
+            conn_supervisor,
+            ConnectionSettings::default(),
+        );
+
+        let error = connection
+            .receive()
+            .await
+            .expect("no io errors")
+            .expect_err("unsupported message");
+        assert_eq!(error.to_string(), "unsupported frontend message tag '!'");
+        assert_eq!(
+            error.message_format_error().map(MessageFormatError::kind),
+            Some(MessageFormatErrorType::UnsupportedFrontendMessage)
+        );
+        assert_eq!(
+            BackendMessage::from(&error),
+            BackendMessage::ErrorResponse(ErrorFields::new(
+                "ERROR",
+                "08P01",
+                "unsupported frontend message tag '!'"
+            ))
+        );
+
+        let terminate = connection.receive().await.expect("no io errors");
+        assert_eq!(terminate, Ok(CommandMessage::Terminate));
+    });
+}
+
+#[test]
+fn malformed_message_payload() {
+    block_on(async {
+        let stream = Stream::from(TestCase::new(vec![
+            &[81],
+            &[0, 0, 0, 13],
+            b"select 1;",
+            &[88],
+            &[0, 0, 0, 4],
+        ]));
+        let channel = Arc::new(AsyncMutex::new(Channel::Plain(stream)));
+        let conn_supervisor = ConnSupervisor::new(1, 2);
+        let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
+        let mut connection = Connection::new(
+            conn_id,
+            secret_key,
+            vec![],
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
+            channel,
+            conn_supervisor,
+            ConnectionSettings::default(),
+        );
+
+        let error = connection
+            .receive()
+            .await
+            .expect("no io errors")
+            .expect_err("malformed message");
+        assert_eq!(
+            error.message_format_error().map(MessageFormatError::kind),
+            Some(MessageFormatErrorType::PayloadError)
+        );
+
+        let terminate = connection.receive().await.expect("no io errors");
+        assert_eq!(terminate, Ok(CommandMessage::Terminate));
+    });
+}
+
+#[test]
+fn message_longer_than_configured_max_message_len() {
+    block_on(async {
+        let stream = Stream::from(TestCase::new(vec![&[81], &[0, 0, 0, 14], b"select 1;\0"]));
+        let channel = Arc::new(AsyncMutex::new(Channel::Plain(stream)));
+        let conn_supervisor = ConnSupervisor::new(1, 2);
+        let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
+        let mut connection = Connection::new(
+            conn_id,
+            secret_key,
+            vec![],
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
+            channel,
+            conn_supervisor,
+            ProtocolConfiguration::not_secure()
+                .with_max_message_len(10)
+                .connection_settings(),
+        );
+
+        let error = connection
+            .receive()
+            .await
+            .expect("no io errors")
+            .expect_err("message is too long");
+        assert_eq!(error.to_string(), "invalid message length 14, expected from 4 to 10");
+    });
+}
+
+#[test]
+fn read_many_messages_with_shared_buffer() {
+    block_on(async {
+        let long_query = format!("select '{}';", "a".repeat(1_000));
+        let long_query_len = (4 + long_query.len() + 1) as i32;
+        let stream = Stream::from(TestCase::new(vec![
+            &[81],
+            &[0, 0, 0, 14],
+            b"select 1;\0",
+            &[81],
+            &long_query_len.to_be_bytes(),
+            long_query.as_bytes(),
+            &[0],
+            &[81],
+            &[0, 0, 0, 9],
+            b"sel;\0",
+            &[88],
+            &[0, 0, 0, 4],
+        ]));
+        let channel = Arc::new(AsyncMutex::new(Channel::Plain(stream)));
+        let conn_supervisor = ConnSupervisor::new(1, 2);
+        let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
+        let mut connection = Connection::new(
+            conn_id,
+            secret_key,
+            vec![],
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
+            channel,
+            conn_supervisor,
+            ConnectionSettings::default(),
+        );
+
+        for sql in &["select 1;", long_query.as_str(), "sel;"] {
+            assert_eq!(
+                connection.receive().await.expect("no io errors"),
+                Ok(CommandMessage::Query { sql: (*sql).to_owned() })
+            );
+        }
+        assert_eq!(
+            connection.receive().await.expect("no io errors"),
+            Ok(CommandMessage::Terminate)
+        );
+    });
+}
+
+#[test]
+fn read_sasl_response() {
+    block_on(async {
+        let stream = Stream::from(TestCase::new(vec![&[112], &[0, 0, 0, 13], b"c=biws,r="]));
+        let channel = Arc::new(AsyncMutex::new(Channel::Plain(stream)));
+        let conn_supervisor = ConnSupervisor::new(1, 2);
+        let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
+        let mut connection = Connection::new(
+            conn_id,
+            secret_key,
+            vec![],
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
+            channel,
+            conn_supervisor,
+            ConnectionSettings::default(),
+        );
+        connection.set_decoder_mode(MessageDecoderMode::SaslResponse);
+
+        let query = connection.receive().await.expect("no io errors");
+        assert_eq!(
+            query,
+            Ok(CommandMessage::SaslResponse {
+                data: b"c=biws,r=".to_vec()
+            })
+        );
+    });
+}
+
+#[test]
+fn read_timed_out() {
+    block_on(async {
+        let stream = Stream::from(TestCase::never_ready());
+        let channel = Arc::new(AsyncMutex::new(Channel::Plain(stream)));
+        let conn_supervisor = ConnSupervisor::new(1, 2);
+        let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
+        let mut connection = Connection::new(
+            conn_id,
+            secret_key,
+            vec![],
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
+            channel,
+            conn_supervisor,
+            ConnectionSettings::default(),
+        );
+        connection.set_read_timeout(Some(Duration::from_millis(10)));
+
+        let error = connection.receive().await.expect_err("read timed out");
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+    });
+}
+
+#[test]
+fn idle_timeout_is_considered_as_terminate() {
+    block_on(async {
+        let stream = Stream::from(TestCase::never_ready());
+        let channel = Arc::new(AsyncMutex::new(Channel::Plain(stream)));
+        let conn_supervisor = ConnSupervisor::new(1, 2);
+        let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
+        let mut connection = Connection::new(
+            conn_id,
+            secret_key,
+            vec![],
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
+            channel,
+            conn_supervisor,
+            ConnectionSettings::default(),
+        );
+        connection.set_idle_timeout(Some(Duration::from_millis(10)));
+
+        assert_eq!(
+            connection.receive().await.expect("no io errors"),
+            Ok(CommandMessage::Terminate)
+        );
+    });
+}
+
+#[test]
+fn configured_idle_timeout_is_applied() {
+    block_on(async {
+        let stream = Stream::from(TestCase::never_ready());
+        let channel = Arc::new(AsyncMutex::new(Channel::Plain(stream)));
+        let conn_supervisor = ConnSupervisor::new(1, 2);
+        let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
+        let mut connection = Connection::new(
+            conn_id,
+            secret_key,
+            vec![],
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
+            channel,
+            conn_supervisor,
+            ProtocolConfiguration::not_secure()
+                .with_idle_timeout(Some(Duration::from_millis(10)))
+                .connection_settings(),
+        );
+
+        assert_eq!(
+            connection.receive().await.expect("no io errors"),
+            Ok(CommandMessage::Terminate)
+        );
+    });
+}
+
+#[test]
+fn idle_timeout_resets_on_each_message() {
+    block_on(async {
+        let stream = Stream::from(TestCase::with_read_delays(vec![
+            (Duration::from_millis(30), &[81, 0, 0, 0, 14]),
+            (Duration::ZERO, b"select 1;\0"),
+            (Duration::from_millis(30), &[81, 0, 0, 0, 14]),
+            (Duration::ZERO, b"select 2;\0"),
+        ]));
+        let channel = Arc::new(AsyncMutex::new(Channel::Plain(stream)));
+        let conn_supervisor = ConnSupervisor::new(1, 2);
+        let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
+        let mut connection = Connection::new(
+            conn_id,
+            secret_key,
+            vec![],
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
+            channel,
+            conn_supervisor,
+            ConnectionSettings::default(),
+        );
+        // both pauses together exceed the timeout, each of them does not
+        connection.set_idle_timeout(Some(Duration::from_millis(50)));
+
+        for sql in &["select 1;", "select 2;"] {
+            assert_eq!(
+                connection.receive().await.expect("no io errors"),
+                Ok(CommandMessage::Query { sql: sql.to_string() })
+            );
+        }
+    });
+}
+
+#[test]
+fn idle_timeout_elapses_between_messages() {
+    block_on(async {
+        let stream = Stream::from(TestCase::with_read_delays(vec![
+            (Duration::ZERO, &[81, 0, 0, 0, 14]),
+            (Duration::ZERO, b"select 1;\0"),
+            (Duration::from_millis(100), &[81, 0, 0, 0, 14]),
+            (Duration::ZERO, b"select 2;\0"),
+        ]));
+        let channel = Arc::new(AsyncMutex::new(Channel::Plain(stream)));
+        let conn_supervisor = ConnSupervisor::new(1, 2);
+        let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
+        let mut connection = Connection::new(
+            conn_id,
+            secret_key,
+            vec![],
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
+            channel,
+            conn_supervisor,
+            ConnectionSettings::default(),
+        );
+        connection.set_idle_timeout(Some(Duration::from_millis(10)));
+
+        assert_eq!(
+            connection.receive().await.expect("no io errors"),
+            Ok(CommandMessage::Query {
+                sql: "select 1;".to_owned()
+            })
+        );
+        assert_eq!(
+            connection.receive().await.expect("no io errors"),
+            Ok(CommandMessage::Terminate)
+        );
+    });
+}
+
 #[test]
 fn client_disconnected_immediately() {
     block_on(async {
         let stream = Stream::from(TestCase::new(vec![]));
         let channel = Arc::new(AsyncMutex::new(Channel::Plain(stream)));
         let conn_supervisor = ConnSupervisor::new(1, 2);
-        let (conn_id, _) = conn_supervisor.alloc().unwrap();
+        let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
         let mut connection = Connection::new(
             conn_id,
+            secret_key,
             vec![],
             SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
             channel,
             conn_supervisor,
+            ConnectionSettings::default(),
         );
 
         let query = connection.receive().await.expect("no io errors");
         assert_eq!(query, Ok(CommandMessage::Terminate));
     });
 }
+
+#[test]
+fn connection_id_and_secret_key() {
+    let stream = Stream::from(TestCase::new(vec![]));
+    let channel = Arc::new(AsyncMutex::new(Channel::Plain(stream)));
+    let conn_supervisor = ConnSupervisor::new(1, 2);
+    let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
+    let connection = Connection::new(
+        conn_id,
+        secret_key,
+        vec![],
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
+        channel,
+        conn_supervisor.clone(),
+        ConnectionSettings::default(),
+    );
+
+    assert_eq!(connection.id(), conn_id);
+    assert_eq!(connection.secret_key(), secret_key);
+    assert!(conn_supervisor.verify(connection.id(), connection.secret_key()));
+}
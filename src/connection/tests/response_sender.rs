@@ -0,0 +1,207 @@
+// Copyright 2020 - 2021 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::connection::{
+    network::{mock_net::TestCase, Channel, SecureStream, Stream},
+    ConnSupervisor, Connection, ConnectionSettings, ResponseSender, Sender,
+};
+use async_mutex::Mutex as AsyncMutex;
+use futures_lite::future::block_on;
+use pg_wire_payload::{BackendMessage, ColumnMetadata, CommandComplete, ErrorFields, PgType, TransactionStatus};
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+};
+
+fn sender(test_case: TestCase) -> ResponseSender {
+    ResponseSender::new(Arc::new(AsyncMutex::new(Channel::Plain(Stream::from(test_case)))))
+}
+
+fn select_one() -> Vec<BackendMessage> {
+    vec![
+        BackendMessage::RowDescription(vec![ColumnMetadata::new("col1", PgType::Integer)]),
+        BackendMessage::DataRow(vec!["1".to_owned()]),
+        CommandComplete::select(1),
+        BackendMessage::ReadyForQuery,
+    ]
+}
+
+#[test]
+fn batch_is_the_same_as_individual_sends() {
+    let individual = TestCase::new(vec![]);
+    let individual_sender = sender(individual.clone());
+    for message in select_one() {
+        individual_sender.send(message).expect("message sent");
+    }
+
+    let batched = TestCase::new(vec![]);
+    sender(batched.clone())
+        .send_batch(&select_one())
+        .expect("messages sent");
+
+    assert_eq!(block_on(batched.read_result()), block_on(individual.read_result()));
+}
+
+#[test]
+fn batch_is_written_with_single_vectored_write() {
+    let test_case = TestCase::new(vec![]);
+    sender(test_case.clone())
+        .send_batch(&select_one())
+        .expect("messages sent");
+
+    assert_eq!(test_case.write_calls(), 1);
+}
+
+#[test]
+fn batch_partially_written_by_vectored_writes() {
+    let expected = select_one()
+        .iter()
+        .flat_map(BackendMessage::as_vec)
+        .collect::<Vec<u8>>();
+
+    for limit in &[1, 3, 7, 64] {
+        let test_case = TestCase::with_write_limit(*limit);
+        sender(test_case.clone())
+            .send_batch(&select_one())
+            .expect("messages sent");
+
+        assert_eq!(block_on(test_case.read_result()), expected);
+    }
+}
+
+#[test]
+fn batch_over_secure_channel_is_copied_into_single_buffer() {
+    let test_case = TestCase::new(vec![]);
+    ResponseSender::new(Arc::new(AsyncMutex::new(Channel::Secure(SecureStream::from(
+        test_case.clone(),
+    )))))
+    .send_batch(&select_one())
+    .expect("messages sent");
+
+    assert_eq!(test_case.write_calls(), 1);
+    assert_eq!(
+        block_on(test_case.read_result()),
+        select_one()
+            .iter()
+            .flat_map(BackendMessage::as_vec)
+            .collect::<Vec<u8>>()
+    );
+}
+
+#[test]
+fn send_all_then_flush() {
+    let test_case = TestCase::new(vec![]);
+    sender(test_case.clone())
+        .send_all_then_flush(&select_one())
+        .expect("messages sent");
+
+    assert_eq!(
+        block_on(test_case.read_result()),
+        select_one()
+            .iter()
+            .flat_map(BackendMessage::as_vec)
+            .collect::<Vec<u8>>()
+    );
+}
+
+#[test]
+fn ready_for_query_reports_transaction_status() {
+    let test_case = TestCase::new(vec![]);
+    let channel = Arc::new(AsyncMutex::new(Channel::Plain(Stream::from(test_case.clone()))));
+    let conn_supervisor = ConnSupervisor::new(1, 2);
+    let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
+    let connection = Connection::new(
+        conn_id,
+        secret_key,
+        vec![],
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
+        channel,
+        conn_supervisor,
+        ConnectionSettings::default(),
+    );
+    let sender = connection.sender();
+
+    assert_eq!(connection.transaction_status(), TransactionStatus::Idle);
+    sender.send_ready_for_query().expect("message sent");
+    connection.set_transaction_status(TransactionStatus::InTransaction);
+    sender.send_ready_for_query().expect("message sent");
+    connection.set_transaction_status(TransactionStatus::Failed);
+    sender.send_ready_for_query().expect("message sent");
+    connection.set_transaction_status(TransactionStatus::Idle);
+    sender.send_ready_for_query().expect("message sent");
+
+    let statuses = block_on(test_case.read_result())
+        .chunks(6)
+        .map(|message| message[5])
+        .collect::<Vec<u8>>();
+    assert_eq!(statuses, b"ITEI".to_vec());
+}
+
+#[test]
+fn report_parameter() {
+    let test_case = TestCase::new(vec![]);
+    sender(test_case.clone())
+        .report_parameter("search_path", "public")
+        .expect("parameter reported");
+
+    assert_eq!(
+        block_on(test_case.read_result()),
+        BackendMessage::ParameterStatus("search_path".to_owned(), "public".to_owned()).as_vec()
+    );
+}
+
+#[test]
+fn fatal_disconnect() {
+    let test_case = TestCase::new(vec![]);
+    sender(test_case.clone())
+        .fatal_disconnect("terminating connection due to administrator command")
+        .expect("error reported");
+
+    let content = block_on(test_case.read_result());
+    assert_eq!(content[0], b'E');
+    assert_eq!(
+        i32::from_be_bytes([content[1], content[2], content[3], content[4]]) as usize,
+        content.len() - 1
+    );
+    assert_eq!(
+        content[5..],
+        b"SFATAL\0C57P01\0Mterminating connection due to administrator command\0\0"[..]
+    );
+    assert_eq!(
+        content,
+        BackendMessage::ErrorResponse(ErrorFields::new(
+            "FATAL",
+            "57P01",
+            "terminating connection due to administrator command"
+        ))
+        .as_vec()
+    );
+}
+
+#[test]
+fn send_to_disconnected_client() {
+    let sender = sender(TestCase::broken_pipe());
+
+    let error = sender.send(BackendMessage::ReadyForQuery).expect_err("write fails");
+    assert_eq!(error.kind(), io::ErrorKind::BrokenPipe);
+}
+
+#[test]
+fn send_batch_to_disconnected_client() {
+    let sender = sender(TestCase::broken_pipe());
+
+    let error = sender.send_batch(&select_one()).expect_err("write fails");
+    assert_eq!(error.kind(), io::ErrorKind::BrokenPipe);
+}
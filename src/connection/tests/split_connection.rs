@@ -0,0 +1,96 @@
+// Copyright 2020 - 2021 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    connection::{
+        network::{mock_net::TestCase, Channel, Stream},
+        ConnSupervisor, Connection, ConnectionSettings, Sender,
+    },
+    CommandMessage,
+};
+use async_mutex::Mutex as AsyncMutex;
+use futures_lite::future::{self, block_on};
+use pg_wire_payload::BackendMessage;
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+};
+
+fn connection(test_case: TestCase, conn_supervisor: ConnSupervisor) -> Connection {
+    let (conn_id, secret_key) = conn_supervisor.alloc().unwrap();
+    Connection::new(
+        conn_id,
+        secret_key,
+        vec![],
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1000),
+        Arc::new(AsyncMutex::new(Channel::Plain(Stream::from(test_case)))),
+        conn_supervisor,
+        ConnectionSettings::default(),
+    )
+}
+
+#[test]
+fn receive_while_sending() {
+    block_on(async {
+        let test_case = TestCase::new(vec![&[81], &[0, 0, 0, 14], b"select 1;\0"]);
+        let (mut reader, writer) = connection(test_case.clone(), ConnSupervisor::new(1, 2)).split();
+
+        let (query, sent) = future::zip(reader.receive(), async { writer.send(BackendMessage::ParseComplete) }).await;
+
+        assert_eq!(
+            query.expect("no io errors"),
+            Ok(CommandMessage::Query {
+                sql: "select 1;".to_owned()
+            })
+        );
+        assert!(matches!(sent, Ok(())));
+        assert_eq!(test_case.read_result().await, BackendMessage::ParseComplete.as_vec());
+    });
+}
+
+#[test]
+fn send_while_client_is_silent() {
+    block_on(async {
+        let test_case = TestCase::never_ready();
+        let (mut reader, writer) = connection(test_case.clone(), ConnSupervisor::new(1, 2)).split();
+
+        let sent = future::or(
+            async {
+                let _ = reader.receive().await;
+                panic!("client has nothing to send")
+            },
+            async { writer.send(BackendMessage::EmptyQueryResponse) },
+        )
+        .await;
+
+        assert!(matches!(sent, Ok(())));
+        assert_eq!(
+            test_case.read_result().await,
+            BackendMessage::EmptyQueryResponse.as_vec()
+        );
+    });
+}
+
+#[test]
+fn connection_id_is_freed_when_both_halves_dropped() {
+    let conn_supervisor = ConnSupervisor::new(1, 2);
+    let (reader, writer) = connection(TestCase::new(vec![]), conn_supervisor.clone()).split();
+    assert_eq!(conn_supervisor.active_count(), 1);
+
+    drop(reader);
+    assert_eq!(conn_supervisor.active_count(), 1);
+
+    drop(writer);
+    assert_eq!(conn_supervisor.active_count(), 0);
+}
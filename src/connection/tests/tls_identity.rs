@@ -0,0 +1,76 @@
+// Copyright 2020 - 2021 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{connection::native_tls_identity, ProtocolConfiguration, TlsIdentity};
+use std::{fs, path::PathBuf};
+
+#[test]
+fn pem_configuration() {
+    let config = ProtocolConfiguration::with_pem_ssl(
+        PathBuf::from("./etc/pem/certificate.pem"),
+        PathBuf::from("./etc/pem/key.pem"),
+    );
+
+    assert!(config.ssl_support());
+    assert_eq!(
+        config.tls_identity(),
+        Some(&TlsIdentity::Pem {
+            certificate: PathBuf::from("./etc/pem/certificate.pem"),
+            key: PathBuf::from("./etc/pem/key.pem")
+        })
+    );
+    assert_eq!(config.ssl_config(), None);
+}
+
+#[test]
+fn pkcs12_configuration() {
+    let config = ProtocolConfiguration::with_ssl(PathBuf::from("./etc/identity.pfx"), "password".to_owned());
+
+    assert!(config.ssl_support());
+    assert_eq!(
+        config.ssl_config(),
+        Some(&(PathBuf::from("./etc/identity.pfx"), "password".to_owned()))
+    );
+    assert_eq!(
+        config.tls_identity(),
+        Some(&TlsIdentity::Pkcs12 {
+            path: PathBuf::from("./etc/identity.pfx"),
+            password: "password".to_owned()
+        })
+    );
+}
+
+#[test]
+fn load_pem_identity() {
+    let identity = TlsIdentity::Pem {
+        certificate: PathBuf::from("./etc/pem/certificate.pem"),
+        key: PathBuf::from("./etc/pem/key.pem"),
+    };
+    let certificate = fs::read("./etc/pem/certificate.pem").unwrap();
+    let key = fs::read("./etc/pem/key.pem").unwrap();
+
+    assert!(native_tls_identity(&identity, &certificate, &key).is_ok());
+}
+
+#[test]
+fn key_does_not_match_misplaced_files() {
+    let identity = TlsIdentity::Pem {
+        certificate: PathBuf::from("./etc/pem/key.pem"),
+        key: PathBuf::from("./etc/pem/certificate.pem"),
+    };
+    let certificate = fs::read("./etc/pem/certificate.pem").unwrap();
+    let key = fs::read("./etc/pem/key.pem").unwrap();
+
+    assert!(native_tls_identity(&identity, &key, &certificate).is_err());
+}
@@ -39,13 +39,24 @@ impl<'c> Cursor<'c> {
         self.buf = &self.buf[n..]
     }
 
-    fn peek_byte(&self) -> Result<u8, PayloadError> {
+    /// Returns number of bytes that are not read yet.
+    pub(crate) fn remaining(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns the next byte without advancing the cursor.
+    pub(crate) fn peek_byte(&self) -> Result<u8, PayloadError> {
         self.buf
             .get(0)
             .copied()
             .ok_or_else(|| PayloadError::from(PayloadErrorKind::EndOfBuffer))
     }
 
+    /// Returns the next 32-bit signed integer without advancing the cursor.
+    pub(crate) fn peek_i32(&self) -> Result<i32, PayloadError> {
+        Cursor::from(self.buf).read_i32()
+    }
+
     pub(crate) fn read_byte(&mut self) -> Result<u8, PayloadError> {
         let byte = self.peek_byte()?;
         self.advance(1);
@@ -55,7 +66,7 @@ impl<'c> Cursor<'c> {
     fn consume_next(&mut self, size: usize) -> Result<&'c [u8], PayloadError> {
         if self.buf.len() < size {
             Err(PayloadError::from(PayloadErrorKind::NotEnoughBytes {
-                required: size as u8,
+                required: size,
                 source: self.buf.to_vec(),
             }))
         } else {
@@ -65,6 +76,11 @@ impl<'c> Cursor<'c> {
         }
     }
 
+    /// Returns the next `len` bytes. The cursor is advanced past them.
+    pub(crate) fn read_bytes(&mut self, len: usize) -> Result<&'c [u8], PayloadError> {
+        self.consume_next(len)
+    }
+
     /// Returns the next null-terminated string. The null character is not
     /// included the returned string. The cursor is advanced past the null-
     /// terminated string.
@@ -109,6 +125,53 @@ impl<'c> Cursor<'c> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn remaining() {
+        let buffer = vec![1, 2, 3, 4, 5];
+        let mut cursor = Cursor::from(buffer.as_slice());
+        assert_eq!(cursor.remaining(), 5);
+        cursor.read_i32().unwrap();
+        assert_eq!(cursor.remaining(), 1);
+        cursor.read_byte().unwrap();
+        assert_eq!(cursor.remaining(), 0);
+    }
+
+    #[test]
+    fn peek_then_read_byte() {
+        let buffer = vec![1, 2];
+        let mut cursor = Cursor::from(buffer.as_slice());
+        assert_eq!(cursor.peek_byte(), Ok(1));
+        assert_eq!(cursor.peek_byte(), Ok(1));
+        assert_eq!(cursor.read_byte(), Ok(1));
+        assert_eq!(cursor.peek_byte(), Ok(2));
+        assert_eq!(cursor.remaining(), 1);
+    }
+
+    #[test]
+    fn peek_then_read_i32() {
+        let buffer = [123i32.to_be_bytes(), 456i32.to_be_bytes()].concat();
+        let mut cursor = Cursor::from(buffer.as_slice());
+        assert_eq!(cursor.peek_i32(), Ok(123));
+        assert_eq!(cursor.remaining(), 8);
+        assert_eq!(cursor.read_i32(), Ok(123));
+        assert_eq!(cursor.peek_i32(), Ok(456));
+        assert_eq!(cursor.read_i32(), Ok(456));
+    }
+
+    #[test]
+    fn error_peek_i32() {
+        let buffer = vec![0, 1];
+        let cursor = Cursor::from(buffer.as_slice());
+        assert_eq!(
+            cursor.peek_i32(),
+            Err(PayloadError::from(PayloadErrorKind::NotEnoughBytes {
+                required: 4,
+                source: buffer.to_vec()
+            }))
+        );
+        assert_eq!(cursor.remaining(), 2);
+    }
+
     #[test]
     fn ok_read_byte() {
         let buffer = vec![1];
@@ -161,6 +224,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ok_read_bytes() {
+        let buffer = vec![1, 2, 3, 4];
+        let mut cursor = Cursor::from(buffer.as_slice());
+        assert_eq!(cursor.read_bytes(3), Ok(&[1, 2, 3][..]));
+        assert_eq!(cursor.read_bytes(1), Ok(&[4][..]));
+    }
+
+    #[test]
+    fn zero_length_read_bytes() {
+        let buffer = vec![1];
+        let mut cursor = Cursor::from(buffer.as_slice());
+        assert_eq!(cursor.read_bytes(0), Ok(&[][..]));
+        assert_eq!(cursor.read_byte(), Ok(1));
+    }
+
+    #[test]
+    fn error_read_bytes() {
+        let buffer = vec![1, 2];
+        let mut cursor = Cursor::from(buffer.as_slice());
+        assert_eq!(
+            cursor.read_bytes(3),
+            Err(PayloadError::from(PayloadErrorKind::NotEnoughBytes {
+                required: 3,
+                source: buffer.to_vec()
+            }))
+        );
+    }
+
     #[test]
     fn ok_read_i16() {
         let buffer = 123i16.to_be_bytes().to_vec();
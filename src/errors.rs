@@ -13,12 +13,23 @@
 // limitations under the License.
 
 pub(crate) use hand_shake_error::*;
-pub(crate) use message_format_error::*;
-pub(crate) use payload_error::*;
-use std::fmt::{self, Display, Formatter};
+pub(crate) use message_format_error::MessageFormatErrorKind;
+pub use message_format_error::{MessageFormatError, MessageFormatErrorType};
+pub(crate) use payload_error::PayloadErrorKind;
+pub use payload_error::{PayloadError, PayloadErrorType};
+use pg_wire_payload::{BackendMessage, ErrorFields};
+use std::{
+    error::Error as StdError,
+    fmt::{self, Display, Formatter},
+};
+
+const PROTOCOL_VIOLATION: &str = "08P01";
+const CONNECTION_FAILURE: &str = "08006";
+const FEATURE_NOT_SUPPORTED: &str = "0A000";
+const INVALID_AUTHORIZATION_SPECIFICATION: &str = "28000";
 
 /// Protocol Error
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct Error {
     kind: ErrorKind,
 }
@@ -29,6 +40,22 @@ enum ErrorKind {
     MessageFormat(MessageFormatError),
     TlsHandShake(native_tls::Error),
     SecretKeysHaveNotMatch,
+    SslRequired,
+}
+
+/// `native_tls::Error` is not comparable, TLS errors are compared by their
+/// messages instead
+impl PartialEq for ErrorKind {
+    fn eq(&self, other: &ErrorKind) -> bool {
+        match (self, other) {
+            (ErrorKind::HandShake(left), ErrorKind::HandShake(right)) => left == right,
+            (ErrorKind::MessageFormat(left), ErrorKind::MessageFormat(right)) => left == right,
+            (ErrorKind::TlsHandShake(left), ErrorKind::TlsHandShake(right)) => left.to_string() == right.to_string(),
+            (ErrorKind::SecretKeysHaveNotMatch, ErrorKind::SecretKeysHaveNotMatch) => true,
+            (ErrorKind::SslRequired, ErrorKind::SslRequired) => true,
+            _ => false,
+        }
+    }
 }
 
 impl From<HandShakeError> for Error {
@@ -56,11 +83,27 @@ impl From<native_tls::Error> for Error {
 }
 
 impl Error {
+    /// Returns [MessageFormatError] if a client message could not be decoded,
+    /// its [kind](MessageFormatError::kind) tells unsupported message tags
+    /// apart from malformed payloads
+    pub fn message_format_error(&self) -> Option<&MessageFormatError> {
+        match &self.kind {
+            ErrorKind::MessageFormat(error) => Some(error),
+            _ => None,
+        }
+    }
+
     pub(crate) fn secret_keys_have_not_matched() -> Error {
         Error {
             kind: ErrorKind::SecretKeysHaveNotMatch,
         }
     }
+
+    pub(crate) fn ssl_required() -> Error {
+        Error {
+            kind: ErrorKind::SslRequired,
+        }
+    }
 }
 
 impl Display for Error {
@@ -73,15 +116,187 @@ impl Display for Error {
                 f,
                 "secret for query cancellation has not matched secret of the current connection"
             ),
+            ErrorKind::SslRequired => write!(f, "server requires SSL connection, connection without SSL is refused"),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match &self.kind {
+            ErrorKind::HandShake(error) => Some(error),
+            ErrorKind::MessageFormat(error) => Some(error),
+            ErrorKind::TlsHandShake(error) => Some(error),
+            ErrorKind::SecretKeysHaveNotMatch | ErrorKind::SslRequired => None,
         }
     }
 }
 
+/// Reports the error to the client as `ErrorResponse` with best matched
+/// SQLSTATE code. Refused connection without SSL is reported with `FATAL`
+/// severity as PostgreSQL does, other errors with `ERROR` severity
+impl From<&Error> for BackendMessage {
+    fn from(error: &Error) -> BackendMessage {
+        let (severity, code) = match &error.kind {
+            ErrorKind::HandShake(error) if error.is_unsupported_protocol_version() => ("ERROR", FEATURE_NOT_SUPPORTED),
+            ErrorKind::TlsHandShake(_) => ("ERROR", CONNECTION_FAILURE),
+            ErrorKind::SslRequired => ("FATAL", INVALID_AUTHORIZATION_SPECIFICATION),
+            ErrorKind::HandShake(_) | ErrorKind::MessageFormat(_) | ErrorKind::SecretKeysHaveNotMatch => {
+                ("ERROR", PROTOCOL_VIOLATION)
+            }
+        };
+        BackendMessage::ErrorResponse(ErrorFields::new(severity, code, error))
+    }
+}
+
+#[cfg(test)]
+mod equality {
+    use super::*;
+    use crate::request_codes::VERSION_1_CODE;
+
+    #[test]
+    fn same_hand_shake_errors() {
+        assert_eq!(
+            Error::from(HandShakeError::from(HandShakeErrorKind::UnsupportedProtocolVersion(
+                VERSION_1_CODE
+            ))),
+            Error::from(HandShakeError::from(HandShakeErrorKind::UnsupportedProtocolVersion(
+                VERSION_1_CODE
+            )))
+        );
+    }
+
+    #[test]
+    fn different_errors() {
+        assert_ne!(
+            Error::from(HandShakeError::from(HandShakeErrorKind::UnsupportedProtocolVersion(
+                VERSION_1_CODE
+            ))),
+            Error::secret_keys_have_not_matched()
+        );
+        assert_ne!(
+            Error::from(MessageFormatError::from(MessageFormatErrorKind::MissingMessageTag)),
+            Error::from(HandShakeError::from(HandShakeErrorKind::UnsupportedProtocolVersion(
+                VERSION_1_CODE
+            )))
+        );
+    }
+}
+
+#[cfg(test)]
+mod source {
+    use super::*;
+    use std::str::{self, Utf8Error};
+
+    #[test]
+    fn chain_down_to_utf8_error() {
+        let invalid_utf = vec![0x96];
+        let cause = str::from_utf8(&invalid_utf).unwrap_err();
+        let error = Error::from(HandShakeError::from(PayloadError::from(
+            PayloadErrorKind::InvalidUtfString {
+                cause,
+                source: invalid_utf.clone(),
+            },
+        )));
+
+        let chain = std::iter::successors(Some(&error as &(dyn StdError + 'static)), |&error| error.source())
+            .collect::<Vec<_>>();
+
+        assert_eq!(chain.len(), 4);
+        assert!(chain[1].is::<HandShakeError>());
+        assert!(chain[2].is::<PayloadError>());
+        assert_eq!(chain[3].downcast_ref::<Utf8Error>(), Some(&cause));
+    }
+
+    #[test]
+    fn message_format_error_chain() {
+        let error = Error::from(MessageFormatError::from(PayloadError::from(
+            PayloadErrorKind::EndOfBuffer,
+        )));
+
+        let payload_error = error.source().and_then(StdError::source);
+
+        assert!(payload_error.is_some_and(|error| error.is::<PayloadError>()));
+        assert!(payload_error.and_then(StdError::source).is_none());
+    }
+
+    #[test]
+    fn errors_without_cause() {
+        assert!(Error::secret_keys_have_not_matched().source().is_none());
+        assert!(Error::ssl_required().source().is_none());
+        assert!(MessageFormatError::from(MessageFormatErrorKind::MissingMessageTag)
+            .source()
+            .is_none());
+    }
+}
+
+#[cfg(test)]
+mod error_response {
+    use super::*;
+
+    #[test]
+    fn message_format_error() {
+        let error = Error::from(MessageFormatError::from(MessageFormatErrorKind::MissingMessageTag));
+
+        assert_eq!(
+            BackendMessage::from(&error),
+            BackendMessage::ErrorResponse(ErrorFields::new(
+                "ERROR",
+                PROTOCOL_VIOLATION,
+                "Message tag is not found in payload"
+            ))
+        );
+    }
+
+    #[test]
+    fn unsupported_protocol_version() {
+        let error = Error::from(HandShakeError::from(HandShakeErrorKind::UnsupportedProtocolVersion(
+            crate::request_codes::VERSION_1_CODE,
+        )));
+
+        assert_eq!(
+            BackendMessage::from(&error),
+            BackendMessage::ErrorResponse(ErrorFields::new(
+                "ERROR",
+                FEATURE_NOT_SUPPORTED,
+                "Unsupported Protocol Version: Version 1.0 Request"
+            ))
+        );
+    }
+
+    #[test]
+    fn secret_keys_have_not_matched() {
+        assert_eq!(
+            BackendMessage::from(&Error::secret_keys_have_not_matched()),
+            BackendMessage::ErrorResponse(ErrorFields::new(
+                "ERROR",
+                PROTOCOL_VIOLATION,
+                "secret for query cancellation has not matched secret of the current connection"
+            ))
+        );
+    }
+
+    #[test]
+    fn ssl_required() {
+        assert_eq!(
+            BackendMessage::from(&Error::ssl_required()),
+            BackendMessage::ErrorResponse(ErrorFields::new(
+                "FATAL",
+                INVALID_AUTHORIZATION_SPECIFICATION,
+                "server requires SSL connection, connection without SSL is refused"
+            ))
+        );
+    }
+}
+
 mod hand_shake_error {
     use crate::{errors::PayloadError, request_codes::Code};
-    use std::fmt::{self, Display, Formatter};
+    use std::{
+        error::Error,
+        fmt::{self, Display, Formatter},
+    };
 
-    /// An error which can be returned during [HandShakeProcess](crate::hand_shake::Process)
+    /// An error which can be returned during [HandShakeProcess](crate::HandShakeProcess)
     /// and client send erroneous bytes or functionality is not yet supported
     #[derive(Debug, PartialEq)]
     pub struct HandShakeError {
@@ -98,10 +313,33 @@ mod hand_shake_error {
                     write!(f, "Unsupported Client Code Request: '{}'", code)
                 }
                 HandShakeErrorKind::PayloadError(error) => write!(f, "{}", error),
+                HandShakeErrorKind::InvalidMessageLength { len, max } => {
+                    write!(f, "invalid startup packet length {}, expected from 4 to {}", len, max)
+                }
+                HandShakeErrorKind::MissingUser => write!(f, "no PostgreSQL user name specified in startup packet"),
+                HandShakeErrorKind::InvalidProxySignature => write!(f, "invalid PROXY protocol v2 signature"),
+                HandShakeErrorKind::UnsupportedProxyCommand(byte) => {
+                    write!(f, "unsupported PROXY protocol version and command 0x{:02x}", byte)
+                }
+            }
+        }
+    }
+
+    impl Error for HandShakeError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match &self.kind {
+                HandShakeErrorKind::PayloadError(error) => Some(error),
+                _ => None,
             }
         }
     }
 
+    impl HandShakeError {
+        pub(crate) fn is_unsupported_protocol_version(&self) -> bool {
+            matches!(self.kind, HandShakeErrorKind::UnsupportedProtocolVersion(_))
+        }
+    }
+
     impl<'e> From<HandShakeErrorKind> for HandShakeError {
         fn from(kind: HandShakeErrorKind) -> HandShakeError {
             HandShakeError { kind }
@@ -121,6 +359,10 @@ mod hand_shake_error {
         UnsupportedProtocolVersion(Code),
         UnsupportedClientRequest(Code),
         PayloadError(PayloadError),
+        InvalidMessageLength { len: i32, max: usize },
+        MissingUser,
+        InvalidProxySignature,
+        UnsupportedProxyCommand(u8),
     }
 
     #[cfg(test)]
@@ -151,16 +393,51 @@ mod hand_shake_error {
                 "Unsupported Client Code Request: 'High bytes 0x1234 Low bytes: 0x5678'"
             );
         }
+
+        #[test]
+        fn invalid_message_length() {
+            assert_eq!(
+                HandShakeError::from(HandShakeErrorKind::InvalidMessageLength { len: 3, max: 10_000 }).to_string(),
+                "invalid startup packet length 3, expected from 4 to 10000"
+            );
+        }
+
+        #[test]
+        fn missing_user() {
+            assert_eq!(
+                HandShakeError::from(HandShakeErrorKind::MissingUser).to_string(),
+                "no PostgreSQL user name specified in startup packet"
+            );
+        }
+
+        #[test]
+        fn invalid_proxy_signature() {
+            assert_eq!(
+                HandShakeError::from(HandShakeErrorKind::InvalidProxySignature).to_string(),
+                "invalid PROXY protocol v2 signature"
+            );
+        }
+
+        #[test]
+        fn unsupported_proxy_command() {
+            assert_eq!(
+                HandShakeError::from(HandShakeErrorKind::UnsupportedProxyCommand(0x11)).to_string(),
+                "unsupported PROXY protocol version and command 0x11"
+            );
+        }
     }
 }
 
 mod message_format_error {
     use crate::errors::PayloadError;
     use pg_wire_payload::{NotSupportedOid, UnrecognizedFormat};
-    use std::fmt::{self, Display, Formatter};
+    use std::{
+        error::Error,
+        fmt::{self, Display, Formatter},
+    };
 
     /// An error which can be returned when decoding
-    /// [FrontendMessage](crate::messages::FrontendMessage)s from raw bytes
+    /// [CommandMessage](crate::CommandMessage)s from raw bytes
     #[derive(Debug, PartialEq)]
     pub struct MessageFormatError {
         kind: MessageFormatErrorKind,
@@ -196,6 +473,56 @@ mod message_format_error {
         }
     }
 
+    impl MessageFormatError {
+        /// Returns [PayloadError] if the message payload could not be read
+        pub fn payload_error(&self) -> Option<&PayloadError> {
+            match &self.kind {
+                MessageFormatErrorKind::PayloadError(error) => Some(error),
+                _ => None,
+            }
+        }
+
+        /// Type of the error to distinguish why the message could not be decoded
+        pub fn kind(&self) -> MessageFormatErrorType {
+            match self.kind {
+                MessageFormatErrorKind::MissingMessageTag => MessageFormatErrorType::MissingMessageTag,
+                MessageFormatErrorKind::PayloadError(_) => MessageFormatErrorType::PayloadError,
+                MessageFormatErrorKind::InvalidTypeByte(_) => MessageFormatErrorType::InvalidTypeByte,
+                MessageFormatErrorKind::UnsupportedFrontendMessage(_) => {
+                    MessageFormatErrorType::UnsupportedFrontendMessage
+                }
+                MessageFormatErrorKind::NotSupportedOid(_) => MessageFormatErrorType::NotSupportedOid,
+                MessageFormatErrorKind::UnrecognizedFormat(_) => MessageFormatErrorType::UnrecognizedFormat,
+                MessageFormatErrorKind::InvalidMessageLength { .. } => MessageFormatErrorType::InvalidMessageLength,
+                MessageFormatErrorKind::InvalidSaslResponseLength(_) => {
+                    MessageFormatErrorType::InvalidSaslResponseLength
+                }
+            }
+        }
+    }
+
+    /// Type of [MessageFormatError]
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    #[non_exhaustive]
+    pub enum MessageFormatErrorType {
+        /// Message does not start with a tag
+        MissingMessageTag,
+        /// Message payload is malformed, see [MessageFormatError::payload_error]
+        PayloadError,
+        /// `Describe` or `Close` message targets neither a statement nor a portal
+        InvalidTypeByte,
+        /// Message tag is not a known frontend message
+        UnsupportedFrontendMessage,
+        /// Parameter type OID is not supported
+        NotSupportedOid,
+        /// Format code is neither text nor binary
+        UnrecognizedFormat,
+        /// Message length is negative, less than 4 or above the maximum
+        InvalidMessageLength,
+        /// `SASLInitialResponse` length is negative and not `-1`
+        InvalidSaslResponseLength,
+    }
+
     impl<'e> Display for MessageFormatError {
         fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
             match &self.kind {
@@ -209,6 +536,23 @@ mod message_format_error {
                 MessageFormatErrorKind::NotSupportedOid(error) => write!(f, "{}", error),
                 MessageFormatErrorKind::UnrecognizedFormat(error) => write!(f, "{}", error),
                 MessageFormatErrorKind::MissingMessageTag => write!(f, "Message tag is not found in payload"),
+                MessageFormatErrorKind::InvalidMessageLength { len, max } => {
+                    write!(f, "invalid message length {}, expected from 4 to {}", len, max)
+                }
+                MessageFormatErrorKind::InvalidSaslResponseLength(len) => {
+                    write!(f, "invalid SASL initial response length {}", len)
+                }
+            }
+        }
+    }
+
+    impl Error for MessageFormatError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match &self.kind {
+                MessageFormatErrorKind::PayloadError(error) => Some(error),
+                MessageFormatErrorKind::NotSupportedOid(error) => Some(error),
+                MessageFormatErrorKind::UnrecognizedFormat(error) => Some(error),
+                _ => None,
             }
         }
     }
@@ -221,6 +565,8 @@ mod message_format_error {
         UnsupportedFrontendMessage(char),
         NotSupportedOid(NotSupportedOid),
         UnrecognizedFormat(UnrecognizedFormat),
+        InvalidMessageLength { len: i32, max: usize },
+        InvalidSaslResponseLength(i32),
     }
 
     #[cfg(test)]
@@ -270,6 +616,22 @@ mod message_format_error {
             );
         }
 
+        #[test]
+        fn invalid_message_length() {
+            assert_eq!(
+                MessageFormatError::from(MessageFormatErrorKind::InvalidMessageLength { len: 0, max: 100 }).to_string(),
+                "invalid message length 0, expected from 4 to 100"
+            );
+        }
+
+        #[test]
+        fn invalid_sasl_response_length() {
+            assert_eq!(
+                MessageFormatError::from(MessageFormatErrorKind::InvalidSaslResponseLength(-2)).to_string(),
+                "invalid SASL initial response length -2"
+            );
+        }
+
         #[test]
         fn invalid_type_byte() {
             assert_eq!(
@@ -278,10 +640,47 @@ mod message_format_error {
             );
         }
     }
+
+    #[cfg(test)]
+    mod kind {
+        use super::*;
+        use crate::errors::PayloadErrorKind;
+        use pg_wire_payload::{PgFormat, PgType};
+        use std::convert::TryFrom;
+
+        #[test]
+        fn matching_on_kind() {
+            let errors = [
+                MessageFormatError::from(MessageFormatErrorKind::MissingMessageTag),
+                MessageFormatError::from(PayloadError::from(PayloadErrorKind::EndOfBuffer)),
+                MessageFormatError::from(MessageFormatErrorKind::InvalidTypeByte('U')),
+                MessageFormatError::from(MessageFormatErrorKind::UnsupportedFrontendMessage('t')),
+                MessageFormatError::from(PgType::from_oid(100).unwrap_err()),
+                MessageFormatError::from(PgFormat::try_from(5).unwrap_err()),
+                MessageFormatError::from(MessageFormatErrorKind::InvalidMessageLength { len: 0, max: 100 }),
+                MessageFormatError::from(MessageFormatErrorKind::InvalidSaslResponseLength(-2)),
+            ];
+
+            assert_eq!(
+                errors.iter().map(MessageFormatError::kind).collect::<Vec<_>>(),
+                vec![
+                    MessageFormatErrorType::MissingMessageTag,
+                    MessageFormatErrorType::PayloadError,
+                    MessageFormatErrorType::InvalidTypeByte,
+                    MessageFormatErrorType::UnsupportedFrontendMessage,
+                    MessageFormatErrorType::NotSupportedOid,
+                    MessageFormatErrorType::UnrecognizedFormat,
+                    MessageFormatErrorType::InvalidMessageLength,
+                    MessageFormatErrorType::InvalidSaslResponseLength,
+                ]
+            );
+        }
+    }
 }
 
 mod payload_error {
     use std::{
+        error::Error,
         fmt::{self, Display, Formatter},
         str::Utf8Error,
     };
@@ -298,6 +697,32 @@ mod payload_error {
         }
     }
 
+    impl PayloadError {
+        /// Type of the error to distinguish which part of the payload is malformed
+        pub fn kind(&self) -> PayloadErrorType {
+            match self.kind {
+                PayloadErrorKind::InvalidUtfString { .. } => PayloadErrorType::InvalidUtfString,
+                PayloadErrorKind::CStringNotTerminated { .. } => PayloadErrorType::CStringNotTerminated,
+                PayloadErrorKind::EndOfBuffer => PayloadErrorType::EndOfBuffer,
+                PayloadErrorKind::NotEnoughBytes { .. } => PayloadErrorType::NotEnoughBytes,
+            }
+        }
+    }
+
+    /// Type of [PayloadError]
+    #[derive(Debug, PartialEq, Clone, Copy)]
+    #[non_exhaustive]
+    pub enum PayloadErrorType {
+        /// String is not encoded in UTF-8
+        InvalidUtfString,
+        /// String is not terminated with `\0` byte
+        CStringNotTerminated,
+        /// Payload ended while a byte was expected
+        EndOfBuffer,
+        /// Payload has fewer bytes than a value requires
+        NotEnoughBytes,
+    }
+
     impl<'e> Display for PayloadError {
         fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
             match &self.kind {
@@ -325,12 +750,21 @@ mod payload_error {
         }
     }
 
+    impl Error for PayloadError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match &self.kind {
+                PayloadErrorKind::InvalidUtfString { cause, .. } => Some(cause),
+                _ => None,
+            }
+        }
+    }
+
     #[derive(Debug, PartialEq)]
     pub(crate) enum PayloadErrorKind {
         InvalidUtfString { cause: Utf8Error, source: Vec<u8> },
         CStringNotTerminated { source: Vec<u8> },
         EndOfBuffer,
-        NotEnoughBytes { required: u8, source: Vec<u8> },
+        NotEnoughBytes { required: usize, source: Vec<u8> },
     }
 
     #[cfg(test)]
@@ -380,5 +814,49 @@ mod payload_error {
                 "Buffer does not contain required number of bytes. Bytes required 4, buffer content [0, 123]"
             );
         }
+
+        #[test]
+        fn not_enough_bytes_more_than_u8_max() {
+            assert_eq!(
+                PayloadError::from(PayloadErrorKind::NotEnoughBytes {
+                    required: 300,
+                    source: vec![1, 2],
+                })
+                .to_string(),
+                "Buffer does not contain required number of bytes. Bytes required 300, buffer content [1, 2]"
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod kind {
+        use super::*;
+
+        #[test]
+        fn matching_on_kind() {
+            let invalid_utf = vec![0x96];
+            let errors = [
+                PayloadError::from(PayloadErrorKind::InvalidUtfString {
+                    cause: std::str::from_utf8(&invalid_utf).unwrap_err(),
+                    source: invalid_utf.clone(),
+                }),
+                PayloadError::from(PayloadErrorKind::CStringNotTerminated { source: vec![] }),
+                PayloadError::from(PayloadErrorKind::EndOfBuffer),
+                PayloadError::from(PayloadErrorKind::NotEnoughBytes {
+                    required: 4,
+                    source: vec![],
+                }),
+            ];
+
+            assert_eq!(
+                errors.iter().map(PayloadError::kind).collect::<Vec<_>>(),
+                vec![
+                    PayloadErrorType::InvalidUtfString,
+                    PayloadErrorType::CStringNotTerminated,
+                    PayloadErrorType::EndOfBuffer,
+                    PayloadErrorType::NotEnoughBytes,
+                ]
+            );
+        }
     }
 }
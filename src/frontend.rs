@@ -13,6 +13,10 @@
 #![allow(unused_attributes)]
 #![rustfmt::skip]
 
+use crate::{
+    message_decoder::{MessageDecoder, MessageDecoderMode},
+    Error,
+};
 use pg_wire_payload::{PgFormat, PgType};
 
 /// Frontend message that could be received during client server communication
@@ -114,4 +118,331 @@ pub enum CommandMessage {
 
     /// Terminate a connection.
     Terminate,
+
+    /// Finish sending data to the server.
+    ///
+    /// This message is part of the `COPY ... FROM STDIN` flow.
+    CopyDone,
+
+    /// Abort sending data to the server.
+    ///
+    /// This message is part of the `COPY ... FROM STDIN` flow.
+    CopyFail {
+        /// The reason of the failure.
+        message: String,
+    },
+
+    /// Call a function via the legacy fast-path interface.
+    FunctionCall {
+        /// The object ID of the function to call.
+        object_id: u32,
+        /// The formats used to encode the arguments.
+        arg_formats: Vec<PgFormat>,
+        /// The value of each argument.
+        args: Vec<Option<Vec<u8>>>,
+        /// The desired format for the function result.
+        result_format: PgFormat,
+    },
+
+    /// Select a SASL authentication mechanism and send its initial response.
+    ///
+    /// This message is part of the SASL authentication flow.
+    SaslInitialResponse {
+        /// The name of the SASL authentication mechanism that client selected.
+        mechanism: String,
+        /// SASL mechanism specific "Initial Client Response".
+        data: Vec<u8>,
+    },
+
+    /// Continue SASL authentication exchange.
+    ///
+    /// This message is part of the SASL authentication flow.
+    SaslResponse {
+        /// SASL mechanism specific message data.
+        data: Vec<u8>,
+    },
+
+    /// Continue GSSAPI or SSPI authentication exchange.
+    ///
+    /// This message is part of the GSSAPI authentication flow.
+    GssResponse {
+        /// GSSAPI or SSPI authentication data.
+        data: Vec<u8>,
+    },
+}
+
+/// Frontend message that borrows its string and binary fields from the buffer
+/// it was decoded from. It avoids allocation per field when a message has to be
+/// inspected only transiently, e.g. by a proxy. See [CommandMessage] for
+/// description of each message.
+#[derive(Debug, PartialEq, Clone)]
+pub enum CommandMessageRef<'a> {
+    /// see [CommandMessage::Query]
+    Query {
+        /// The SQL to execute.
+        sql: &'a str,
+    },
+    /// see [CommandMessage::Parse]
+    Parse {
+        /// The name of the prepared statement to create.
+        statement_name: &'a str,
+        /// The SQL to parse.
+        sql: &'a str,
+        /// The specified parameter data types.
+        param_types: Vec<Option<PgType>>,
+    },
+    /// see [CommandMessage::DescribeStatement]
+    DescribeStatement {
+        /// The name of the prepared statement to describe.
+        name: &'a str,
+    },
+    /// see [CommandMessage::DescribePortal]
+    DescribePortal {
+        /// The name of the portal to describe.
+        name: &'a str,
+    },
+    /// see [CommandMessage::Bind]
+    Bind {
+        /// The destination portal.
+        portal_name: &'a str,
+        /// The source prepared statement.
+        statement_name: &'a str,
+        /// The formats used to encode the parameters.
+        param_formats: Vec<PgFormat>,
+        /// The value of each parameter.
+        raw_params: Vec<Option<&'a [u8]>>,
+        /// The desired formats for the columns in the result set.
+        result_formats: Vec<PgFormat>,
+    },
+    /// see [CommandMessage::Execute]
+    Execute {
+        /// The name of the portal to execute.
+        portal_name: &'a str,
+        /// The maximum number of rows to return before suspending.
+        max_rows: i32,
+    },
+    /// see [CommandMessage::Flush]
+    Flush,
+    /// see [CommandMessage::Sync]
+    Sync,
+    /// see [CommandMessage::CloseStatement]
+    CloseStatement {
+        /// The name of the prepared statement to close.
+        name: &'a str,
+    },
+    /// see [CommandMessage::ClosePortal]
+    ClosePortal {
+        /// The name of the portal to close.
+        name: &'a str,
+    },
+    /// see [CommandMessage::Terminate]
+    Terminate,
+    /// see [CommandMessage::CopyDone]
+    CopyDone,
+    /// see [CommandMessage::CopyFail]
+    CopyFail {
+        /// The reason of the failure.
+        message: &'a str,
+    },
+    /// see [CommandMessage::FunctionCall]
+    FunctionCall {
+        /// The object ID of the function to call.
+        object_id: u32,
+        /// The formats used to encode the arguments.
+        arg_formats: Vec<PgFormat>,
+        /// The value of each argument.
+        args: Vec<Option<&'a [u8]>>,
+        /// The desired format for the function result.
+        result_format: PgFormat,
+    },
+    /// see [CommandMessage::SaslInitialResponse]
+    SaslInitialResponse {
+        /// The name of the SASL authentication mechanism that client selected.
+        mechanism: &'a str,
+        /// SASL mechanism specific "Initial Client Response".
+        data: &'a [u8],
+    },
+    /// see [CommandMessage::SaslResponse]
+    SaslResponse {
+        /// SASL mechanism specific message data.
+        data: &'a [u8],
+    },
+    /// see [CommandMessage::GssResponse]
+    GssResponse {
+        /// GSSAPI or SSPI authentication data.
+        data: &'a [u8],
+    },
+}
+
+impl<'a> CommandMessageRef<'a> {
+    /// Decodes message `payload`, that follows message `tag` and length, without
+    /// copying its string and binary fields
+    pub fn decode(tag: u8, mode: MessageDecoderMode, payload: &'a [u8]) -> Result<CommandMessageRef<'a>, Error> {
+        Ok(MessageDecoder::decode_ref(tag, mode, payload)?)
+    }
+
+    /// Copies borrowed fields into an owned [CommandMessage]
+    pub fn to_owned(&self) -> CommandMessage {
+        fn values(values: &[Option<&[u8]>]) -> Vec<Option<Vec<u8>>> {
+            values.iter().map(|value| value.map(<[u8]>::to_vec)).collect()
+        }
+
+        match self {
+            CommandMessageRef::Query { sql } => CommandMessage::Query { sql: (*sql).to_owned() },
+            CommandMessageRef::Parse {
+                statement_name,
+                sql,
+                param_types,
+            } => CommandMessage::Parse {
+                statement_name: (*statement_name).to_owned(),
+                sql: (*sql).to_owned(),
+                param_types: param_types.clone(),
+            },
+            CommandMessageRef::DescribeStatement { name } => CommandMessage::DescribeStatement {
+                name: (*name).to_owned(),
+            },
+            CommandMessageRef::DescribePortal { name } => CommandMessage::DescribePortal {
+                name: (*name).to_owned(),
+            },
+            CommandMessageRef::Bind {
+                portal_name,
+                statement_name,
+                param_formats,
+                raw_params,
+                result_formats,
+            } => CommandMessage::Bind {
+                portal_name: (*portal_name).to_owned(),
+                statement_name: (*statement_name).to_owned(),
+                param_formats: param_formats.clone(),
+                raw_params: values(raw_params),
+                result_formats: result_formats.clone(),
+            },
+            CommandMessageRef::Execute { portal_name, max_rows } => CommandMessage::Execute {
+                portal_name: (*portal_name).to_owned(),
+                max_rows: *max_rows,
+            },
+            CommandMessageRef::Flush => CommandMessage::Flush,
+            CommandMessageRef::Sync => CommandMessage::Sync,
+            CommandMessageRef::CloseStatement { name } => CommandMessage::CloseStatement {
+                name: (*name).to_owned(),
+            },
+            CommandMessageRef::ClosePortal { name } => CommandMessage::ClosePortal {
+                name: (*name).to_owned(),
+            },
+            CommandMessageRef::Terminate => CommandMessage::Terminate,
+            CommandMessageRef::CopyDone => CommandMessage::CopyDone,
+            CommandMessageRef::CopyFail { message } => CommandMessage::CopyFail {
+                message: (*message).to_owned(),
+            },
+            CommandMessageRef::FunctionCall {
+                object_id,
+                arg_formats,
+                args,
+                result_format,
+            } => CommandMessage::FunctionCall {
+                object_id: *object_id,
+                arg_formats: arg_formats.clone(),
+                args: values(args),
+                result_format: *result_format,
+            },
+            CommandMessageRef::SaslInitialResponse { mechanism, data } => CommandMessage::SaslInitialResponse {
+                mechanism: (*mechanism).to_owned(),
+                data: data.to_vec(),
+            },
+            CommandMessageRef::SaslResponse { data } => CommandMessage::SaslResponse { data: data.to_vec() },
+            CommandMessageRef::GssResponse { data } => CommandMessage::GssResponse { data: data.to_vec() },
+        }
+    }
+}
+
+impl CommandMessage {
+    /// Returns the maximum number of rows that `Execute` command asks to return,
+    /// `None` means that there is no limit. It is always `None` for other commands.
+    pub fn row_limit(&self) -> Option<u32> {
+        match self {
+            CommandMessage::Execute { max_rows, .. } if *max_rows > 0 => Some(*max_rows as u32),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn execute(max_rows: i32) -> CommandMessage {
+        CommandMessage::Execute {
+            portal_name: "".to_owned(),
+            max_rows,
+        }
+    }
+
+    #[test]
+    fn zero_max_rows_is_no_limit() {
+        assert_eq!(execute(0).row_limit(), None);
+    }
+
+    #[test]
+    fn negative_max_rows_is_no_limit() {
+        assert_eq!(execute(-1).row_limit(), None);
+    }
+
+    #[test]
+    fn positive_max_rows() {
+        assert_eq!(execute(100).row_limit(), Some(100));
+    }
+
+    #[test]
+    fn not_execute_command() {
+        assert_eq!(CommandMessage::Sync.row_limit(), None);
+    }
+
+    #[cfg(test)]
+    mod borrowed {
+        use super::*;
+
+        #[test]
+        fn borrows_from_payload() {
+            let payload = b"select 1\0".to_vec();
+            let message = CommandMessageRef::decode(b'Q', MessageDecoderMode::Command, &payload).expect("decoded");
+
+            match message {
+                CommandMessageRef::Query { sql } => {
+                    assert_eq!(sql, "select 1");
+                    assert!(payload.as_ptr_range().contains(&sql.as_ptr()));
+                }
+                other => panic!("unexpected message {:?}", other),
+            }
+        }
+
+        #[test]
+        fn borrows_bind_params_from_payload() {
+            let mut payload = b"portal\0statement\0".to_vec();
+            payload.extend_from_slice(&0i16.to_be_bytes());
+            payload.extend_from_slice(&2i16.to_be_bytes());
+            payload.extend_from_slice(&1i32.to_be_bytes());
+            payload.push(b'1');
+            payload.extend_from_slice(&(-1i32).to_be_bytes());
+            payload.extend_from_slice(&0i16.to_be_bytes());
+            let message = CommandMessageRef::decode(b'B', MessageDecoderMode::Command, &payload).expect("decoded");
+
+            match &message {
+                CommandMessageRef::Bind { raw_params, .. } => {
+                    assert_eq!(raw_params, &vec![Some(&b"1"[..]), None]);
+                    assert!(payload.as_ptr_range().contains(&raw_params[0].unwrap().as_ptr()));
+                }
+                other => panic!("unexpected message {:?}", other),
+            }
+            assert_eq!(
+                message.to_owned(),
+                CommandMessage::Bind {
+                    portal_name: "portal".to_owned(),
+                    statement_name: "statement".to_owned(),
+                    param_formats: vec![],
+                    raw_params: vec![Some(b"1".to_vec()), None],
+                    result_formats: vec![],
+                }
+            );
+        }
+    }
 }
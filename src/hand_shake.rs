@@ -16,12 +16,23 @@ use crate::{
     cursor::Cursor,
     errors::{HandShakeError, HandShakeErrorKind},
     request_codes::{Code, CANCEL_REQUEST_CODE, SSL_REQUEST_CODE, VERSION_1_CODE, VERSION_2_CODE, VERSION_3_CODE},
+    Error,
 };
 use pg_wire_payload::{ConnId, ConnSecretKey};
+use std::convert::TryFrom;
+
+/// Prefix of startup packet keys that negotiate protocol extensions instead
+/// of setting run-time parameters
+pub(crate) const PROTOCOL_OPTION_PREFIX: &str = "_pq_.";
 
 pub use Process as HandShakeProcess;
 pub use Status as HandShakeStatus;
 
+/// Maximum length of a startup packet that PostgreSQL accepts
+pub(crate) const MAX_STARTUP_PACKET_LEN: usize = 10_000;
+/// Newest minor version of protocol 3 that the server speaks
+pub(crate) const NEWEST_MINOR_VERSION: i32 = 0;
+
 #[derive(Debug, PartialEq, Clone)]
 pub(crate) enum State {
     MessageLen,
@@ -32,34 +43,35 @@ pub(crate) enum State {
 ///
 /// # Examples
 ///
-/// ```ignore
-/// use pg_wire::{HandShakeProcess, HandShakeStatus, HandShakeRequest};
+/// ```
+/// use pg_wire::{HandShakeProcess, HandShakeStatus};
+/// use std::io::Read;
 ///
-/// let mut stream = accept_tcp_connection();
+/// // startup message of the client that connects as `postgres` user
+/// let mut stream: &[u8] = b"\0\0\0\x17\0\x03\0\0user\0postgres\0\0";
 /// let mut process = HandShakeProcess::start();
 /// let mut buffer: Option<Vec<u8>> = None;
 /// loop {
-///     match process.next_stage(buffer.as_deref()) {
+///     match process.next_stage(buffer.take().as_deref()) {
 ///         Ok(HandShakeStatus::RequestingBytes(len)) => {
 ///             let mut buf = vec![b'0'; len];
-///             buffer = Some(stream.read(&mut buf)?);
+///             stream.read_exact(&mut buf).expect("to read bytes");
+///             buffer = Some(buf);
 ///         }
-///         Ok(HandShakeStatus::UpdatingToSecureWithReadingBytes(len)) => {
-///             stream.write_all(&[b'S']); // accepting tls connection from client
-///             stream = tls_stream(stream);
-///             let mut buf = vec![b'0'; len];
-///             buffer = Some(stream.read_exact(&mut buf)?);
+///         Ok(HandShakeStatus::UpdatingToSecure) => {
+///             // accept tls connection from client by sending `b'S'`
+///             // and continue hand shake over the secure stream
 ///         }
 ///         Ok(HandShakeStatus::Cancel(conn_id, secret_key)) => {
-///             handle_request_cancellation(conn_id, secret_key);
+///             println!("cancel queries of {} connection with {} key", conn_id, secret_key);
 ///             break;
 ///         }
 ///         Ok(HandShakeStatus::Done(props)) => {
-///             handle_authentication_and_other_stuff();
+///             assert_eq!(props, vec![("user".to_owned(), "postgres".to_owned())]);
 ///             break;
 ///         }
 ///         Err(protocol_error) => {
-///             handle_protocol_error(protocol_error);
+///             eprintln!("{}", protocol_error);
 ///             break;
 ///         }
 ///     }
@@ -67,12 +79,31 @@ pub(crate) enum State {
 /// ```
 pub struct Process {
     state: Option<State>,
+    max_message_len: usize,
+    requested_minor_version: i32,
 }
 
 impl Process {
     /// Creates new process to make client <-> server hand shake
     pub fn start() -> Process {
-        Process { state: None }
+        Process::with_max_message_len(MAX_STARTUP_PACKET_LEN)
+    }
+
+    /// Creates new process to make client <-> server hand shake that rejects
+    /// startup packets longer than `max_message_len` bytes
+    pub fn with_max_message_len(max_message_len: usize) -> Process {
+        Process {
+            state: None,
+            max_message_len,
+            requested_minor_version: NEWEST_MINOR_VERSION,
+        }
+    }
+
+    /// Minor version of protocol 3 that the client requested in its startup
+    /// packet. Clients that request a version newer than `3.0` proceed with
+    /// `3.0` and have to be told so with `NegotiateProtocolVersion` message
+    pub fn requested_minor_version(&self) -> i32 {
+        self.requested_minor_version
     }
 
     /// Proceed to the next stage of client <-> server hand shake
@@ -86,6 +117,12 @@ impl Process {
                 State::MessageLen => {
                     let mut buffer = Cursor::from(bytes);
                     let len = buffer.read_i32()?;
+                    if len < 4 || len as usize > self.max_message_len {
+                        return Err(HandShakeError::from(HandShakeErrorKind::InvalidMessageLength {
+                            len,
+                            max: self.max_message_len,
+                        }));
+                    }
                     self.state = Some(State::ParseSetup);
                     Ok(Status::RequestingBytes((len - 4) as usize))
                 }
@@ -96,7 +133,8 @@ impl Process {
                         VERSION_1_CODE | VERSION_2_CODE => Err(HandShakeError::from(
                             HandShakeErrorKind::UnsupportedProtocolVersion(code),
                         )),
-                        VERSION_3_CODE => {
+                        version if version.major_version() == VERSION_3_CODE.major_version() => {
+                            self.requested_minor_version = version.minor_version();
                             let mut props = vec![];
                             loop {
                                 let key = buffer.read_cstr()?.to_owned();
@@ -140,6 +178,92 @@ pub enum Status {
     Cancel(ConnId, ConnSecretKey),
 }
 
+/// Typed view of client runtime settings sent in the startup packet
+///
+/// # Examples
+///
+/// ```
+/// use pg_wire::StartupParameters;
+/// use std::convert::TryFrom;
+///
+/// let props = vec![
+///     ("user".to_owned(), "postgres".to_owned()),
+///     ("database".to_owned(), "postgres".to_owned()),
+/// ];
+/// let params = StartupParameters::try_from(props).expect("user is specified");
+/// assert_eq!(params.user, "postgres");
+/// assert_eq!(params.database.as_deref(), Some("postgres"));
+///
+/// let missing_user: Result<StartupParameters, pg_wire::Error> = StartupParameters::try_from(vec![]);
+/// assert!(missing_user.is_err());
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct StartupParameters {
+    /// Database user name to connect as
+    pub user: String,
+    /// Database to connect to, `None` if the client did not send it, in that
+    /// case PostgreSQL connects to the database named after the user
+    pub database: Option<String>,
+    /// Application name reported by the client
+    pub application_name: Option<String>,
+    /// Client-side character set encoding
+    pub client_encoding: Option<String>,
+    /// Command-line arguments for the backend
+    pub options: Option<String>,
+    /// Rest of run-time parameters in the order client sent them
+    pub extra: Vec<(String, String)>,
+    /// `_pq_.` prefixed protocol options in the order client sent them
+    pub protocol_options: Vec<(String, String)>,
+}
+
+impl TryFrom<Vec<(String, String)>> for StartupParameters {
+    type Error = Error;
+
+    fn try_from(props: Vec<(String, String)>) -> Result<StartupParameters, Error> {
+        let mut user = None;
+        let mut database = None;
+        let mut application_name = None;
+        let mut client_encoding = None;
+        let mut options = None;
+        let mut extra = vec![];
+        let mut protocol_options = vec![];
+        for (key, value) in props {
+            match key.as_str() {
+                "user" => user = Some(value),
+                "database" => database = Some(value),
+                "application_name" => application_name = Some(value),
+                "client_encoding" => client_encoding = Some(value),
+                "options" => options = Some(value),
+                _ if key.starts_with(PROTOCOL_OPTION_PREFIX) => protocol_options.push((key, value)),
+                _ => extra.push((key, value)),
+            }
+        }
+        match user {
+            Some(user) => Ok(StartupParameters {
+                user,
+                database,
+                application_name,
+                client_encoding,
+                options,
+                extra,
+                protocol_options,
+            }),
+            None => Err(Error::from(HandShakeError::from(HandShakeErrorKind::MissingUser))),
+        }
+    }
+}
+
+/// Returns `_pq_.` prefixed protocol options of the startup packet that are
+/// reported back to the client with `NegotiateProtocolVersion`. None of protocol
+/// extensions is supported, so all of them are unrecognized
+pub(crate) fn unrecognized_protocol_options(props: &[(String, String)]) -> Vec<String> {
+    props
+        .iter()
+        .filter(|(key, _)| key.starts_with(PROTOCOL_OPTION_PREFIX))
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
 #[cfg(test)]
 mod perform_hand_shake_loop {
     use super::*;
@@ -162,6 +286,62 @@ mod perform_hand_shake_loop {
         );
     }
 
+    #[test]
+    fn zero_setup_message_length() {
+        let mut process = Process::start();
+
+        process.next_stage(None).expect("proceed to the next stage");
+        assert_eq!(
+            process.next_stage(Some(&0i32.to_be_bytes())),
+            Err(HandShakeError::from(HandShakeErrorKind::InvalidMessageLength {
+                len: 0,
+                max: MAX_STARTUP_PACKET_LEN
+            }))
+        );
+    }
+
+    #[test]
+    fn setup_message_length_less_than_length_itself() {
+        let mut process = Process::start();
+
+        process.next_stage(None).expect("proceed to the next stage");
+        assert_eq!(
+            process.next_stage(Some(&3i32.to_be_bytes())),
+            Err(HandShakeError::from(HandShakeErrorKind::InvalidMessageLength {
+                len: 3,
+                max: MAX_STARTUP_PACKET_LEN
+            }))
+        );
+    }
+
+    #[test]
+    fn negative_setup_message_length() {
+        let mut process = Process::start();
+
+        process.next_stage(None).expect("proceed to the next stage");
+        assert_eq!(
+            process.next_stage(Some(&i32::MIN.to_be_bytes())),
+            Err(HandShakeError::from(HandShakeErrorKind::InvalidMessageLength {
+                len: i32::MIN,
+                max: MAX_STARTUP_PACKET_LEN
+            }))
+        );
+    }
+
+    #[test]
+    fn setup_message_length_exceeds_maximum() {
+        let mut process = Process::with_max_message_len(32);
+
+        process.next_stage(None).expect("proceed to the next stage");
+        assert_eq!(
+            process.next_stage(Some(&[0, 0, 0, 33])),
+            Err(HandShakeError::from(HandShakeErrorKind::InvalidMessageLength {
+                len: 33,
+                max: 32
+            }))
+        );
+    }
+
     #[test]
     fn non_secure_connection_hand_shake() {
         let mut process = Process::start();
@@ -238,6 +418,28 @@ mod perform_hand_shake_loop {
         );
     }
 
+    #[test]
+    fn newer_minor_version_proceeds_with_startup() {
+        let mut process = Process::start();
+
+        process.next_stage(None).expect("proceed to the next stage");
+        process
+            .next_stage(Some(&[0, 0, 0, 19]))
+            .expect("proceed to the next stage");
+
+        let mut payload = vec![];
+        payload.extend_from_slice(&[0, 3, 0, 1]);
+        payload.extend_from_slice(b"key1\0");
+        payload.extend_from_slice(b"value1\0");
+        payload.extend_from_slice(&[0]);
+
+        assert_eq!(
+            process.next_stage(Some(&payload)),
+            Ok(Status::Done(vec![("key1".to_owned(), "value1".to_owned())]))
+        );
+        assert_eq!(process.requested_minor_version(), 1);
+    }
+
     #[test]
     fn ssl_secure_connection_hand_shake() {
         let mut process = Process::start();
@@ -296,3 +498,90 @@ mod perform_hand_shake_loop {
         );
     }
 }
+
+#[cfg(test)]
+mod startup_parameters {
+    use super::*;
+
+    fn props(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn all_parameters() {
+        assert_eq!(
+            StartupParameters::try_from(props(&[
+                ("user", "postgres"),
+                ("database", "test_db"),
+                ("application_name", "psql"),
+                ("client_encoding", "UTF8"),
+                ("options", "-c geqo=off"),
+                ("DateStyle", "ISO"),
+                ("TimeZone", "UTC"),
+            ])),
+            Ok(StartupParameters {
+                user: "postgres".to_owned(),
+                database: Some("test_db".to_owned()),
+                application_name: Some("psql".to_owned()),
+                client_encoding: Some("UTF8".to_owned()),
+                options: Some("-c geqo=off".to_owned()),
+                extra: props(&[("DateStyle", "ISO"), ("TimeZone", "UTC")]),
+                protocol_options: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn only_user() {
+        assert_eq!(
+            StartupParameters::try_from(props(&[("user", "postgres")])),
+            Ok(StartupParameters {
+                user: "postgres".to_owned(),
+                database: None,
+                application_name: None,
+                client_encoding: None,
+                options: None,
+                extra: vec![],
+                protocol_options: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn protocol_options_are_separated_from_parameters() {
+        let startup_props = props(&[
+            ("user", "postgres"),
+            ("_pq_.foo", "on"),
+            ("DateStyle", "ISO"),
+            ("_pq_.bar", "1"),
+        ]);
+
+        assert_eq!(
+            unrecognized_protocol_options(&startup_props),
+            vec!["_pq_.foo".to_owned(), "_pq_.bar".to_owned()]
+        );
+        assert_eq!(
+            StartupParameters::try_from(startup_props),
+            Ok(StartupParameters {
+                user: "postgres".to_owned(),
+                database: None,
+                application_name: None,
+                client_encoding: None,
+                options: None,
+                extra: props(&[("DateStyle", "ISO")]),
+                protocol_options: props(&[("_pq_.foo", "on"), ("_pq_.bar", "1")]),
+            })
+        );
+    }
+
+    #[test]
+    fn missing_user() {
+        assert_eq!(
+            StartupParameters::try_from(props(&[("database", "test_db")])),
+            Err(Error::from(HandShakeError::from(HandShakeErrorKind::MissingUser)))
+        );
+    }
+}
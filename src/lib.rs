@@ -21,21 +21,72 @@ compile_error!("feature \"async_io\" and feature \"tokio_net\" cannot be enabled
 #[cfg(not(any(feature = "async_io", feature = "tokio_net", feature = "mock_net")))]
 compile_error!("at least one of the features: \"async_io\", \"tokio_net\" or \"mock_net\" should be enabled");
 
+pub use client_params::{ClientParams, ClientParamsExt};
 pub use connection::{
-    listener::PgWireListener, ClientRequest, ConnSupervisor, Connection, ProtocolConfiguration, ResponseSender, Sender,
+    listener::PgWireListener, AuthMethod, ClientRequest, ConnSupervisor, Connection, ConnectionReader,
+    ConnectionSettings, ConnectionWriter, ProtocolConfiguration, ResponseSender, Sender, TlsIdentity,
 };
-pub use errors::Error;
-pub use frontend::CommandMessage;
+pub use errors::{Error, MessageFormatError, MessageFormatErrorType, PayloadError, PayloadErrorType};
+pub use frontend::{CommandMessage, CommandMessageRef};
+pub use hand_shake::{HandShakeProcess, HandShakeStatus, StartupParameters};
+pub use message_decoder::{MessageDecoder, MessageDecoderMode, MessageDecoderStatus, MessageStream};
 
 pub use pg_wire_payload::*;
 
+mod client_params;
 mod connection;
 mod cursor;
 mod errors;
 mod frontend;
 mod hand_shake;
+/// Server side of `md5` password authentication
+pub mod md5;
 mod message_decoder;
+/// Commonly used types to glob import them at once
+///
+/// ```
+/// use pg_wire::prelude::*;
+/// use std::io;
+///
+/// fn respond<S: Sender>(sender: &S, command: CommandMessage) -> io::Result<()> {
+///     match command {
+///         CommandMessage::Query { .. } => {
+///             let value = PgType::Integer.encode_text(&Value::Int32(1)).expect("integer value");
+///             sender.send(BackendMessage::RowDescription(vec![ColumnMetadata::with_format(
+///                 "col1",
+///                 PgType::Integer,
+///                 PgFormat::Text,
+///             )]))?;
+///             sender.send(BackendMessage::DataRow(vec![String::from_utf8(value).expect("utf-8")]))?;
+///             sender.send(CommandComplete::select(1))
+///         }
+///         _ => sender.send(BackendMessage::ErrorResponse(ErrorFields::new(
+///             "ERROR",
+///             "0A000",
+///             "only simple query is supported",
+///         ))),
+///     }
+/// }
+///
+/// async fn serve(listener: PgWireListener) -> io::Result<()> {
+///     if let Ok(ClientRequest::Connect((channel, props, supervisor, address, settings))) = listener.accept().await? {
+///         let (conn_id, secret_key) = supervisor.alloc().expect("free connection id");
+///         let channel = std::sync::Arc::new(async_mutex::Mutex::new(channel));
+///         let mut connection = Connection::new(conn_id, secret_key, props, address, channel, supervisor, settings);
+///         if let Ok(command) = connection.receive().await? {
+///             respond(&connection.sender(), command)?;
+///         }
+///     }
+///     Ok(())
+/// }
+///
+/// let _config = ProtocolConfiguration::not_secure();
+/// let _supervisor = ConnSupervisor::new(0, 10);
+/// ```
+pub mod prelude;
+mod proxy_protocol;
 mod request_codes;
-
-/// Connection key-value params
-pub type ClientParams = Vec<(String, String)>;
+/// Server side of `SCRAM-SHA-256` authentication
+/// see [RFC 5802](https://tools.ietf.org/html/rfc5802) and [RFC 7677](https://tools.ietf.org/html/rfc7677)
+#[cfg(feature = "scram")]
+pub mod scram;
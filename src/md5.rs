@@ -0,0 +1,157 @@
+// Copyright 2020 - 2021 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+const PREFIX: &str = "md5";
+const HASH_HEX_LEN: usize = 32;
+
+/// Verifies `client_response` of `PasswordMessage` sent in reply to
+/// `AuthenticationMD5Password` with `salt`. The client computes it as
+/// `concat('md5', md5(concat(md5(concat(password, username)), salt)))`.
+///
+/// `stored_or_plain` is either a password in plain text or a hash of it as
+/// PostgreSQL stores it, i.e. `concat('md5', md5(concat(password, username)))`
+pub fn verify_md5_password(stored_or_plain: &str, username: &str, salt: [u8; 4], client_response: &[u8]) -> bool {
+    let stored = match stored_or_plain.strip_prefix(PREFIX) {
+        Some(hash) if hash.len() == HASH_HEX_LEN && hash.bytes().all(|b| b.is_ascii_hexdigit()) => hash.to_lowercase(),
+        _ => hex(&digest(&[stored_or_plain.as_bytes(), username.as_bytes()].concat())),
+    };
+    let expected = format!("{}{}", PREFIX, hex(&digest(&[stored.as_bytes(), &salt].concat())));
+    let client_response = client_response.strip_suffix(&[0]).unwrap_or(client_response);
+
+    expected.len() == client_response.len()
+        && expected
+            .bytes()
+            .zip(client_response.iter())
+            .fold(0, |acc, (left, right)| acc | (left ^ right))
+            == 0
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// MD5 message digest
+/// see [RFC 1321](https://tools.ietf.org/html/rfc1321)
+fn digest(message: &[u8]) -> [u8; 16] {
+    const SHIFTS: [u32; 64] = [
+        7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14,
+        20, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6,
+        10, 15, 21,
+    ];
+
+    let constants = (0..64)
+        .map(|i| ((i as f64 + 1.0).sin().abs() * 4_294_967_296.0) as u32)
+        .collect::<Vec<u32>>();
+
+    let mut padded = message.to_vec();
+    padded.push(0x80);
+    while padded.len() % 64 != 56 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&((message.len() as u64).wrapping_mul(8)).to_le_bytes());
+
+    let mut state: [u32; 4] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476];
+    for chunk in padded.chunks(64) {
+        let words = chunk
+            .chunks(4)
+            .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+            .collect::<Vec<u32>>();
+        let [mut a, mut b, mut c, mut d] = state;
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let rotated = a
+                .wrapping_add(f)
+                .wrapping_add(constants[i])
+                .wrapping_add(words[g])
+                .rotate_left(SHIFTS[i]);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(rotated);
+        }
+        state[0] = state[0].wrapping_add(a);
+        state[1] = state[1].wrapping_add(b);
+        state[2] = state[2].wrapping_add(c);
+        state[3] = state[3].wrapping_add(d);
+    }
+
+    let mut result = [0; 16];
+    for (bytes, word) in result.chunks_mut(4).zip(state.iter()) {
+        bytes.copy_from_slice(&word.to_le_bytes());
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SALT: [u8; 4] = [1, 2, 3, 4];
+    const CLIENT_RESPONSE: &[u8] = b"md598511ceaec347a656f032c7f2a16ef17\0";
+
+    #[test]
+    fn md5_digest() {
+        assert_eq!(hex(&digest(b"")), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(
+            hex(&digest(b"The quick brown fox jumps over the lazy dog")),
+            "9e107d9d372bb6826bd81d3542a419d6"
+        );
+        assert_eq!(hex(&digest(&[b'a'; 100])), "36a92cc94a9e0fa21f625f8bfb007adf");
+    }
+
+    #[test]
+    fn plain_password() {
+        assert!(verify_md5_password("password", "postgres", SALT, CLIENT_RESPONSE));
+    }
+
+    #[test]
+    fn stored_password() {
+        assert!(verify_md5_password(
+            "md532e12f215ba27cb750c9e093ce4b5127",
+            "postgres",
+            SALT,
+            CLIENT_RESPONSE
+        ));
+    }
+
+    #[test]
+    fn wrong_password() {
+        assert!(!verify_md5_password(
+            "not a password",
+            "postgres",
+            SALT,
+            CLIENT_RESPONSE
+        ));
+    }
+
+    #[test]
+    fn wrong_salt() {
+        assert!(!verify_md5_password(
+            "password",
+            "postgres",
+            [4, 3, 2, 1],
+            CLIENT_RESPONSE
+        ));
+    }
+
+    #[test]
+    fn wrong_username() {
+        assert!(!verify_md5_password("password", "admin", SALT, CLIENT_RESPONSE));
+    }
+}
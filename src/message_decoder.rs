@@ -22,22 +22,31 @@
 use crate::{
     cursor::Cursor,
     errors::{MessageFormatError, MessageFormatErrorKind},
-    frontend::CommandMessage,
+    frontend::{CommandMessage, CommandMessageRef},
+    Error,
 };
 use pg_wire_payload::{PgFormat, PgType};
 use std::convert::TryFrom;
+pub use Mode as MessageDecoderMode;
 pub use Status as MessageDecoderStatus;
 
 const QUERY: u8 = b'Q';
 const BIND: u8 = b'B';
 const CLOSE: u8 = b'C';
+const COPY_DONE: u8 = b'c';
+const COPY_FAIL: u8 = b'f';
 const DESCRIBE: u8 = b'D';
 const EXECUTE: u8 = b'E';
 const FLUSH: u8 = b'H';
+const FUNCTION_CALL: u8 = b'F';
 const PARSE: u8 = b'P';
+const PASSWORD_MESSAGE: u8 = b'p';
 const SYNC: u8 = b'S';
 const TERMINATE: u8 = b'X';
 
+/// Maximum length of a frontend message that PostgreSQL accepts
+pub(crate) const MAX_MESSAGE_LEN: usize = 0x3fff_ffff;
+
 /// Represents a status of a `MessageDecoder` stage
 #[derive(Debug, PartialEq)]
 pub enum Status {
@@ -47,6 +56,20 @@ pub enum Status {
     Done(CommandMessage),
 }
 
+/// Represents how `MessageDecoder` interprets `PasswordMessage` family of
+/// messages which share the same tag
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Mode {
+    /// Client is not in the middle of SASL authentication
+    Command,
+    /// Server waits for `SASLInitialResponse` message
+    SaslInitialResponse,
+    /// Server waits for `SASLResponse` message
+    SaslResponse,
+    /// Server waits for `GSSResponse` message during GSSAPI authentication
+    GssResponse,
+}
+
 #[derive(Debug, PartialEq)]
 pub(crate) enum State {
     RequestingTag,
@@ -58,31 +81,62 @@ pub(crate) enum State {
 ///
 /// # Examples
 ///
-/// ```ignore
-/// use pg_wire::{MessageDecoder, MessageDecoderStatus};
+/// ```
+/// use pg_wire::{CommandMessage, MessageDecoder, MessageDecoderStatus};
+/// use std::io::Read;
 ///
-/// let mut message_decoder = MessageDecoder::new();
+/// // `Query` message that client sent over its own transport
+/// let mut channel: &[u8] = b"Q\0\0\0\x0eselect 1;\0";
+/// let mut message_decoder = MessageDecoder::default();
 /// let mut current: Option<Vec<u8>> = None;
 /// loop {
-///     log::debug!("Read bytes from connection {:?}", current);
 ///     match message_decoder.next_stage(current.take().as_deref()) {
 ///         Ok(MessageDecoderStatus::Requesting(len)) => {
 ///             let mut buffer = vec![b'0'; len];
-///             channel.read_exact(&mut buffer)?;
+///             channel.read_exact(&mut buffer).expect("to read bytes");
 ///             current = Some(buffer);
 ///         }
-///         Ok(MessageDecoderStatus::Done(message)) => return Ok(Ok(message)),
-///         Err(error) => return Err(error),
+///         Ok(MessageDecoderStatus::Done(message)) => {
+///             assert_eq!(message, CommandMessage::Query { sql: "select 1;".to_owned() });
+///             break;
+///         }
+///         Err(error) => {
+///             eprintln!("{}", error);
+///             break;
+///         }
 ///     }
 /// }
 /// ```
-#[derive(Default)]
 pub struct MessageDecoder {
     state: Option<State>,
     tag: u8,
+    max_message_len: usize,
+    mode: Mode,
+}
+
+impl Default for MessageDecoder {
+    fn default() -> MessageDecoder {
+        MessageDecoder::with_max_message_len(MAX_MESSAGE_LEN)
+    }
 }
 
 impl MessageDecoder {
+    /// Creates decoder that rejects messages longer than `max_message_len` bytes
+    pub fn with_max_message_len(max_message_len: usize) -> MessageDecoder {
+        MessageDecoder {
+            state: None,
+            tag: 0,
+            max_message_len,
+            mode: Mode::Command,
+        }
+    }
+
+    /// Sets how the following `PasswordMessage` family of messages are decoded.
+    /// It is up to the server to switch the mode according to the authentication phase
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
     /// Proceed to the next stage of decoding received message
     pub fn next_stage(&mut self, payload: Option<&[u8]>) -> Result<Status, MessageFormatError> {
         let buf = if let Some(payload) = payload { payload } else { &[] };
@@ -101,29 +155,40 @@ impl MessageDecoder {
             }
             Some(State::Tag(tag)) => {
                 self.tag = tag;
+                let len = Cursor::from(buf).read_i32()?;
+                if len < 4 || len as usize > self.max_message_len {
+                    return Err(MessageFormatError::from(MessageFormatErrorKind::InvalidMessageLength {
+                        len,
+                        max: self.max_message_len,
+                    }));
+                }
                 self.state = Some(State::WaitingForPayload);
-                Ok(Status::Requesting((Cursor::from(buf).read_i32()? - 4) as usize))
+                Ok(Status::Requesting((len - 4) as usize))
             }
             Some(State::WaitingForPayload) => {
-                let message = Self::decode(self.tag, buf)?;
+                let message = Self::decode(self.tag, self.mode, buf)?;
                 Ok(Status::Done(message))
             }
         }
     }
 
-    fn decode(tag: u8, buffer: &[u8]) -> Result<CommandMessage, MessageFormatError> {
+    fn decode(tag: u8, mode: Mode, buffer: &[u8]) -> Result<CommandMessage, MessageFormatError> {
+        Self::decode_ref(tag, mode, buffer).map(|message| message.to_owned())
+    }
+
+    pub(crate) fn decode_ref(tag: u8, mode: Mode, buffer: &[u8]) -> Result<CommandMessageRef<'_>, MessageFormatError> {
         let mut cursor = Cursor::from(buffer);
         match tag {
             // Simple query flow.
             QUERY => {
-                let sql = cursor.read_cstr()?.to_owned();
-                Ok(CommandMessage::Query { sql })
+                let sql = cursor.read_cstr()?;
+                Ok(CommandMessageRef::Query { sql })
             }
 
             // Extended query flow.
             BIND => {
-                let portal_name = cursor.read_cstr()?.to_owned();
-                let statement_name = cursor.read_cstr()?.to_owned();
+                let portal_name = cursor.read_cstr()?;
+                let statement_name = cursor.read_cstr()?;
 
                 let mut param_formats = vec![];
                 for _ in 0..cursor.read_i16()? {
@@ -137,11 +202,7 @@ impl MessageDecoder {
                         // As a special case, -1 indicates a NULL parameter value.
                         raw_params.push(None);
                     } else {
-                        let mut value = vec![];
-                        for _ in 0..len {
-                            value.push(cursor.read_byte()?);
-                        }
-                        raw_params.push(Some(value));
+                        raw_params.push(Some(cursor.read_bytes(len as usize)?));
                     }
                 }
 
@@ -150,7 +211,7 @@ impl MessageDecoder {
                     result_formats.push(PgFormat::try_from(cursor.read_i16()?)?)
                 }
 
-                Ok(CommandMessage::Bind {
+                Ok(CommandMessageRef::Bind {
                     portal_name,
                     statement_name,
                     param_formats,
@@ -160,10 +221,10 @@ impl MessageDecoder {
             }
             CLOSE => {
                 let first_char = cursor.read_byte()?;
-                let name = cursor.read_cstr()?.to_owned();
+                let name = cursor.read_cstr()?;
                 match first_char {
-                    b'P' => Ok(CommandMessage::ClosePortal { name }),
-                    b'S' => Ok(CommandMessage::CloseStatement { name }),
+                    b'P' => Ok(CommandMessageRef::ClosePortal { name }),
+                    b'S' => Ok(CommandMessageRef::CloseStatement { name }),
                     other => Err(MessageFormatError::from(MessageFormatErrorKind::InvalidTypeByte(
                         char::from(other),
                     ))),
@@ -171,24 +232,24 @@ impl MessageDecoder {
             }
             DESCRIBE => {
                 let first_char = cursor.read_byte()?;
-                let name = cursor.read_cstr()?.to_owned();
+                let name = cursor.read_cstr()?;
                 match first_char {
-                    b'P' => Ok(CommandMessage::DescribePortal { name }),
-                    b'S' => Ok(CommandMessage::DescribeStatement { name }),
+                    b'P' => Ok(CommandMessageRef::DescribePortal { name }),
+                    b'S' => Ok(CommandMessageRef::DescribeStatement { name }),
                     other => Err(MessageFormatError::from(MessageFormatErrorKind::InvalidTypeByte(
                         char::from(other),
                     ))),
                 }
             }
             EXECUTE => {
-                let portal_name = cursor.read_cstr()?.to_owned();
+                let portal_name = cursor.read_cstr()?;
                 let max_rows = cursor.read_i32()?;
-                Ok(CommandMessage::Execute { portal_name, max_rows })
+                Ok(CommandMessageRef::Execute { portal_name, max_rows })
             }
-            FLUSH => Ok(CommandMessage::Flush),
+            FLUSH => Ok(CommandMessageRef::Flush),
             PARSE => {
-                let statement_name = cursor.read_cstr()?.to_owned();
-                let sql = cursor.read_cstr()?.to_owned();
+                let statement_name = cursor.read_cstr()?;
+                let sql = cursor.read_cstr()?;
 
                 let mut param_types = vec![];
                 for _ in 0..cursor.read_i16()? {
@@ -196,15 +257,77 @@ impl MessageDecoder {
                     param_types.push(pg_type);
                 }
 
-                Ok(CommandMessage::Parse {
+                Ok(CommandMessageRef::Parse {
                     statement_name,
                     sql,
                     param_types,
                 })
             }
-            SYNC => Ok(CommandMessage::Sync),
+            SYNC => Ok(CommandMessageRef::Sync),
+
+            TERMINATE => Ok(CommandMessageRef::Terminate),
+
+            // Copy flow.
+            COPY_DONE => Ok(CommandMessageRef::CopyDone),
+            COPY_FAIL => {
+                let message = cursor.read_cstr()?;
+                Ok(CommandMessageRef::CopyFail { message })
+            }
+
+            // Function call flow.
+            FUNCTION_CALL => {
+                let object_id = cursor.read_u32()?;
+
+                let mut arg_formats = vec![];
+                for _ in 0..cursor.read_i16()? {
+                    arg_formats.push(PgFormat::try_from(cursor.read_i16()?)?)
+                }
+
+                let mut args = vec![];
+                for _ in 0..cursor.read_i16()? {
+                    let len = cursor.read_i32()?;
+                    if len == -1 {
+                        // As a special case, -1 indicates a NULL argument value.
+                        args.push(None);
+                    } else {
+                        args.push(Some(cursor.read_bytes(len as usize)?));
+                    }
+                }
+
+                let result_format = PgFormat::try_from(cursor.read_i16()?)?;
+
+                Ok(CommandMessageRef::FunctionCall {
+                    object_id,
+                    arg_formats,
+                    args,
+                    result_format,
+                })
+            }
+
+            // SASL authentication flow.
+            PASSWORD_MESSAGE if mode == Mode::SaslInitialResponse => {
+                let mechanism = cursor.read_cstr()?;
+                let len = cursor.read_i32()?;
+                let data = match len {
+                    // As a special case, -1 indicates that there is no initial response.
+                    -1 => &[][..],
+                    len if len < 0 => {
+                        return Err(MessageFormatError::from(
+                            MessageFormatErrorKind::InvalidSaslResponseLength(len),
+                        ))
+                    }
+                    len => cursor.read_bytes(len as usize)?,
+                };
+                Ok(CommandMessageRef::SaslInitialResponse { mechanism, data })
+            }
+            PASSWORD_MESSAGE if mode == Mode::SaslResponse => Ok(CommandMessageRef::SaslResponse {
+                data: buffer,
+            }),
 
-            TERMINATE => Ok(CommandMessage::Terminate),
+            // GSSAPI authentication flow.
+            PASSWORD_MESSAGE if mode == Mode::GssResponse => Ok(CommandMessageRef::GssResponse {
+                data: buffer,
+            }),
 
             _ => Err(MessageFormatError::from(
                 MessageFormatErrorKind::UnsupportedFrontendMessage(char::from(tag)),
@@ -213,6 +336,90 @@ impl MessageDecoder {
     }
 }
 
+/// Decodes messages that client sent back-to-back, e.g. `Parse`, `Bind`,
+/// `Describe`, `Execute` and `Sync` of the extended query flow, from a single
+/// buffer. Iteration stops on a partially received trailing message, its bytes
+/// are left after [MessageStream::consumed] to be decoded once the rest arrives
+///
+/// # Examples
+///
+/// ```
+/// use pg_wire::{CommandMessage, MessageStream};
+///
+/// let mut stream = MessageStream::new(&[b'S', 0, 0, 0, 4, b'H', 0, 0]);
+/// assert_eq!(stream.next().transpose(), Ok(Some(CommandMessage::Sync)));
+/// assert_eq!(stream.next(), None);
+/// assert_eq!(stream.consumed(), 5);
+/// ```
+pub struct MessageStream<'b> {
+    buf: &'b [u8],
+    consumed: usize,
+    mode: Mode,
+    max_message_len: usize,
+    failed: bool,
+}
+
+impl<'b> MessageStream<'b> {
+    /// Creates stream of messages contained in `buf`
+    pub fn new(buf: &'b [u8]) -> MessageStream<'b> {
+        MessageStream {
+            buf,
+            consumed: 0,
+            mode: Mode::Command,
+            max_message_len: MAX_MESSAGE_LEN,
+            failed: false,
+        }
+    }
+
+    /// Sets how the following `PasswordMessage` family of messages are decoded,
+    /// see [MessageDecoder::set_mode]
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    /// Number of bytes of completely received messages that were decoded so far
+    pub fn consumed(&self) -> usize {
+        self.consumed
+    }
+
+    /// Number of bytes after [MessageStream::consumed], i.e. of a partially
+    /// received trailing message once iteration stops. Zero means that the
+    /// buffer ends exactly at a message boundary
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.consumed
+    }
+}
+
+impl<'b> Iterator for MessageStream<'b> {
+    type Item = Result<CommandMessage, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.buf[self.consumed..];
+        let mut cursor = Cursor::from(rest);
+        if self.failed || cursor.remaining() < 5 {
+            return None;
+        }
+        let tag = cursor.read_byte().ok()?;
+        // the length includes itself, the message is complete once `len`
+        // bytes are available starting from it
+        let len = cursor.peek_i32().ok()?;
+        if len < 4 || len as usize > self.max_message_len {
+            // message boundaries are unknown after invalid length
+            self.failed = true;
+            return Some(Err(Error::from(MessageFormatError::from(MessageFormatErrorKind::InvalidMessageLength {
+                len,
+                max: self.max_message_len,
+            }))));
+        }
+        let len = len as usize;
+        if cursor.remaining() < len {
+            return None;
+        }
+        self.consumed += len + 1;
+        Some(MessageDecoder::decode(tag, self.mode, &rest[5..len + 1]).map_err(Error::from))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -243,6 +450,21 @@ mod tests {
             );
         }
 
+        #[test]
+        fn not_terminated_query_payload_error() {
+            let mut decoder = MessageDecoder::default();
+
+            decoder.next_stage(None).expect("proceed to the next stage");
+            decoder.next_stage(Some(&[QUERY])).expect("proceed to the next stage");
+            decoder.next_stage(Some(&8i32.to_be_bytes())).expect("proceed to the next stage");
+            let error = decoder.next_stage(Some(b"sql")).expect_err("query is not terminated");
+
+            assert_eq!(
+                error.payload_error().map(crate::PayloadError::kind),
+                Some(crate::PayloadErrorType::CStringNotTerminated)
+            );
+        }
+
         #[test]
         fn request_message_len() {
             let mut decoder = MessageDecoder::default();
@@ -263,6 +485,66 @@ mod tests {
             );
         }
 
+        #[test]
+        fn zero_message_len() {
+            let mut decoder = MessageDecoder::default();
+
+            decoder.next_stage(None).expect("proceed to the next stage");
+            decoder.next_stage(Some(&[QUERY])).expect("proceed to the next stage");
+            assert_eq!(
+                decoder.next_stage(Some(&0i32.to_be_bytes())),
+                Err(MessageFormatError::from(MessageFormatErrorKind::InvalidMessageLength {
+                    len: 0,
+                    max: MAX_MESSAGE_LEN
+                }))
+            );
+        }
+
+        #[test]
+        fn message_len_less_than_len_itself() {
+            let mut decoder = MessageDecoder::default();
+
+            decoder.next_stage(None).expect("proceed to the next stage");
+            decoder.next_stage(Some(&[QUERY])).expect("proceed to the next stage");
+            assert_eq!(
+                decoder.next_stage(Some(&3i32.to_be_bytes())),
+                Err(MessageFormatError::from(MessageFormatErrorKind::InvalidMessageLength {
+                    len: 3,
+                    max: MAX_MESSAGE_LEN
+                }))
+            );
+        }
+
+        #[test]
+        fn negative_message_len() {
+            let mut decoder = MessageDecoder::default();
+
+            decoder.next_stage(None).expect("proceed to the next stage");
+            decoder.next_stage(Some(&[QUERY])).expect("proceed to the next stage");
+            assert_eq!(
+                decoder.next_stage(Some(&i32::MIN.to_be_bytes())),
+                Err(MessageFormatError::from(MessageFormatErrorKind::InvalidMessageLength {
+                    len: i32::MIN,
+                    max: MAX_MESSAGE_LEN
+                }))
+            );
+        }
+
+        #[test]
+        fn message_len_exceeds_maximum() {
+            let mut decoder = MessageDecoder::with_max_message_len(LEN as usize - 1);
+
+            decoder.next_stage(None).expect("proceed to the next stage");
+            decoder.next_stage(Some(&[QUERY])).expect("proceed to the next stage");
+            assert_eq!(
+                decoder.next_stage(Some(&LEN.to_be_bytes())),
+                Err(MessageFormatError::from(MessageFormatErrorKind::InvalidMessageLength {
+                    len: LEN,
+                    max: LEN as usize - 1
+                }))
+            );
+        }
+
         #[test]
         fn decoding_message() {
             let mut decoder = MessageDecoder::default();
@@ -547,6 +829,61 @@ mod tests {
             );
         }
 
+        #[test]
+        fn copy_done() {
+            let buffer = [];
+            let mut decoder = MessageDecoder::default();
+
+            decoder.next_stage(None).expect("proceed to the next stage");
+            decoder.next_stage(Some(&[COPY_DONE])).expect("proceed to the next stage");
+            decoder
+                .next_stage(Some(&4i32.to_be_bytes()))
+                .expect("proceed to the next stage");
+
+            assert_eq!(
+                decoder.next_stage(Some(&buffer)),
+                Ok(Status::Done(CommandMessage::CopyDone))
+            );
+        }
+
+        #[test]
+        fn copy_fail() {
+            let buffer = b"missing data\0";
+            let mut decoder = MessageDecoder::default();
+
+            decoder.next_stage(None).expect("proceed to the next stage");
+            decoder.next_stage(Some(&[COPY_FAIL])).expect("proceed to the next stage");
+            decoder
+                .next_stage(Some(&17i32.to_be_bytes()))
+                .expect("proceed to the next stage");
+
+            assert_eq!(
+                decoder.next_stage(Some(buffer)),
+                Ok(Status::Done(CommandMessage::CopyFail {
+                    message: "missing data".to_owned()
+                }))
+            );
+        }
+
+        #[test]
+        fn copy_fail_with_empty_message() {
+            let buffer = [0];
+            let mut decoder = MessageDecoder::default();
+
+            decoder.next_stage(None).expect("proceed to the next stage");
+            decoder.next_stage(Some(&[COPY_FAIL])).expect("proceed to the next stage");
+            decoder
+                .next_stage(Some(&5i32.to_be_bytes()))
+                .expect("proceed to the next stage");
+
+            assert_eq!(
+                decoder.next_stage(Some(&buffer)),
+                Ok(Status::Done(CommandMessage::CopyFail {
+                    message: "".to_owned()
+                }))
+            );
+        }
+
         #[test]
         fn terminate() {
             let buffer = [];
@@ -584,5 +921,279 @@ mod tests {
                 ))
             );
         }
+
+        #[test]
+        fn function_call() {
+            let buffer = [
+                0, 0, 5, 57, 0, 1, 0, 1, 0, 2, 0, 0, 0, 4, 0, 0, 0, 42, 255, 255, 255, 255, 0, 0,
+            ];
+            let mut decoder = MessageDecoder::default();
+
+            decoder.next_stage(None).expect("proceed to the next stage");
+            decoder
+                .next_stage(Some(&[FUNCTION_CALL]))
+                .expect("proceed to the next stage");
+            decoder
+                .next_stage(Some(&LEN.to_be_bytes()))
+                .expect("proceed to the next stage");
+
+            assert_eq!(
+                decoder.next_stage(Some(&buffer)),
+                Ok(Status::Done(CommandMessage::FunctionCall {
+                    object_id: 1337,
+                    arg_formats: vec![PgFormat::Binary],
+                    args: vec![Some(vec![0, 0, 0, 42]), None],
+                    result_format: PgFormat::Text,
+                }))
+            );
+        }
+
+        #[test]
+        fn sasl_initial_response() {
+            let buffer = [
+                83, 67, 82, 65, 77, 45, 83, 72, 65, 45, 50, 53, 54, 0, 0, 0, 0, 8, 110, 44, 44, 110, 61, 44, 114, 61,
+            ];
+            let mut decoder = MessageDecoder::default();
+            decoder.set_mode(Mode::SaslInitialResponse);
+
+            decoder.next_stage(None).expect("proceed to the next stage");
+            decoder
+                .next_stage(Some(&[PASSWORD_MESSAGE]))
+                .expect("proceed to the next stage");
+            decoder
+                .next_stage(Some(&LEN.to_be_bytes()))
+                .expect("proceed to the next stage");
+
+            assert_eq!(
+                decoder.next_stage(Some(&buffer)),
+                Ok(Status::Done(CommandMessage::SaslInitialResponse {
+                    mechanism: "SCRAM-SHA-256".to_owned(),
+                    data: b"n,,n=,r=".to_vec(),
+                }))
+            );
+        }
+
+        #[test]
+        fn sasl_initial_response_without_data() {
+            let buffer = [83, 67, 82, 65, 77, 45, 83, 72, 65, 45, 50, 53, 54, 0, 255, 255, 255, 255];
+            let mut decoder = MessageDecoder::default();
+            decoder.set_mode(Mode::SaslInitialResponse);
+
+            decoder.next_stage(None).expect("proceed to the next stage");
+            decoder
+                .next_stage(Some(&[PASSWORD_MESSAGE]))
+                .expect("proceed to the next stage");
+            decoder
+                .next_stage(Some(&LEN.to_be_bytes()))
+                .expect("proceed to the next stage");
+
+            assert_eq!(
+                decoder.next_stage(Some(&buffer)),
+                Ok(Status::Done(CommandMessage::SaslInitialResponse {
+                    mechanism: "SCRAM-SHA-256".to_owned(),
+                    data: vec![],
+                }))
+            );
+        }
+
+        #[test]
+        fn sasl_initial_response_with_negative_length() {
+            let buffer = [83, 67, 82, 65, 77, 45, 83, 72, 65, 45, 50, 53, 54, 0, 255, 255, 255, 254];
+            let mut decoder = MessageDecoder::default();
+            decoder.set_mode(Mode::SaslInitialResponse);
+
+            decoder.next_stage(None).expect("proceed to the next stage");
+            decoder
+                .next_stage(Some(&[PASSWORD_MESSAGE]))
+                .expect("proceed to the next stage");
+            decoder
+                .next_stage(Some(&LEN.to_be_bytes()))
+                .expect("proceed to the next stage");
+
+            assert_eq!(
+                decoder.next_stage(Some(&buffer)),
+                Err(MessageFormatError::from(MessageFormatErrorKind::InvalidSaslResponseLength(-2)))
+            );
+        }
+
+        #[test]
+        fn sasl_response() {
+            let buffer = [99, 61, 98, 105, 119, 115, 44, 114, 61];
+            let mut decoder = MessageDecoder::default();
+            decoder.set_mode(Mode::SaslResponse);
+
+            decoder.next_stage(None).expect("proceed to the next stage");
+            decoder
+                .next_stage(Some(&[PASSWORD_MESSAGE]))
+                .expect("proceed to the next stage");
+            decoder
+                .next_stage(Some(&LEN.to_be_bytes()))
+                .expect("proceed to the next stage");
+
+            assert_eq!(
+                decoder.next_stage(Some(&buffer)),
+                Ok(Status::Done(CommandMessage::SaslResponse {
+                    data: b"c=biws,r=".to_vec(),
+                }))
+            );
+        }
+
+        #[test]
+        fn gss_response() {
+            let buffer = [0x60, 0x82, 0x02, 0x6b, 0x06, 0x09, 0x2a, 0x86, 0x48];
+            let mut decoder = MessageDecoder::default();
+            decoder.set_mode(Mode::GssResponse);
+
+            decoder.next_stage(None).expect("proceed to the next stage");
+            decoder
+                .next_stage(Some(&[PASSWORD_MESSAGE]))
+                .expect("proceed to the next stage");
+            decoder
+                .next_stage(Some(&13i32.to_be_bytes()))
+                .expect("proceed to the next stage");
+
+            assert_eq!(
+                decoder.next_stage(Some(&buffer)),
+                Ok(Status::Done(CommandMessage::GssResponse { data: buffer.to_vec() }))
+            );
+        }
+
+        #[test]
+        fn password_message_outside_of_sasl_flow() {
+            let buffer = [];
+            let mut decoder = MessageDecoder::default();
+
+            decoder.next_stage(None).expect("proceed to the next stage");
+            decoder
+                .next_stage(Some(&[PASSWORD_MESSAGE]))
+                .expect("proceed to the next stage");
+            decoder
+                .next_stage(Some(&LEN.to_be_bytes()))
+                .expect("proceed to the next stage");
+
+            assert_eq!(
+                decoder.next_stage(Some(&buffer)),
+                Err(MessageFormatError::from(
+                    MessageFormatErrorKind::UnsupportedFrontendMessage('p')
+                ))
+            );
+        }
+    }
+    #[cfg(test)]
+    mod message_stream {
+        use super::*;
+
+        fn message(tag: u8, payload: &[u8]) -> Vec<u8> {
+            [&[tag][..], &(payload.len() as i32 + 4).to_be_bytes(), payload].concat()
+        }
+
+        fn extended_query() -> Vec<u8> {
+            [
+                message(PARSE, b"\0select 1;\0\0\0"),
+                message(BIND, b"portal\0\0\0\0\0\0\0\0"),
+                message(DESCRIBE, b"Pportal\0"),
+                message(EXECUTE, b"portal\0\0\0\0\0"),
+                message(SYNC, b""),
+            ]
+            .concat()
+        }
+
+        #[test]
+        fn pipelined_extended_query() {
+            let buffer = extended_query();
+            let mut stream = MessageStream::new(&buffer);
+
+            assert_eq!(
+                stream.by_ref().collect::<Result<Vec<CommandMessage>, Error>>(),
+                Ok(vec![
+                    CommandMessage::Parse {
+                        statement_name: "".to_owned(),
+                        sql: "select 1;".to_owned(),
+                        param_types: vec![],
+                    },
+                    CommandMessage::Bind {
+                        portal_name: "portal".to_owned(),
+                        statement_name: "".to_owned(),
+                        param_formats: vec![],
+                        raw_params: vec![],
+                        result_formats: vec![],
+                    },
+                    CommandMessage::DescribePortal {
+                        name: "portal".to_owned()
+                    },
+                    CommandMessage::Execute {
+                        portal_name: "portal".to_owned(),
+                        max_rows: 0,
+                    },
+                    CommandMessage::Sync,
+                ])
+            );
+            assert_eq!(stream.consumed(), buffer.len());
+        }
+
+        #[test]
+        fn partial_trailing_message() {
+            let mut buffer = message(SYNC, b"");
+            buffer.extend_from_slice(&message(QUERY, QUERY_BYTES)[..LEN as usize]);
+            let mut stream = MessageStream::new(&buffer);
+
+            assert_eq!(stream.next(), Some(Ok(CommandMessage::Sync)));
+            assert_eq!(stream.next(), None);
+            assert_eq!(stream.consumed(), 5);
+            assert_eq!(stream.remaining(), LEN as usize);
+        }
+
+        #[test]
+        fn partial_trailing_length() {
+            let buffer = [SYNC, 0, 0, 0, 4, QUERY, 0, 0];
+            let mut stream = MessageStream::new(&buffer);
+
+            assert_eq!(stream.next(), Some(Ok(CommandMessage::Sync)));
+            assert_eq!(stream.next(), None);
+            assert_eq!(stream.consumed(), 5);
+            assert_eq!(stream.remaining(), 3);
+        }
+
+        #[test]
+        fn no_trailing_bytes() {
+            let buffer = [message(SYNC, b""), message(FLUSH, b"")].concat();
+            let mut stream = MessageStream::new(&buffer);
+
+            assert_eq!(stream.by_ref().count(), 2);
+            assert_eq!(stream.remaining(), 0);
+        }
+
+        #[test]
+        fn malformed_message_does_not_stop_stream() {
+            let buffer = [message(DESCRIBE, b"Rname\0"), message(SYNC, b"")].concat();
+            let mut stream = MessageStream::new(&buffer);
+
+            assert_eq!(
+                stream.next(),
+                Some(Err(Error::from(MessageFormatError::from(
+                    MessageFormatErrorKind::InvalidTypeByte('R')
+                ))))
+            );
+            assert_eq!(stream.next(), Some(Ok(CommandMessage::Sync)));
+            assert_eq!(stream.next(), None);
+        }
+
+        #[test]
+        fn invalid_message_length_stops_stream() {
+            let buffer = [SYNC, 0, 0, 0, 3, SYNC, 0, 0, 0, 4];
+            let mut stream = MessageStream::new(&buffer);
+
+            assert_eq!(
+                stream.next(),
+                Some(Err(Error::from(MessageFormatError::from(
+                    MessageFormatErrorKind::InvalidMessageLength {
+                        len: 3,
+                        max: MAX_MESSAGE_LEN
+                    }
+                ))))
+            );
+            assert_eq!(stream.next(), None);
+            assert_eq!(stream.consumed(), 0);
+        }
     }
 }
@@ -0,0 +1,170 @@
+// Copyright 2020 - 2021 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    cursor::Cursor,
+    errors::{HandShakeError, HandShakeErrorKind},
+};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// Length of PROXY protocol v2 header up to the address block
+pub(crate) const HEADER_LEN: usize = 16;
+
+const SIGNATURE: &[u8; 12] = b"\r\n\r\n\0\r\nQUIT\n";
+const LOCAL: u8 = 0x20;
+const PROXY: u8 = 0x21;
+const TCP4: u8 = 0x11;
+const TCP6: u8 = 0x21;
+
+/// PROXY protocol v2 header that a proxy sends before any client bytes
+/// see [spec](https://www.haproxy.org/download/2.3/doc/proxy-protocol.txt)
+#[derive(Debug, PartialEq)]
+pub(crate) struct ProxyHeader {
+    command: u8,
+    family: u8,
+    len: usize,
+}
+
+impl ProxyHeader {
+    /// Parses fixed part of the header: signature, version with command,
+    /// address family and length of the address block that follows
+    pub(crate) fn parse(bytes: &[u8]) -> Result<ProxyHeader, HandShakeError> {
+        let mut buffer = Cursor::from(bytes);
+        if buffer.read_bytes(SIGNATURE.len())? != SIGNATURE {
+            return Err(HandShakeError::from(HandShakeErrorKind::InvalidProxySignature));
+        }
+        let command = buffer.read_byte()?;
+        if command != LOCAL && command != PROXY {
+            return Err(HandShakeError::from(HandShakeErrorKind::UnsupportedProxyCommand(
+                command,
+            )));
+        }
+        let family = buffer.read_byte()?;
+        let len = buffer.read_i16()? as u16 as usize;
+        Ok(ProxyHeader { command, family, len })
+    }
+
+    /// Length of the address block that follows the fixed part of the header
+    pub(crate) fn addresses_len(&self) -> usize {
+        self.len
+    }
+
+    /// Decodes the source address from the address block. Returns `None`
+    /// when the connection endpoints should be used as is, i.e. for
+    /// `LOCAL` command or address families other than TCP over IPv4 and IPv6
+    pub(crate) fn source_address(&self, addresses: &[u8]) -> Result<Option<SocketAddr>, HandShakeError> {
+        if self.command == LOCAL {
+            return Ok(None);
+        }
+        let mut buffer = Cursor::from(addresses);
+        let ip = match self.family {
+            TCP4 => {
+                let mut octets = [0; 4];
+                octets.copy_from_slice(buffer.read_bytes(4)?);
+                buffer.read_bytes(4)?;
+                IpAddr::V4(Ipv4Addr::from(octets))
+            }
+            TCP6 => {
+                let mut octets = [0; 16];
+                octets.copy_from_slice(buffer.read_bytes(16)?);
+                buffer.read_bytes(16)?;
+                IpAddr::V6(Ipv6Addr::from(octets))
+            }
+            _ => return Ok(None),
+        };
+        let port = buffer.read_i16()? as u16;
+        Ok(Some(SocketAddr::new(ip, port)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(command: u8, family: u8, len: u16) -> Vec<u8> {
+        let mut header = SIGNATURE.to_vec();
+        header.push(command);
+        header.push(family);
+        header.extend_from_slice(&len.to_be_bytes());
+        header
+    }
+
+    #[test]
+    fn tcp4_source_address() {
+        let header = ProxyHeader::parse(&header(PROXY, TCP4, 12)).expect("valid header");
+        assert_eq!(header.addresses_len(), 12);
+        assert_eq!(
+            header.source_address(&[192, 168, 0, 1, 10, 0, 0, 1, 0xdc, 0x04, 0x15, 0x38]),
+            Ok(Some("192.168.0.1:56324".parse().unwrap()))
+        );
+    }
+
+    #[test]
+    fn tcp6_source_address() {
+        let mut addresses = vec![0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+        addresses.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        addresses.extend_from_slice(&[0xdc, 0x04, 0x15, 0x38]);
+
+        let header = ProxyHeader::parse(&header(PROXY, TCP6, 36)).expect("valid header");
+        assert_eq!(
+            header.source_address(&addresses),
+            Ok(Some("[2001:db8::1]:56324".parse().unwrap()))
+        );
+    }
+
+    #[test]
+    fn tlvs_after_addresses_are_ignored() {
+        let header = ProxyHeader::parse(&header(PROXY, TCP4, 16)).expect("valid header");
+        assert_eq!(
+            header.source_address(&[127, 0, 0, 2, 127, 0, 0, 1, 0, 80, 0, 81, 0x04, 0, 1, 0]),
+            Ok(Some("127.0.0.2:80".parse().unwrap()))
+        );
+    }
+
+    #[test]
+    fn local_command() {
+        let header = ProxyHeader::parse(&header(LOCAL, 0x00, 0)).expect("valid header");
+        assert_eq!(header.source_address(&[]), Ok(None));
+    }
+
+    #[test]
+    fn unix_socket_family() {
+        let header = ProxyHeader::parse(&header(PROXY, 0x31, 216)).expect("valid header");
+        assert_eq!(header.source_address(&[0; 216]), Ok(None));
+    }
+
+    #[test]
+    fn truncated_addresses() {
+        let header = ProxyHeader::parse(&header(PROXY, TCP4, 6)).expect("valid header");
+        assert!(header.source_address(&[192, 168, 0, 1, 10, 0]).is_err());
+    }
+
+    #[test]
+    fn invalid_signature() {
+        let mut bytes = header(PROXY, TCP4, 12);
+        bytes[0] = b'P';
+        assert_eq!(
+            ProxyHeader::parse(&bytes),
+            Err(HandShakeError::from(HandShakeErrorKind::InvalidProxySignature))
+        );
+    }
+
+    #[test]
+    fn unsupported_version() {
+        assert_eq!(
+            ProxyHeader::parse(&header(0x11, TCP4, 12)),
+            Err(HandShakeError::from(HandShakeErrorKind::UnsupportedProxyCommand(0x11)))
+        );
+    }
+}
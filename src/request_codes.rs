@@ -30,18 +30,35 @@ pub(crate) const GSSENC_REQUEST_CODE: Code = Code(80_877_104);
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub struct Code(pub(crate) i32);
 
+impl Code {
+    /// Major protocol version, high 16 bits of the code
+    pub(crate) fn major_version(&self) -> i32 {
+        self.0 >> 16
+    }
+
+    /// Minor protocol version, low 16 bits of the code
+    pub(crate) fn minor_version(&self) -> i32 {
+        self.0 & 0x00_00_FF_FF
+    }
+}
+
 impl Display for Code {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             &CANCEL_REQUEST_CODE => write!(f, "Cancel Request"),
             &SSL_REQUEST_CODE => write!(f, "SSL Request"),
             &GSSENC_REQUEST_CODE => write!(f, "GSSENC Request"),
-            &VERSION_1_CODE | &VERSION_2_CODE | &VERSION_3_CODE => write!(
-                f,
-                "Version {}.{} Request",
-                (self.0 >> 16) as i16,
-                (self.0 & 0x00_00_FF_FF) as i16
-            ),
+            &VERSION_1_CODE | &VERSION_2_CODE => {
+                write!(f, "Version {}.{} Request", self.major_version(), self.minor_version())
+            }
+            version if version.major_version() == VERSION_3_CODE.major_version() => {
+                write!(
+                    f,
+                    "Version {}.{} Request",
+                    version.major_version(),
+                    version.minor_version()
+                )
+            }
             other => write!(
                 f,
                 "High bytes 0x{:x?} Low bytes: 0x{:x?}",
@@ -77,6 +94,11 @@ mod code_display_tests {
         assert_eq!(VERSION_3_CODE.to_string(), "Version 3.0 Request");
     }
 
+    #[test]
+    fn newer_minor_version_three_request() {
+        assert_eq!(Code(0x00_03_00_01).to_string(), "Version 3.1 Request");
+    }
+
     #[test]
     fn cancel_request() {
         assert_eq!(CANCEL_REQUEST_CODE.to_string(), "Cancel Request")
@@ -0,0 +1,349 @@
+// Copyright 2020 - 2021 Alex Dukhno
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use hmac::{Hmac, Mac, NewMac};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use std::{
+    fmt::{self, Display, Formatter},
+    str,
+};
+
+/// Name of the SASL mechanism implemented by [ScramServer]
+pub const SCRAM_SHA_256: &str = "SCRAM-SHA-256";
+
+const CLIENT_KEY: &[u8] = b"Client Key";
+const SERVER_KEY: &[u8] = b"Server Key";
+const NONCE_LEN: usize = 18;
+const KEY_LEN: usize = 32;
+
+/// An error which can be returned during `SCRAM-SHA-256` authentication exchange
+#[derive(Debug, PartialEq)]
+pub struct ScramError {
+    kind: ScramErrorKind,
+}
+
+impl From<ScramErrorKind> for ScramError {
+    fn from(kind: ScramErrorKind) -> ScramError {
+        ScramError { kind }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ScramErrorKind {
+    InvalidVerifier,
+    InvalidMessage(&'static str),
+    ChannelBindingNotSupported,
+    ChannelBindingMismatch,
+    NonceMismatch,
+    ProofMismatch,
+    UnexpectedMessage,
+}
+
+impl Display for ScramError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ScramErrorKind::InvalidVerifier => write!(f, "stored SCRAM-SHA-256 verifier has invalid format"),
+            ScramErrorKind::InvalidMessage(message) => write!(f, "malformed SCRAM {} message", message),
+            ScramErrorKind::ChannelBindingNotSupported => write!(f, "SCRAM channel binding is not supported"),
+            ScramErrorKind::ChannelBindingMismatch => write!(f, "SCRAM channel binding check failed"),
+            ScramErrorKind::NonceMismatch => write!(f, "SCRAM nonce does not match"),
+            ScramErrorKind::ProofMismatch => write!(f, "password authentication failed"),
+            ScramErrorKind::UnexpectedMessage => write!(f, "SCRAM message was received out of order"),
+        }
+    }
+}
+
+/// Computes `SCRAM-SHA-256$<iterations>:<salt>$<StoredKey>:<ServerKey>` verifier
+/// in the same format as PostgreSQL stores it in `pg_authid.rolpassword`
+pub fn verifier(password: &str, salt: &[u8], iterations: u32) -> String {
+    let mut salted_password = [0u8; KEY_LEN];
+    pbkdf2::pbkdf2::<Hmac<Sha256>>(password.as_bytes(), salt, iterations, &mut salted_password);
+    let client_key = hmac(&salted_password, CLIENT_KEY);
+    let stored_key = Sha256::digest(&client_key);
+    let server_key = hmac(&salted_password, SERVER_KEY);
+    format!(
+        "{}${}:{}${}:{}",
+        SCRAM_SHA_256,
+        iterations,
+        base64::encode(salt),
+        base64::encode(stored_key),
+        base64::encode(server_key)
+    )
+}
+
+#[derive(Debug)]
+enum State {
+    Initial,
+    ServerFirstSent {
+        gs2_header: String,
+        nonce: String,
+        client_first_bare: String,
+        server_first: String,
+    },
+    Finished,
+}
+
+/// Encapsulate server side of `SCRAM-SHA-256` authentication exchange
+///
+/// # Examples
+///
+/// ```ignore
+/// use pg_wire::scram::ScramServer;
+///
+/// let mut server = ScramServer::new(&stored_verifier)?;
+/// // data of SASLInitialResponse
+/// let server_first = server.handle_client_first(&client_first)?;
+/// sender.send(BackendMessage::AuthenticationSaslContinue(server_first))?;
+/// // data of SASLResponse
+/// let server_final = server.handle_client_final(&client_final)?;
+/// sender.send(BackendMessage::AuthenticationSaslFinal(server_final))?;
+/// ```
+#[derive(Debug)]
+pub struct ScramServer {
+    iterations: u32,
+    salt: String,
+    stored_key: Vec<u8>,
+    server_key: Vec<u8>,
+    server_nonce: String,
+    state: State,
+}
+
+impl ScramServer {
+    /// Creates new authentication exchange against stored `SCRAM-SHA-256$...` verifier
+    pub fn new(verifier: &str) -> Result<ScramServer, ScramError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill(&mut nonce);
+        ScramServer::with_nonce(verifier, base64::encode(nonce))
+    }
+
+    fn with_nonce(verifier: &str, server_nonce: String) -> Result<ScramServer, ScramError> {
+        let invalid = || ScramError::from(ScramErrorKind::InvalidVerifier);
+        let rest = verifier
+            .strip_prefix(SCRAM_SHA_256)
+            .and_then(|rest| rest.strip_prefix('$'))
+            .ok_or_else(invalid)?;
+        let (params, keys) = split_once(rest, '$').ok_or_else(invalid)?;
+        let (iterations, salt) = split_once(params, ':').ok_or_else(invalid)?;
+        let (stored_key, server_key) = split_once(keys, ':').ok_or_else(invalid)?;
+        let iterations = iterations.parse().map_err(|_| invalid())?;
+        base64::decode(salt).map_err(|_| invalid())?;
+        let stored_key = base64::decode(stored_key).map_err(|_| invalid())?;
+        let server_key = base64::decode(server_key).map_err(|_| invalid())?;
+        if stored_key.len() != KEY_LEN || server_key.len() != KEY_LEN {
+            return Err(invalid());
+        }
+        Ok(ScramServer {
+            iterations,
+            salt: salt.to_owned(),
+            stored_key,
+            server_key,
+            server_nonce,
+            state: State::Initial,
+        })
+    }
+
+    /// Consumes `client-first-message` and returns `server-first-message`
+    pub fn handle_client_first(&mut self, client_first: &[u8]) -> Result<Vec<u8>, ScramError> {
+        match self.state {
+            State::Initial => {}
+            _ => return Err(ScramError::from(ScramErrorKind::UnexpectedMessage)),
+        }
+        self.state = State::Finished;
+
+        let malformed = || ScramError::from(ScramErrorKind::InvalidMessage("client-first"));
+        let message = str::from_utf8(client_first).map_err(|_| malformed())?;
+        let (cbind_flag, rest) = split_once(message, ',').ok_or_else(malformed)?;
+        match cbind_flag {
+            "n" | "y" => {}
+            flag if flag.starts_with("p=") => return Err(ScramError::from(ScramErrorKind::ChannelBindingNotSupported)),
+            _ => return Err(malformed()),
+        }
+        let (_authzid, client_first_bare) = split_once(rest, ',').ok_or_else(malformed)?;
+        let gs2_header = &message[..message.len() - client_first_bare.len()];
+
+        let mut attributes = client_first_bare.split(',');
+        match attributes.next() {
+            Some(username) if username.starts_with("n=") => {}
+            _ => return Err(malformed()),
+        }
+        let client_nonce = match attributes.next().and_then(|nonce| nonce.strip_prefix("r=")) {
+            Some(nonce) if !nonce.is_empty() => nonce,
+            _ => return Err(malformed()),
+        };
+
+        let nonce = format!("{}{}", client_nonce, self.server_nonce);
+        let server_first = format!("r={},s={},i={}", nonce, self.salt, self.iterations);
+        self.state = State::ServerFirstSent {
+            gs2_header: gs2_header.to_owned(),
+            nonce,
+            client_first_bare: client_first_bare.to_owned(),
+            server_first: server_first.clone(),
+        };
+        Ok(server_first.into_bytes())
+    }
+
+    /// Consumes `client-final-message`, verifies client proof and returns `server-final-message`
+    pub fn handle_client_final(&mut self, client_final: &[u8]) -> Result<Vec<u8>, ScramError> {
+        let (gs2_header, nonce, client_first_bare, server_first) =
+            match std::mem::replace(&mut self.state, State::Finished) {
+                State::ServerFirstSent {
+                    gs2_header,
+                    nonce,
+                    client_first_bare,
+                    server_first,
+                } => (gs2_header, nonce, client_first_bare, server_first),
+                _ => return Err(ScramError::from(ScramErrorKind::UnexpectedMessage)),
+            };
+
+        let malformed = || ScramError::from(ScramErrorKind::InvalidMessage("client-final"));
+        let message = str::from_utf8(client_final).map_err(|_| malformed())?;
+        let proof_position = message.rfind(",p=").ok_or_else(malformed)?;
+        let client_final_without_proof = &message[..proof_position];
+        let proof = base64::decode(&message[proof_position + 3..]).map_err(|_| malformed())?;
+
+        let mut attributes = client_final_without_proof.split(',');
+        let channel_binding = attributes
+            .next()
+            .and_then(|binding| binding.strip_prefix("c="))
+            .ok_or_else(malformed)?;
+        if base64::decode(channel_binding).map_err(|_| malformed())? != gs2_header.as_bytes() {
+            return Err(ScramError::from(ScramErrorKind::ChannelBindingMismatch));
+        }
+        let client_nonce = attributes
+            .next()
+            .and_then(|nonce| nonce.strip_prefix("r="))
+            .ok_or_else(malformed)?;
+        if client_nonce != nonce {
+            return Err(ScramError::from(ScramErrorKind::NonceMismatch));
+        }
+
+        let auth_message = format!("{},{},{}", client_first_bare, server_first, client_final_without_proof);
+        let client_signature = hmac(&self.stored_key, auth_message.as_bytes());
+        if proof.len() != client_signature.len() {
+            return Err(ScramError::from(ScramErrorKind::ProofMismatch));
+        }
+        let client_key = proof
+            .iter()
+            .zip(client_signature.iter())
+            .map(|(p, s)| p ^ s)
+            .collect::<Vec<u8>>();
+        let stored_key = Sha256::digest(&client_key);
+        let difference = stored_key
+            .iter()
+            .zip(self.stored_key.iter())
+            .fold(0, |acc, (left, right)| acc | (left ^ right));
+        if difference != 0 {
+            return Err(ScramError::from(ScramErrorKind::ProofMismatch));
+        }
+
+        let server_signature = hmac(&self.server_key, auth_message.as_bytes());
+        Ok(format!("v={}", base64::encode(server_signature)).into_bytes())
+    }
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn split_once(s: &str, delimiter: char) -> Option<(&str, &str)> {
+    s.find(delimiter).map(|index| (&s[..index], &s[index + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // test vectors from https://tools.ietf.org/html/rfc7677#section-3
+    const SALT: &str = "W22ZaJ0SNY7soEsUEjb6gQ==";
+    const SERVER_NONCE: &str = "%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0";
+    const VERIFIER: &str = "SCRAM-SHA-256$4096:W22ZaJ0SNY7soEsUEjb6gQ==$\
+                            WG5d8oPm3OtcPnkdi4Uo7BkeZkBFzpcXkuLmtbsT4qY=:\
+                            wfPLwcE6nTWhTAmQ7tl2KeoiWGPlZqQxSrmfPwDl2dU=";
+    const CLIENT_FIRST: &[u8] = b"n,,n=user,r=rOprNGfwEbeRWgbNEkqO";
+    const SERVER_FIRST: &[u8] =
+        b"r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096";
+    const CLIENT_FINAL: &[u8] = b"c=biws,r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,\
+                                  p=dHzbZapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ=";
+    const SERVER_FINAL: &[u8] = b"v=6rriTRBi23WpRR/wtup+mMhUZUn/dB5nLTJRsjl95G4=";
+
+    #[test]
+    fn compute_verifier() {
+        assert_eq!(
+            verifier("pencil", &base64::decode(SALT).unwrap(), 4096),
+            VERIFIER.to_owned()
+        );
+    }
+
+    #[test]
+    fn invalid_verifier() {
+        assert_eq!(
+            ScramServer::new("md5a3556571e93b0d20722ba62be61e8c2d").unwrap_err(),
+            ScramError::from(ScramErrorKind::InvalidVerifier)
+        );
+    }
+
+    #[test]
+    fn successful_authentication() {
+        let mut server = ScramServer::with_nonce(VERIFIER, SERVER_NONCE.to_owned()).expect("valid verifier");
+
+        assert_eq!(server.handle_client_first(CLIENT_FIRST), Ok(SERVER_FIRST.to_vec()));
+        assert_eq!(server.handle_client_final(CLIENT_FINAL), Ok(SERVER_FINAL.to_vec()));
+    }
+
+    #[test]
+    fn wrong_password() {
+        let verifier = verifier("not a pencil", &base64::decode(SALT).unwrap(), 4096);
+        let mut server = ScramServer::with_nonce(&verifier, SERVER_NONCE.to_owned()).expect("valid verifier");
+
+        server.handle_client_first(CLIENT_FIRST).expect("server first message");
+        assert_eq!(
+            server.handle_client_final(CLIENT_FINAL),
+            Err(ScramError::from(ScramErrorKind::ProofMismatch))
+        );
+    }
+
+    #[test]
+    fn nonce_mismatch() {
+        let mut server = ScramServer::with_nonce(VERIFIER, "other nonce".to_owned()).expect("valid verifier");
+
+        server.handle_client_first(CLIENT_FIRST).expect("server first message");
+        assert_eq!(
+            server.handle_client_final(CLIENT_FINAL),
+            Err(ScramError::from(ScramErrorKind::NonceMismatch))
+        );
+    }
+
+    #[test]
+    fn channel_binding_requested() {
+        let mut server = ScramServer::new(VERIFIER).expect("valid verifier");
+
+        assert_eq!(
+            server.handle_client_first(b"p=tls-server-end-point,,n=,r=rOprNGfwEbeRWgbNEkqO"),
+            Err(ScramError::from(ScramErrorKind::ChannelBindingNotSupported))
+        );
+    }
+
+    #[test]
+    fn client_final_before_client_first() {
+        let mut server = ScramServer::new(VERIFIER).expect("valid verifier");
+
+        assert_eq!(
+            server.handle_client_final(CLIENT_FINAL),
+            Err(ScramError::from(ScramErrorKind::UnexpectedMessage))
+        );
+    }
+}